@@ -15,7 +15,7 @@
  */
 
 use errors_lib::{
-    miette::{self, Diagnostic, NamedSource, SourceSpan},
+    miette::{self, Diagnostic, LabeledSpan, NamedSource, SourceSpan},
     snafu::prelude::*,
 };
 
@@ -33,9 +33,10 @@ pub enum CliError {
         /// The source text, used by miette/Ariadne for snippet rendering.
         #[source_code]
         src: NamedSource<String>,
-        /// Points miette at the exact location of the syntax error.
-        #[label("syntax error here")]
-        span: SourceSpan,
+        /// Points miette at each location involved in the error, e.g. an
+        /// unexpected token and the brace it left unclosed.
+        #[label(collection)]
+        labels: Vec<LabeledSpan>,
     },
 
     /// Network call timed out.
@@ -56,7 +57,25 @@ pub enum CliError {
     Io { source: std::io::Error },
 }
 
+impl CliError {
+    /// A `ConfigParseError` with one label per `(span, message)` pair.
+    pub fn config_parse_with_labels(
+        path: impl Into<String>,
+        src: NamedSource<String>,
+        spans: Vec<(SourceSpan, &str)>,
+    ) -> Self {
+        Self::ConfigParseError {
+            path: path.into(),
+            src,
+            labels: spans
+                .into_iter()
+                .map(|(span, message)| LabeledSpan::new_with_span(Some(message.to_owned()), span))
+                .collect(),
+        }
+    }
+}
+
 /// Helper to wrap a `CliError` result into a `LibReport` at the boundary.
 pub fn into_lib_report(r: Result<(), CliError>) -> errors_lib::LibResult<(), CliError> {
-    r.map_err(|e| errors_lib::LibReport(errors_lib::rootcause::Report::new(e)))
+    r.map_err(|e| errors_lib::rootcause::Report::new(e).into())
 }
@@ -2,20 +2,25 @@
  * CLI Entry point.
  *
  * Sets up dual diagnostics:
- * 1. miette    — structured terminal rendering for handled errors
+ * 1. miette    — structured terminal rendering for handled errors, with a
+ *    sysexits-style exit code per error namespace
  * 2. color-eyre — beautiful panic reports for unhandled crashes
- * 3. tracing   — structured JSON logs to ./logs/api-errors.log
+ * 3. tracing   — structured JSON logs to ./logs/api-errors.log (panics
+ *    included, as ApiError events)
  */
 
 mod errors;
 
+use std::{path::PathBuf, process::ExitCode};
+
 use errors::{CliError, into_lib_report};
 use errors_lib::{
-    LibReport, LibResult, ReportExt, handle_error_logic,
+    ErrorAction, ExitCodeMap, LibReport, LibResult, LoggingBuilder, ReportExt, classify_error,
+    install_panic_to_api_error,
     miette::{self, NamedSource},
     rootcause::Report,
+    run_with_exit_codes,
 };
-use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 
 // ---------------------------------------------------------------------------
 // App logic — internal functions use Result<_, CliError> for ergonomic `?`
@@ -30,56 +35,78 @@ fn read_config_file(path: &str) -> Result<String, CliError> {
 
 /// Boundary function: wraps into `LibReport` for the framework pipeline.
 fn perform_task() -> LibResult<(), CliError> {
-    let err = CliError::ConfigParseError {
-        path: "config.json".into(),
-        src: NamedSource::new("config.json", "{ \"key\": !!invalid }".to_string()),
-        span: (10, 9).into(),
-    };
-
-    Err(LibReport(Report::new(err).attach(
-        "The application cannot proceed without a valid config.",
-    )))
+    let err = CliError::config_parse_with_labels(
+        "config.json",
+        NamedSource::new("config.json", "{ \"key\": !!invalid".to_string()),
+        vec![
+            ((0, 1).into(), "object opened here is never closed"),
+            ((9, 9).into(), "unexpected token"),
+        ],
+    );
+
+    Err(Report::new(err)
+        .attach("The application cannot proceed without a valid config.")
+        .into())
 }
 
 // ---------------------------------------------------------------------------
 // Entry point
 // ---------------------------------------------------------------------------
 
-fn main() -> miette::Result<()> {
+fn main() -> ExitCode {
     // 1. Install color-eyre for beautiful panic reports
     color_eyre::install().expect("Failed to install color-eyre");
 
-    // 2. Setup file appender for structured JSON logs
-    let file_appender = tracing_appender::rolling::daily("logs", "api-errors.log");
-    let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
+    // 2. Structured JSON logs to a daily file; compact stderr output that
+    // respects RUST_LOG or defaults to 'off'. The guard flushes the file.
+    let _logging = LoggingBuilder::new()
+        .file("logs", "api-errors.log")
+        .default_filter("off")
+        .build()
+        .expect("Failed to set up logging");
 
-    // 3. Respect RUST_LOG or default to 'off'
-    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("off"));
+    // 3. Miette hook for structured panic diagnostics
+    miette::set_panic_hook();
 
-    tracing_subscriber::registry()
-        .with(fmt::layer().json().with_writer(non_blocking))
-        .with(
-            fmt::layer()
-                .with_writer(std::io::stderr)
-                .compact()
-                .with_filter(filter),
-        )
-        .init();
+    // 4. Log panics as ApiError events too, then defer to the miette hook
+    install_panic_to_api_error();
 
-    // 4. Miette hook for structured panic diagnostics
-    miette::set_panic_hook();
+    // 5. Run the demos; failures exit with a code scripts can branch on
+    run_with_exit_codes(run, &exit_codes())
+}
+
+/// Reacts to what went wrong, beyond rendering the report.
+fn act_on(report: &LibReport<CliError>) {
+    for action in classify_error(report) {
+        match action {
+            ErrorAction::MissingFile {
+                path,
+            } => {
+                let path = path.map_or_else(String::new, |p| format!(": {}", p.display()));
+                println!("--- LOGIC CHECK: Missing file detected{path} ---");
+            },
+            ErrorAction::PermissionDenied => println!("--- LOGIC CHECK: Permission denied ---"),
+            ErrorAction::NetworkTimeout {
+                ..
+            } => println!("--- LOGIC CHECK: Network timeout, worth a retry ---"),
+            ErrorAction::Unknown => {},
+        }
+    }
+}
 
+/// sysexits-style statuses per error namespace.
+fn exit_codes() -> ExitCodeMap {
+    ExitCodeMap::sysexits().rule("network::*", 69) // EX_UNAVAILABLE
+}
+
+fn run() -> LibResult<(), CliError> {
     // ---------------------------------------------------------------------------
     // Demo 1: structured config parse error with source snippet
     // ---------------------------------------------------------------------------
     println!("--- Demo 1: Config parse error ---");
     if let Err(report) = perform_task() {
-        handle_error_logic(&report);
-
-        let api_err = report.to_api_error();
-        eprintln!("\n[Diagnostic ID: {}]", api_err.correlation_id);
-
-        return Err(miette::Report::new(report));
+        act_on(&report);
+        return Err(report);
     }
 
     // ---------------------------------------------------------------------------
@@ -87,8 +114,10 @@ fn main() -> miette::Result<()> {
     // then wrapped into LibReport at the boundary via into_lib_report().
     // ---------------------------------------------------------------------------
     println!("\n--- Demo 2: IO error via ? ---");
-    if let Err(report) = into_lib_report(read_config_file("nonexistent.json").map(|_| ())) {
-        handle_error_logic(&report);
+    let path = "nonexistent.json";
+    if let Err(report) = into_lib_report(read_config_file(path).map(|_| ())) {
+        let report = report.attach_typed(PathBuf::from(path));
+        act_on(&report);
 
         let api_err = report.to_api_error();
         eprintln!("\n[Diagnostic ID: {}]", api_err.correlation_id);
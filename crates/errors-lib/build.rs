@@ -1,9 +1,14 @@
 /*
  * Build script to inject environment metadata and Git state.
- * Handles PKG_VERSION for documentation and GIT_HASH for version tracking.
+ * Handles PKG_VERSION for documentation, GIT_HASH/GIT_BRANCH/GIT_DIRTY for
+ * version tracking, BUILD_TIMESTAMP for telling deployments apart and
+ * RUSTC_VERSION/TARGET_TRIPLE for toolchain-specific bugs.
  */
 
-use std::process::Command;
+use std::{
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 fn main() {
     // 1. Documentation Metadata
@@ -13,15 +18,111 @@ fn main() {
     println!("cargo:rustc-env=ERROR_DOCS_URL=https://docs.rs/errors-lib/{version}");
 
     // 2. Git Metadata
-    let output = Command::new("git")
-        .args(["rev-parse", "--short", "HEAD"])
-        .output();
-
-    let git_hash = match output {
-        Ok(out) if out.status.success() => String::from_utf8_lossy(&out.stdout).trim().to_string(),
-        _ => "unknown".to_string(),
-    };
+    let git_hash = git(&["rev-parse", "--short", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    let git_branch =
+        git(&["rev-parse", "--abbrev-ref", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    let git_dirty = git(&["status", "--porcelain"]).is_some_and(|status| !status.is_empty());
 
     println!("cargo:rustc-env=GIT_HASH={git_hash}");
-    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rustc-env=GIT_BRANCH={git_branch}");
+    println!("cargo:rustc-env=GIT_DIRTY={git_dirty}");
+
+    // Watch the real HEAD and index files; a path that doesn't exist would
+    // make cargo rerun this script on every build. GIT_DIRTY reflects the
+    // tree as of the last rerun, not every unstaged edit.
+    for name in ["HEAD", "index"] {
+        if let Some(path) = git(&["rev-parse", "--git-path", name]) {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+
+    // 3. Build Timestamp
+    //
+    // Tradeoff: the timestamp is only refreshed when this script reruns —
+    // when the sources, the checked-out commit or the package version
+    // change. A no-op `cargo build` keeps the previous value rather than
+    // recompiling the crate every time. SOURCE_DATE_EPOCH pins it for
+    // reproducible builds.
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+
+    let epoch_secs = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs())
+        });
+
+    println!("cargo:rustc-env=BUILD_TIMESTAMP={}", rfc3339(epoch_secs));
+
+    // 4. Toolchain
+    //
+    // Cargo hands build scripts the compiler it is using in RUSTC; fall back
+    // to whatever is on PATH, then to "unknown".
+    println!("cargo:rerun-if-env-changed=RUSTC");
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map_or_else(
+            || "unknown".to_string(),
+            |out| String::from_utf8_lossy(&out.stdout).trim().to_string(),
+        );
+
+    let target_triple = [
+        "CARGO_CFG_TARGET_ARCH",
+        "CARGO_CFG_TARGET_OS",
+        "CARGO_CFG_TARGET_ENV",
+    ]
+    .iter()
+    .filter_map(|key| std::env::var(key).ok())
+    .filter(|part| !part.is_empty())
+    .collect::<Vec<_>>()
+    .join("-");
+
+    println!("cargo:rustc-env=RUSTC_VERSION={rustc_version}");
+    println!("cargo:rustc-env=TARGET_TRIPLE={target_triple}");
+}
+
+/// Runs a git command, returning its trimmed stdout on success.
+/// `None` when git is missing or this isn't a git checkout.
+fn git(args: &[&str]) -> Option<String> {
+    let out = Command::new("git").args(args).output().ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Formats seconds since the Unix epoch as an RFC 3339 UTC timestamp.
+fn rfc3339(epoch_secs: u64) -> String {
+    let days = epoch_secs / 86_400;
+    let secs = epoch_secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// Converts days since 1970-01-01 to a (year, month, day) civil date.
+/// Howard Hinnant's `civil_from_days`, restricted to post-epoch dates.
+const fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
 }
@@ -0,0 +1,177 @@
+/*
+ * classify_error: what went wrong, as data a caller can act on.
+ *
+ * io errors anywhere in the tree map to an ErrorAction. Every node is
+ * visited, children included, and on each one three positions are checked:
+ * the node's context, the context's source() chain, and io::Error values
+ * attached with attach_typed. Details io::Error can't carry come from typed
+ * attachments on the same node: a PathBuf for the missing file, a Duration
+ * for the timeout.
+ *
+ * handle_error_logic hands those actions to an ActionHandler and reports
+ * which one it acted on and what came of it: handled, unhandled, or worth a
+ * retry. DefaultActionHandler treats a missing file as handled and leaves
+ * the rest to the caller.
+ */
+
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use miette::Diagnostic;
+
+use crate::{LibReport, source_of};
+
+/// Something a caller can react to, found by [`classify_error`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// A file was not found (`io::ErrorKind::NotFound`); `path` is a
+    /// `PathBuf` attached to the same node, if any.
+    MissingFile { path: Option<PathBuf> },
+    /// Access was refused (`io::ErrorKind::PermissionDenied`).
+    PermissionDenied,
+    /// An operation timed out (`io::ErrorKind::TimedOut`); `seconds` is a
+    /// `Duration` attached to the same node, if any.
+    NetworkTimeout { seconds: Option<u64> },
+    /// Nothing in the chain was recognized.
+    Unknown,
+}
+
+/// The actions for every recognized io error in the report, top-level node
+/// first; `[ErrorAction::Unknown]` when there are none.
+///
+/// Every node is inspected depth-first, not just the top-level one. On each
+/// node an `io::Error` is recognized as the context itself, anywhere in the
+/// context's `source()` chain, or as a typed attachment, in that order.
+pub fn classify_error<E, O>(report: &LibReport<E, O>) -> Vec<ErrorAction>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    let mut actions = Vec::new();
+    for node in report.0.iter_reports() {
+        let source = source_of::<E>(node);
+        let context = node.downcast_current_context::<io::Error>();
+        let attachments = node.attachments();
+        let io_errors = context
+            .into_iter()
+            .chain(
+                std::iter::successors(source, |&err| err.source())
+                    .filter_map(|err| err.downcast_ref::<io::Error>()),
+            )
+            .chain(
+                attachments
+                    .iter()
+                    .filter_map(|attachment| attachment.downcast_inner::<io::Error>()),
+            );
+
+        let path = attachments
+            .iter()
+            .find_map(|attachment| attachment.downcast_inner::<PathBuf>())
+            .map(PathBuf::as_path);
+        let timeout = attachments
+            .iter()
+            .find_map(|attachment| attachment.downcast_inner::<Duration>());
+        actions.extend(io_errors.filter_map(|err| action_for(err, path, timeout)));
+    }
+
+    if actions.is_empty() {
+        actions.push(ErrorAction::Unknown);
+    }
+    actions
+}
+
+fn action_for(
+    err: &io::Error,
+    path: Option<&Path>,
+    timeout: Option<&Duration>,
+) -> Option<ErrorAction> {
+    match err.kind() {
+        io::ErrorKind::NotFound => Some(ErrorAction::MissingFile {
+            path: path.map(Path::to_path_buf),
+        }),
+        io::ErrorKind::PermissionDenied => Some(ErrorAction::PermissionDenied),
+        io::ErrorKind::TimedOut => Some(ErrorAction::NetworkTimeout {
+            seconds: timeout.map(Duration::as_secs),
+        }),
+        _ => None,
+    }
+}
+
+/// What a handler did about an [`ErrorAction`], returned by
+/// [`handle_error_logic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandleOutcome {
+    /// The handler dealt with the error.
+    Handled,
+    /// Nothing was done; the caller should treat the error as usual.
+    Unhandled,
+    /// The operation is worth retrying after this delay.
+    Retry(Duration),
+}
+
+/// Decides what to do about each [`ErrorAction`] in a report, for
+/// [`handle_error_logic`]. Leaves every action unhandled unless
+/// overridden; closures taking `&ErrorAction` implement it too.
+pub trait ActionHandler {
+    /// What was done about `action`; [`HandleOutcome::Unhandled`] to let
+    /// [`handle_error_logic`] move on to the next one.
+    fn handle(&self, action: &ErrorAction) -> HandleOutcome {
+        let _ = action;
+        HandleOutcome::Unhandled
+    }
+}
+
+impl<F> ActionHandler for F
+where
+    F: Fn(&ErrorAction) -> HandleOutcome,
+{
+    fn handle(&self, action: &ErrorAction) -> HandleOutcome {
+        self(action)
+    }
+}
+
+/// The handler for callers with nothing more specific: a missing file is
+/// [`HandleOutcome::Handled`], every other action
+/// [`HandleOutcome::Unhandled`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultActionHandler;
+
+impl ActionHandler for DefaultActionHandler {
+    fn handle(&self, action: &ErrorAction) -> HandleOutcome {
+        match action {
+            ErrorAction::MissingFile {
+                ..
+            } => HandleOutcome::Handled,
+            _ => HandleOutcome::Unhandled,
+        }
+    }
+}
+
+/// Runs `handler` over the actions [`classify_error`] finds, top-level
+/// node first, and returns the first outcome other than
+/// [`HandleOutcome::Unhandled`] with the action it came from.
+///
+/// When no action is dealt with, the outcome is `Unhandled` and the action
+/// is the first one found, [`ErrorAction::Unknown`] if none was recognized.
+pub fn handle_error_logic<E, O, H>(
+    report: &LibReport<E, O>,
+    handler: &H,
+) -> (ErrorAction, HandleOutcome)
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+    H: ActionHandler + ?Sized,
+{
+    let mut actions = classify_error(report);
+    let handled = actions.iter().enumerate().find_map(|(index, action)| {
+        let outcome = handler.handle(action);
+        (outcome != HandleOutcome::Unhandled).then_some((index, outcome))
+    });
+    match handled {
+        Some((index, outcome)) => (actions.swap_remove(index), outcome),
+        None => (actions.swap_remove(0), HandleOutcome::Unhandled),
+    }
+}
@@ -0,0 +1,74 @@
+/*
+ * Docs anchors for diagnostic codes.
+ *
+ * Diagnostic::url() links each code to `{docs}/#{anchor}`. Codes are free
+ * text, so the anchor is percent-encoded to stay a valid URL fragment; the
+ * docs generator calls code_to_anchor() to produce matching IDs. The `::`
+ * separator may be remapped (e.g. to `-`) for sites that slugify headings.
+ */
+
+use std::sync::{OnceLock, PoisonError, RwLock};
+
+use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
+
+/// The separator anchors use in place of `::` until
+/// [`set_anchor_separator`] is called; `::` itself, i.e. unchanged.
+pub const DEFAULT_ANCHOR_SEPARATOR: &str = "::";
+
+/// Bytes escaped in an anchor: everything RFC 3986 disallows in a fragment,
+/// plus `/` and `?`, which docs sites tend to treat as path or query
+/// delimiters. Non-ASCII is always escaped, as UTF-8.
+const ANCHOR_ESCAPES: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+fn separator() -> &'static RwLock<String> {
+    static SEPARATOR: OnceLock<RwLock<String>> = OnceLock::new();
+    SEPARATOR.get_or_init(|| RwLock::new(DEFAULT_ANCHOR_SEPARATOR.to_owned()))
+}
+
+/// Replaces the text `::` maps to in anchors.
+pub fn set_anchor_separator(new_separator: impl Into<String>) {
+    *separator().write().unwrap_or_else(PoisonError::into_inner) = new_separator.into();
+}
+
+/// The text `::` currently maps to in anchors.
+#[must_use]
+pub fn anchor_separator() -> String {
+    separator()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Turns a diagnostic code into the URL fragment its docs live under.
+///
+/// Surrounding whitespace is trimmed, `::` is replaced by the
+/// [anchor separator](set_anchor_separator), and anything not safe in a
+/// fragment is percent-encoded:
+///
+/// ```
+/// use errors_lib::code_to_anchor;
+///
+/// assert_eq!(code_to_anchor("config::invalid_format"), "config::invalid_format");
+/// assert_eq!(code_to_anchor("disk full"), "disk%20full");
+/// ```
+#[must_use]
+pub fn code_to_anchor(code: &str) -> String {
+    let code = code.trim().replace("::", &anchor_separator());
+    utf8_percent_encode(&code, ANCHOR_ESCAPES).to_string()
+}
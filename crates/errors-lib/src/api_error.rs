@@ -0,0 +1,140 @@
+/*
+ * The ApiError data model.
+ *
+ * Plain data plus serde (both ways), with no std dependency, so embedded and
+ * WASM consumers can use it with only the `alloc` feature and build the
+ * values themselves. Building one from a report (to_api_error) needs std.
+ *
+ * Keys are the Rust field names; wrap the value in CamelCase to serialize
+ * it with `correlationId`, `gitHash`, ... for JS consumers.
+ */
+
+use alloc::{borrow::Cow, collections::BTreeMap, string::String, vec::Vec};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{ErrorCode, ErrorTree};
+
+/// One line of [`ApiError::history`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorFrame {
+    /// A context's message or a rendered attachment, as displayed.
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiError {
+    /// Build details are compile-time constants, so `to_api_error` borrows
+    /// them instead of allocating; deserialized values are owned.
+    pub git_hash: Cow<'static, str>,
+    #[serde(default)]
+    pub git_branch: Cow<'static, str>,
+    #[serde(default)]
+    pub git_dirty: bool,
+    #[serde(default)]
+    pub build_timestamp: Cow<'static, str>,
+    #[serde(default)]
+    pub rustc_version: Cow<'static, str>,
+    #[serde(default)]
+    pub target: Cow<'static, str>,
+    /// The crate's docs root (see
+    /// [`docs_base_url`](crate::docs_base_url)), borrowed.
+    pub docs_url: Cow<'static, str>,
+    /// The link for the code itself, as
+    /// [`docs_url_for`](crate::docs_url_for) gives it; `None` without a
+    /// code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_url: Option<String>,
+    pub correlation_id: String,
+    pub title: String,
+    /// The report on one line, every context's message included; set
+    /// for trees with children and logged as the title.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// The context's diagnostic code; see
+    /// [`ErrorCode::is_well_formed`](crate::ErrorCode::is_well_formed).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<ErrorCode>,
+    /// The code's namespace (`config` for `config::invalid_format`); `None`
+    /// without a well-formed code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    /// `error`, `warning` or `advice`, when the report declares one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    /// Where the report was wrapped, as `file:line:column`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// The name of the thread the error was built on; `None` for unnamed
+    /// threads.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_name: Option<String>,
+    /// The ID of the thread the error was built on, as `ThreadId(N)`;
+    /// empty for values built by hand without threads (`no_std`).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub thread_id: String,
+    /// Pairs attached with
+    /// [`LibReport::attach_field`](crate::LibReport::attach_field).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+    /// Process-wide fields (service, environment, ...) set with
+    /// [`set_global_fields`](crate::set_global_fields).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, String>,
+    /// Whether the failure is transient; see
+    /// [`ReportExt::is_retryable`](crate::ReportExt::is_retryable).
+    #[serde(default)]
+    pub retryable: bool,
+    /// How long to wait before retrying, for a `Retry-After` header; set
+    /// from a [`RetryHint`](crate::RetryHint) in the report, or from a
+    /// [`RetryPolicy`](crate::RetryPolicy) when one is supplied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_ms: Option<u64>,
+    /// How many attempts are worth making, from a
+    /// [`RetryHint`](crate::RetryHint) in the report.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+    #[serde(
+        serialize_with = "serialize_history_flat",
+        deserialize_with = "deserialize_history_flat"
+    )]
+    pub history: Vec<ErrorFrame>,
+    /// The spans active when the error was built, innermost first, as
+    /// `name{fields}`; requires the `span-trace` feature and tracing-error's
+    /// `ErrorLayer`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span_trace: Option<Vec<String>>,
+    /// The OpenTelemetry trace the error was built in, as 32 hex digits;
+    /// requires the `otel` feature and a tracing-opentelemetry layer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    /// The OpenTelemetry span the error was built in, as 16 hex digits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span_id: Option<String>,
+    /// The report's structure, when requested via
+    /// [`ApiErrorOptions::include_tree`](crate::ApiErrorOptions::include_tree).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tree: Option<ErrorTree>,
+}
+
+pub fn serialize_history_flat<S>(history: &[ErrorFrame], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_seq(history.iter().map(|f| f.message.as_str()))
+}
+
+fn deserialize_history_flat<'de, D>(deserializer: D) -> Result<Vec<ErrorFrame>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let flat = Vec::<String>::deserialize(deserializer)?;
+    Ok(flat
+        .into_iter()
+        .map(|message| ErrorFrame {
+            message,
+        })
+        .collect())
+}
@@ -0,0 +1,183 @@
+/*
+ * Attachments on a LibReport: plain messages and typed payloads.
+ *
+ * Anything attached to the rootcause tree can be fished back out by type.
+ * Display attachments also show up in ApiError history; typed payloads
+ * attached with `attach_typed` need no Display and stay out of it.
+ * `attach_field` pairs are typed too, and surface as ApiError::fields.
+ */
+
+use std::{collections::BTreeMap, fmt, panic::Location};
+
+use miette::{Diagnostic, Severity};
+use rootcause::{Report, handlers::AttachmentHandler};
+
+use crate::{LibReport, emit};
+
+impl<E> LibReport<E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    /// Attaches a message to the top-level node. It appears in
+    /// `ApiError::history` and can still be retrieved by type.
+    #[must_use]
+    pub fn attach<A>(self, attachment: A) -> Self
+    where
+        A: fmt::Display + fmt::Debug + Send + Sync + 'static,
+    {
+        Self::wrap(self.0.attach(attachment))
+    }
+
+    /// Attaches `at file:line:column`, marking where the report passed
+    /// through; [`attach_location!`](crate::attach_location) fills in the
+    /// call site.
+    #[must_use]
+    pub fn attach_source_location(self, file: &'static str, line: u32, column: u32) -> Self {
+        self.attach(format!("at {file}:{line}:{column}"))
+    }
+
+    /// Attaches structured data (request info, ids, ...) for later
+    /// retrieval with [`LibReport::get_attachment`]. Typed payloads render
+    /// as nothing, so they are left out of `ApiError::history`.
+    #[must_use]
+    pub fn attach_typed<T>(self, value: T) -> Self
+    where
+        T: Send + Sync + 'static,
+    {
+        Self::wrap(attach_opaque(self.0, value))
+    }
+
+    /// Attaches a `key=value` pair, queryable in logs: it lands in
+    /// `ApiError::fields` and on the tracing event instead of in history.
+    /// For a repeated key the latest value wins.
+    #[must_use]
+    pub fn attach_field(self, key: &'static str, value: impl fmt::Display) -> Self {
+        emit::register_field_key(key);
+        self.attach_typed(ContextField {
+            key,
+            value: value.to_string(),
+        })
+    }
+
+    /// Overrides the context's declared severity for this report only,
+    /// e.g. to downgrade an expected failure to a warning. Affects
+    /// rendering; `ApiError::severity` and the tracing level take the
+    /// [`LibReport::max_severity`] of the tree, so a more severe child
+    /// still wins there.
+    ///
+    /// Stored as an attachment on the top-level node, so it survives
+    /// further attachments and `into_cloneable`/`into_dynamic`; the latest
+    /// override wins.
+    #[must_use]
+    pub fn with_severity(self, severity: Severity) -> Self {
+        self.attach_typed(SeverityOverride(severity))
+    }
+}
+
+impl<E, O> LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    /// The first attachment of type `T` anywhere in the tree, searching
+    /// from the top-level node down.
+    #[must_use]
+    pub fn get_attachment<T: 'static>(&self) -> Option<&T> {
+        self.iter_attachments().next()
+    }
+
+    /// Every attachment of type `T` in the tree, top-level node first.
+    pub fn iter_attachments<T: 'static>(&self) -> impl Iterator<Item = &T> {
+        self.0.iter_reports().flat_map(|node| {
+            node.attachments()
+                .iter()
+                .filter_map(|attachment| attachment.downcast_inner::<T>())
+        })
+    }
+}
+
+/// Where a [`LibReport`] was created.
+///
+/// [`LibReport::new`] (and so `Report::into()`) is `#[track_caller]` and
+/// attaches one of these as a typed attachment, so `ApiError::location`
+/// names the call site without a backtrace. Retrieve it with
+/// `report.get_attachment::<ReportLocation>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportLocation(pub &'static Location<'static>);
+
+impl ReportLocation {
+    /// The location of the (`#[track_caller]`) caller.
+    #[must_use]
+    #[track_caller]
+    pub const fn caller() -> Self {
+        Self(Location::caller())
+    }
+}
+
+/// `file:line:column`.
+impl fmt::Display for ReportLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.0, f)
+    }
+}
+
+/// Attachment recorded by [`LibReport::with_severity`].
+struct SeverityOverride(Severity);
+
+/// The latest severity override on the report's top-level node.
+pub fn severity_override<C: ?Sized, O>(report: &Report<C, O>) -> Option<Severity> {
+    report
+        .attachments()
+        .iter()
+        .rev()
+        .find_map(|attachment| attachment.downcast_inner::<SeverityOverride>())
+        .map(|o| o.0)
+}
+
+/// Attachment recorded by [`LibReport::attach_field`].
+struct ContextField {
+    key: &'static str,
+    value: String,
+}
+
+/// Every [`LibReport::attach_field`] pair in the tree. For a repeated key
+/// the latest value wins: outer nodes over inner ones, and later
+/// attachments over earlier ones on the same node.
+pub fn fields_of<C: ?Sized, O>(report: &Report<C, O>) -> BTreeMap<String, String> {
+    let mut fields = BTreeMap::new();
+    for node in report.iter_reports() {
+        let latest_first = node
+            .attachments()
+            .iter()
+            .rev()
+            .filter_map(|attachment| attachment.downcast_inner::<ContextField>());
+        for field in latest_first {
+            fields
+                .entry(field.key.to_owned())
+                .or_insert_with(|| field.value.clone());
+        }
+    }
+    fields
+}
+
+/// Handler for typed payloads: no `Display` output, type name as `Debug`.
+struct Opaque;
+
+/// `report` with `value` attached as a typed payload.
+pub fn attach_opaque<E, T>(report: Report<E>, value: T) -> Report<E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    T: Send + Sync + 'static,
+{
+    report.attach_custom::<Opaque, _>(value)
+}
+
+impl<A: 'static> AttachmentHandler<A> for Opaque {
+    fn display(_value: &A, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Ok(())
+    }
+
+    fn debug(_value: &A, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(std::any::type_name::<A>())
+    }
+}
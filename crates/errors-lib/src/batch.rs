@@ -0,0 +1,73 @@
+/*
+ * Batches of reports converted together.
+ *
+ * Failures collected from one fan-out (join_all over requests, a batch
+ * job's items) are easier to follow in logs when their correlation IDs
+ * say they belong together. A batch draws one random 6-character prefix
+ * and numbers its errors 00-99 after it; larger batches draw a new prefix
+ * per hundred.
+ */
+
+use std::fmt;
+
+use nanoid::nanoid;
+
+use crate::{ApiError, ApiErrorOptions, ReportExt};
+
+/// An 8-character correlation ID, for
+/// [`ApiErrorOptions::correlation_id`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CorrelationId([u8; 8]);
+
+impl CorrelationId {
+    /// `id`, if it is exactly 8 ASCII characters.
+    #[must_use]
+    pub fn new(id: &str) -> Option<Self> {
+        let bytes: [u8; 8] = id.as_bytes().try_into().ok()?;
+        bytes.is_ascii().then_some(Self(bytes))
+    }
+
+    /// The ID as text.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        // Only ASCII is ever stored.
+        std::str::from_utf8(&self.0).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+/// Converts many reports at once, with related correlation IDs.
+pub trait BatchReportExt {
+    /// One [`ReportExt::to_api_error`] per report, in order, each emitted
+    /// as usual. The IDs share a 6-character prefix and end in the
+    /// report's two-digit position (`V1StGX00`, `V1StGX01`, ...).
+    fn batch_to_api_errors(&self) -> Vec<ApiError>;
+}
+
+impl<R: ReportExt> BatchReportExt for [R] {
+    fn batch_to_api_errors(&self) -> Vec<ApiError> {
+        let mut errors = Vec::with_capacity(self.len());
+        for chunk in self.chunks(100) {
+            let prefix = nanoid!(6);
+            for (index, report) in chunk.iter().enumerate() {
+                let mut options = ApiErrorOptions::default();
+                if let Some(id) = CorrelationId::new(&format!("{prefix}{index:02}")) {
+                    options = options.correlation_id(id);
+                }
+                errors.push(report.to_api_error_with_options(options));
+            }
+        }
+        errors
+    }
+}
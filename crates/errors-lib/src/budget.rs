@@ -0,0 +1,89 @@
+/*
+ * Size budgets for ApiError payloads.
+ *
+ * Gateways cap response bodies, and a deep history can push an ApiError
+ * past the cap. size_budget trims the least essential parts first — the
+ * tree and summary, then history — and only cuts into title and help as a
+ * last resort, so the error stays deliverable instead of being rejected
+ * outright.
+ */
+
+use crate::ApiError;
+
+/// Appended to `help` when [`ApiError::size_budget`] drops history.
+pub const HISTORY_TRUNCATED: &str = "history truncated";
+
+const ELLIPSIS: char = '…';
+
+impl ApiError {
+    /// `Ok(self)` when the JSON form fits in `max_bytes`.
+    ///
+    /// # Errors
+    ///
+    /// When it doesn't, returns a trimmed copy: without `tree` or
+    /// `summary`, with history entries dropped from the front until it
+    /// fits and [`HISTORY_TRUNCATED`] noted in `help`. If even an empty history
+    /// is too much, `help` and then `title` are shortened (ending in `…`).
+    /// Only when those are empty too can the result still exceed the
+    /// budget: the build metadata alone takes a few hundred bytes.
+    // Both variants are the error itself, so boxing one would only make
+    // callers unwrap it differently.
+    #[allow(clippy::result_large_err)]
+    pub fn size_budget(self, max_bytes: usize) -> Result<Self, Self> {
+        if json_len(&self) <= max_bytes {
+            return Ok(self);
+        }
+
+        let mut err = self;
+        err.tree = None;
+        err.summary = None;
+        if !err.history.is_empty() {
+            err.help = Some(err.help.take().map_or_else(
+                || HISTORY_TRUNCATED.to_owned(),
+                |help| format!("{help} ({HISTORY_TRUNCATED})"),
+            ));
+        }
+        while json_len(&err) > max_bytes && !err.history.is_empty() {
+            err.history.remove(0);
+        }
+
+        while err.help.is_some() {
+            let over = json_len(&err).saturating_sub(max_bytes);
+            if over == 0 {
+                break;
+            }
+            if !err.help.as_mut().is_some_and(|help| shorten(help, over)) {
+                err.help = None;
+            }
+        }
+        loop {
+            let over = json_len(&err).saturating_sub(max_bytes);
+            if over == 0 || !shorten(&mut err.title, over) {
+                break;
+            }
+        }
+        Err(err)
+    }
+}
+
+fn json_len(err: &ApiError) -> usize {
+    serde_json::to_vec(err).map_or(usize::MAX, |json| json.len())
+}
+
+/// Cuts at least `by` bytes from the end of `text`, marking the cut with
+/// an ellipsis. Returns false, leaving `text` empty, when nothing would be
+/// left but the ellipsis.
+fn shorten(text: &mut String, by: usize) -> bool {
+    let keep = text.len().saturating_sub(by + ELLIPSIS.len_utf8());
+    let keep = (0..=keep)
+        .rev()
+        .find(|&i| text.is_char_boundary(i))
+        .unwrap_or(0);
+    if keep == 0 {
+        text.clear();
+        return false;
+    }
+    text.truncate(keep);
+    text.push(ELLIPSIS);
+    true
+}
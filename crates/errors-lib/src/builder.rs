@@ -0,0 +1,161 @@
+/*
+ * ApiError without a report.
+ *
+ * Adapters that relay failures from another system, and tests, need an
+ * ApiError with no rootcause::Report behind it. ApiErrorBuilder takes the
+ * fields a report would supply and stamps the rest (build details, docs
+ * link, correlation ID) exactly as to_api_error does, so the result is
+ * indistinguishable from one built from a report. Nothing is emitted to
+ * the sinks.
+ *
+ * A report always has a title, so build() fails without one instead of
+ * producing an ApiError to_api_error never would. Codes are kept as a
+ * report keeps them: a `namespace::variant` one gets a domain, any other
+ * non-blank one is kept without, so every ApiError a report yields can be
+ * rebuilt here.
+ */
+
+use std::{borrow::Cow, fmt};
+
+use crate::{ApiError, ErrorFrame, build_api_error};
+
+impl ApiError {
+    /// An empty [`ApiErrorBuilder`].
+    pub fn builder() -> ApiErrorBuilder {
+        ApiErrorBuilder::new()
+    }
+}
+
+/// Why [`ApiErrorBuilder::build`] failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiErrorBuilderError {
+    /// No title was set, or it is blank.
+    MissingTitle,
+}
+
+impl fmt::Display for ApiErrorBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingTitle => f.write_str("an ApiError needs a title"),
+        }
+    }
+}
+
+impl std::error::Error for ApiErrorBuilderError {}
+
+/// Builds an [`ApiError`] field by field; see [`ApiError::builder`].
+///
+/// ```
+/// use errors_lib::ApiError;
+///
+/// let api_err = ApiError::builder()
+///     .title("Upstream rejected the payment")
+///     .code("billing::declined")
+///     .push_history_frame("card issuer returned 05")
+///     .build()
+///     .unwrap();
+/// assert_eq!(api_err.domain.as_deref(), Some("billing"));
+///
+/// let legacy = ApiError::builder().title("Declined").code("E0051").build().unwrap();
+/// assert_eq!(legacy.domain, None);
+///
+/// assert!(ApiError::builder().code("billing::declined").build().is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct ApiErrorBuilder {
+    title: Option<String>,
+    code: Option<String>,
+    help: Option<String>,
+    history: Vec<ErrorFrame>,
+    git_hash: Option<String>,
+    docs_url: Option<String>,
+    correlation_id: Option<String>,
+}
+
+impl ApiErrorBuilder {
+    /// An empty builder; only the title is required.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the title.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the code, and with it `code_url` and, for a
+    /// `namespace::variant` code, `domain`. A blank code is no code.
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    /// Sets the help text.
+    pub fn help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Replaces the history with `messages`, in order.
+    pub fn history<M: Into<String>>(mut self, messages: impl IntoIterator<Item = M>) -> Self {
+        self.history = messages
+            .into_iter()
+            .map(|message| ErrorFrame {
+                message: message.into(),
+            })
+            .collect();
+        self
+    }
+
+    /// Appends a history frame; frames keep the order they are pushed in.
+    pub fn push_history_frame(mut self, message: impl Into<String>) -> Self {
+        self.history.push(ErrorFrame {
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Used instead of this build's git hash.
+    pub fn git_hash(mut self, hash: impl Into<String>) -> Self {
+        self.git_hash = Some(hash.into());
+        self
+    }
+
+    /// Used instead of the docs base URL.
+    pub fn docs_url(mut self, url: impl Into<String>) -> Self {
+        self.docs_url = Some(url.into());
+        self
+    }
+
+    /// Used instead of a fresh random ID.
+    pub fn correlation_id(mut self, id: impl Into<String>) -> Self {
+        self.correlation_id = Some(id.into());
+        self
+    }
+
+    /// The `ApiError`, with defaults for every field not set.
+    ///
+    /// # Errors
+    ///
+    /// [`ApiErrorBuilderError::MissingTitle`] without a non-blank title.
+    pub fn build(self) -> Result<ApiError, ApiErrorBuilderError> {
+        let title = self
+            .title
+            .filter(|title| !title.trim().is_empty())
+            .ok_or(ApiErrorBuilderError::MissingTitle)?;
+        let mut api_err =
+            build_api_error(self.history, title, self.code, self.help, None, false, None);
+        if let Some(hash) = self.git_hash {
+            api_err.git_hash = Cow::Owned(hash);
+        }
+        if let Some(url) = self.docs_url {
+            api_err.docs_url = Cow::Owned(url);
+        }
+        if let Some(id) = self.correlation_id {
+            api_err.correlation_id = id;
+        }
+        Ok(api_err)
+    }
+}
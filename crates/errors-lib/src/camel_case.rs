@@ -0,0 +1,184 @@
+/*
+ * camelCase JSON keys, chosen per serialization.
+ *
+ * JS consumers expect `correlationId` and `gitHash`, but ApiError's wire
+ * format is shared by every sink and consumer in the dependency graph, so
+ * it can't change crate-wide. CamelCase wraps a borrowed value and
+ * serializes it through a mirror struct with `rename_all = "camelCase"`
+ * and the same skip rules, leaving the default format alone.
+ */
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeMap, string::String};
+
+use serde::{Serialize, Serializer};
+
+#[cfg(feature = "alloc")]
+use crate::{ApiError, ErrorCode, ErrorFrame, ErrorTree, api_error::serialize_history_flat};
+
+/// Serializes the wrapped value with camelCase keys.
+///
+/// ```
+/// use errors_lib::{ApiError, CamelCase};
+///
+/// let api_err = ApiError::builder().title("Upstream failed").build().unwrap();
+/// let json = serde_json::to_value(CamelCase(&api_err)).unwrap();
+/// assert_eq!(json["correlationId"], api_err.correlation_id.as_str());
+/// assert!(json.get("correlation_id").is_none());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CamelCase<'a, T>(pub &'a T);
+
+#[cfg(feature = "alloc")]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiErrorFields<'a> {
+    git_hash: &'a str,
+    git_branch: &'a str,
+    git_dirty: bool,
+    build_timestamp: &'a str,
+    rustc_version: &'a str,
+    target: &'a str,
+    docs_url: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code_url: Option<&'a str>,
+    correlation_id: &'a str,
+    title: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<&'a ErrorCode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    domain: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    severity: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thread_name: Option<&'a str>,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    thread_id: &'a str,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    fields: &'a BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    extra: &'a BTreeMap<String, String>,
+    retryable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_retries: Option<u32>,
+    #[serde(serialize_with = "serialize_history_flat")]
+    history: &'a [ErrorFrame],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    span_trace: Option<&'a [String]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trace_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    span_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tree: Option<&'a ErrorTree>,
+}
+
+#[cfg(feature = "alloc")]
+impl Serialize for CamelCase<'_, ApiError> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // No `..`: a field added to ApiError must be added here too.
+        let ApiError {
+            git_hash,
+            git_branch,
+            git_dirty,
+            build_timestamp,
+            rustc_version,
+            target,
+            docs_url,
+            code_url,
+            correlation_id,
+            title,
+            summary,
+            code,
+            domain,
+            help,
+            severity,
+            location,
+            thread_name,
+            thread_id,
+            fields,
+            extra,
+            retryable,
+            retry_after_ms,
+            max_retries,
+            history,
+            span_trace,
+            trace_id,
+            span_id,
+            tree,
+        } = self.0;
+        ApiErrorFields {
+            git_hash,
+            git_branch,
+            git_dirty: *git_dirty,
+            build_timestamp,
+            rustc_version,
+            target,
+            docs_url,
+            code_url: code_url.as_deref(),
+            correlation_id,
+            title,
+            summary: summary.as_deref(),
+            code: code.as_ref(),
+            domain: domain.as_deref(),
+            help: help.as_deref(),
+            severity: severity.as_deref(),
+            location: location.as_deref(),
+            thread_name: thread_name.as_deref(),
+            thread_id,
+            fields,
+            extra,
+            retryable: *retryable,
+            retry_after_ms: *retry_after_ms,
+            max_retries: *max_retries,
+            history,
+            span_trace: span_trace.as_deref(),
+            trace_id: trace_id.as_deref(),
+            span_id: span_id.as_deref(),
+            tree: tree.as_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FixedApiErrorFields<'a, const N: usize, const H: usize> {
+    git_hash: &'a str,
+    docs_url: &'a str,
+    correlation_id: &'a str,
+    title: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help: Option<&'a str>,
+    retryable: bool,
+    history: &'a heapless::Vec<heapless::String<N>, H>,
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize, const H: usize> Serialize for CamelCase<'_, crate::FixedApiError<N, H>> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let e = self.0;
+        FixedApiErrorFields {
+            git_hash: e.git_hash,
+            docs_url: e.docs_url,
+            correlation_id: &e.correlation_id,
+            title: &e.title,
+            code: e.code.as_deref(),
+            help: e.help.as_deref(),
+            retryable: e.retryable,
+            history: &e.history,
+        }
+        .serialize(serializer)
+    }
+}
@@ -0,0 +1,32 @@
+/*
+ * CBOR encoding for ApiError (`cbor` feature).
+ *
+ * For binary transports (IoT gateways, message queues) where JSON's size
+ * matters. Both directions go through the same serde impls as JSON, so the
+ * flattened `history` and skipped empty fields look the same in either.
+ */
+
+use crate::ApiError;
+
+impl ApiError {
+    /// Encodes the error as CBOR.
+    ///
+    /// # Panics
+    ///
+    /// Never; every field maps onto a CBOR type and the buffer is in memory.
+    #[must_use]
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes).expect("ApiError encodes as CBOR");
+        bytes
+    }
+
+    /// Decodes an error encoded by [`ApiError::to_cbor`].
+    ///
+    /// # Errors
+    ///
+    /// If `bytes` is not CBOR, or does not describe an `ApiError`.
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, ciborium::de::Error<std::io::Error>> {
+        ciborium::from_reader(bytes)
+    }
+}
@@ -0,0 +1,205 @@
+/*
+ * Diagnostic codes as a validated type.
+ *
+ * Codes follow `namespace::variant` (`config::invalid_format`), and exit
+ * codes, docs links, handlers and the `domain` field all key off the
+ * namespace. ErrorCode checks the shape once, when the code enters an
+ * ApiError, so consumers can split it without re-validating. Deeper codes
+ * (`config::io::denied`) are accepted: the namespace is the first segment
+ * and the variant everything after it.
+ *
+ * Not every code has that shape (`E0001`, `timeout`, a dependency's own
+ * codes), and such a code is still the code: it keeps its place in the
+ * ApiError, stats and fingerprints, it just has no namespace. So the check
+ * is recorded rather than enforced; only ErrorCode::new and FromStr reject.
+ */
+
+use alloc::string::{String, ToString};
+use core::{fmt, ops::Deref, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// A diagnostic code, usually of the form `namespace::variant`.
+///
+/// [`ErrorCode::new`] only accepts that form; [`ErrorCode::lenient`] and
+/// `From<String>` keep any code and record whether it is well-formed.
+/// Serializes as the plain string, and deserializes any string.
+///
+/// ```
+/// use errors_lib::ErrorCode;
+///
+/// let code = ErrorCode::new("config::invalid_format").unwrap();
+/// assert_eq!(code.namespace(), Some("config"));
+/// assert_eq!(code.variant(), Some("invalid_format"));
+/// assert!(ErrorCode::new("nocolon").is_err());
+///
+/// let legacy = ErrorCode::lenient("E0001").unwrap();
+/// assert!(!legacy.is_well_formed());
+/// assert_eq!(legacy.namespace(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct ErrorCode {
+    code: String,
+    well_formed: bool,
+}
+
+/// Why a string is not an [`ErrorCode`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorCodeError {
+    /// There is no `::`, e.g. `nocolon`.
+    MissingSeparator { code: String },
+    /// A segment is empty or contains whitespace, e.g. `config::` or
+    /// `config::bad format`.
+    InvalidSegment { code: String },
+}
+
+impl fmt::Display for ErrorCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSeparator {
+                code,
+            } => write!(f, "error code `{code}` has no `::` separator"),
+            Self::InvalidSegment {
+                code,
+            } => write!(
+                f,
+                "error code `{code}` has an empty segment or one containing whitespace"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for ErrorCodeError {}
+
+impl ErrorCode {
+    /// Checks that `code` is `namespace::variant`, with every `::`-separated
+    /// segment non-empty and free of whitespace.
+    ///
+    /// # Errors
+    ///
+    /// [`ErrorCodeError::MissingSeparator`] without a `::`, and
+    /// [`ErrorCodeError::InvalidSegment`] for an empty or blank segment.
+    pub fn new(code: &str) -> Result<Self, ErrorCodeError> {
+        validate(code)?;
+        Ok(Self {
+            code: code.to_string(),
+            well_formed: true,
+        })
+    }
+
+    /// Keeps any code that isn't blank, well-formed or not; `None` for a
+    /// blank one, which is no code at all.
+    #[must_use]
+    pub fn lenient(code: impl Into<String>) -> Option<Self> {
+        let code = code.into();
+        (!code.trim().is_empty()).then(|| Self::from(code))
+    }
+
+    /// Whether the code is `namespace::variant`, as [`ErrorCode::new`]
+    /// requires.
+    #[must_use]
+    pub const fn is_well_formed(&self) -> bool {
+        self.well_formed
+    }
+
+    /// The first segment: `config` for `config::invalid_format`; `None`
+    /// unless the code is well-formed.
+    #[must_use]
+    pub fn namespace(&self) -> Option<&str> {
+        self.split().map(|(namespace, _)| namespace)
+    }
+
+    /// Everything after the namespace: `invalid_format` for
+    /// `config::invalid_format`, `io::denied` for `config::io::denied`;
+    /// `None` unless the code is well-formed.
+    #[must_use]
+    pub fn variant(&self) -> Option<&str> {
+        self.split().map(|(_, variant)| variant)
+    }
+
+    /// The code as written.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.code
+    }
+
+    fn split(&self) -> Option<(&str, &str)> {
+        self.code.split_once("::").filter(|_| self.well_formed)
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.code)
+    }
+}
+
+impl Deref for ErrorCode {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.code
+    }
+}
+
+impl AsRef<str> for ErrorCode {
+    fn as_ref(&self) -> &str {
+        &self.code
+    }
+}
+
+impl PartialEq<str> for ErrorCode {
+    fn eq(&self, other: &str) -> bool {
+        self.code == other
+    }
+}
+
+impl PartialEq<&str> for ErrorCode {
+    fn eq(&self, other: &&str) -> bool {
+        self.code == *other
+    }
+}
+
+/// Strict, as [`ErrorCode::new`].
+impl FromStr for ErrorCode {
+    type Err = ErrorCodeError;
+
+    fn from_str(code: &str) -> Result<Self, ErrorCodeError> {
+        Self::new(code)
+    }
+}
+
+/// Keeps the code as is, recording whether it is well-formed.
+impl From<String> for ErrorCode {
+    fn from(code: String) -> Self {
+        let well_formed = validate(&code).is_ok();
+        Self {
+            code,
+            well_formed,
+        }
+    }
+}
+
+impl From<ErrorCode> for String {
+    fn from(code: ErrorCode) -> Self {
+        code.code
+    }
+}
+
+fn validate(code: &str) -> Result<(), ErrorCodeError> {
+    if !code.contains("::") {
+        return Err(ErrorCodeError::MissingSeparator {
+            code: code.to_string(),
+        });
+    }
+    if code
+        .split("::")
+        .any(|segment| segment.is_empty() || segment.contains(char::is_whitespace))
+    {
+        return Err(ErrorCodeError::InvalidSegment {
+            code: code.to_string(),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+/*
+ * ariadne rendering for LibReport (`ariadne` feature).
+ *
+ * An alternative to miette's graphical handler for teams that prefer
+ * ariadne's style. It draws from the same Diagnostic data — message, code,
+ * severity, help, labelled source — so both renderers agree on content.
+ * miette spans are byte offsets, so ariadne is told to index by byte too
+ * (its default counts chars).
+ */
+
+use std::{fmt, ops::Range};
+
+use ariadne::{Config, IndexType, Label, Report, ReportKind, Source};
+use miette::{Diagnostic, LabeledSpan, Severity, SourceCode, SourceSpan};
+
+use crate::LibReport;
+
+/// Source id used when the diagnostic carries no named source.
+const UNNAMED_SOURCE: &str = "<unknown>";
+
+impl<E, O> LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    /// Renders the report through ariadne, with the context's labels drawn
+    /// over its source code.
+    ///
+    /// Without source code (or when it can't be read) the labels are
+    /// listed as notes instead. Pass `ansi = false` for plain output, as
+    /// with [`LibReport::render_pretty`].
+    #[must_use]
+    pub fn render_ariadne(&self, ansi: bool) -> String {
+        let ctx = self.0.current_context();
+        let labels: Vec<_> = ctx.labels().into_iter().flatten().collect();
+        let source = ctx.source_code().and_then(read_all);
+
+        let (name, text) = source.unwrap_or_else(|| (UNNAMED_SOURCE.to_owned(), String::new()));
+        let in_source = |span: &SourceSpan| span.offset() + span.len() <= text.len();
+
+        let first = labels
+            .iter()
+            .map(LabeledSpan::inner)
+            .find(|span| in_source(span))
+            .map_or(0..0, span_range);
+        let mut report = Report::build(kind(self.severity()), (name.clone(), first))
+            .with_config(
+                Config::default()
+                    .with_color(ansi)
+                    .with_index_type(IndexType::Byte),
+            )
+            .with_message(ctx);
+
+        if let Some(code) = self.code() {
+            report = report.with_code(code);
+        }
+        for label in &labels {
+            let text = label.label().unwrap_or_default();
+            if in_source(label.inner()) {
+                report = report.with_label(
+                    Label::new((name.clone(), span_range(label.inner()))).with_message(text),
+                );
+            } else if !text.is_empty() {
+                report = report.with_note(text);
+            }
+        }
+        if let Some(help) = self.help() {
+            report = report.with_help(help);
+        }
+
+        let mut out = Vec::new();
+        // Writing into a Vec cannot fail.
+        let _ = report.finish().write((name, Source::from(text)), &mut out);
+        String::from_utf8_lossy(&out).into_owned()
+    }
+}
+
+/// The whole source as `(name, text)`, with offsets matching the labels.
+fn read_all(source: &dyn SourceCode) -> Option<(String, String)> {
+    let contents = source
+        .read_span(&SourceSpan::from(0..0), usize::MAX, usize::MAX)
+        .ok()?;
+    if contents.span().offset() != 0 {
+        return None;
+    }
+    let name = contents.name().unwrap_or(UNNAMED_SOURCE).to_owned();
+    let text = String::from_utf8_lossy(contents.data()).into_owned();
+    Some((name, text))
+}
+
+const fn span_range(span: &SourceSpan) -> Range<usize> {
+    span.offset()..span.offset() + span.len()
+}
+
+const fn kind(severity: Option<Severity>) -> ReportKind<'static> {
+    match severity {
+        Some(Severity::Warning) => ReportKind::Warning,
+        Some(Severity::Advice) => ReportKind::Advice,
+        Some(Severity::Error) | None => ReportKind::Error,
+    }
+}
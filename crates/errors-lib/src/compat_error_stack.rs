@@ -0,0 +1,35 @@
+/*
+ * error-stack interop (`error-stack` feature).
+ *
+ * rootcause ships the conversions in both directions; these wrappers make
+ * them available on LibReport itself, so callers mixing the two stacks
+ * need not reach for the inner report.
+ */
+
+use rootcause::compat::error_stack06::{IntoErrorStack, IntoRootcause};
+
+use crate::LibReport;
+
+/// Unwraps the rootcause chain into an error-stack report, keeping the
+/// frames in order.
+impl<E> From<LibReport<E>> for error_stack::Report<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn from(report: LibReport<E>) -> Self {
+        report.0.into_error_stack()
+    }
+}
+
+impl<E> LibReport<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Adopts an error-stack report, recording the calling location like
+    /// [`LibReport::new`].
+    #[track_caller]
+    #[must_use]
+    pub fn from_error_stack(report: error_stack::Report<E>) -> Self {
+        Self::new(report.into_rootcause())
+    }
+}
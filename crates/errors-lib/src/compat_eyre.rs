@@ -0,0 +1,44 @@
+/*
+ * color-eyre interop (`color-eyre` feature).
+ *
+ * LibReport<E> -> color_eyre::Report needs no impl here: LibReport is a
+ * std::error::Error, so eyre's blanket `From<E: Error>` applies (and `?`
+ * works in eyre-returning functions). Its `source()` exposes the rootcause
+ * tree, which the eyre handler prints as the "Caused by" chain.
+ *
+ * The other direction recovers an ApiError by reading that chain back.
+ */
+
+use crate::{ApiError, ErrorFrame, build_api_error, retry};
+
+/// Builds an `ApiError` from an eyre report's display chain: the outermost
+/// message becomes the title, each cause a history frame.
+///
+/// eyre reports are untyped, so `code` and `help` are left empty and only
+/// transient io errors in the chain mark it `retryable`. Fails,
+/// handing the report back, when there is no message to use as a title.
+impl TryFrom<color_eyre::Report> for ApiError {
+    type Error = color_eyre::Report;
+
+    fn try_from(report: color_eyre::Report) -> Result<Self, Self::Error> {
+        let title = report.to_string();
+        if title.is_empty() {
+            return Err(report);
+        }
+
+        let history = report
+            .chain()
+            .skip(1)
+            .map(|cause| ErrorFrame {
+                message: cause.to_string(),
+            })
+            .collect();
+
+        let root: &(dyn std::error::Error + 'static) = report.as_ref();
+        let retryable = retry::chain_is_transient_io(Some(root));
+
+        Ok(build_api_error(
+            history, title, None, None, None, retryable, None,
+        ))
+    }
+}
@@ -0,0 +1,37 @@
+/*
+ * GraphQL error extensions (`graphql` feature).
+ *
+ * GraphQL responses carry an error as a `message` plus a free-form
+ * `extensions` object. The title is the message; the code, correlation ID
+ * and history go in the extensions, where clients branch on `code` and
+ * support quotes the ID. Resolvers pass the pair to their server's error
+ * type (async-graphql's `extend_error`, juniper's `FieldError`, ...).
+ */
+
+use serde_json::{Map, Value};
+
+use crate::ApiError;
+
+impl ApiError {
+    /// The GraphQL `message` (the title) and `extensions`: `code` when
+    /// there is one, `correlation_id`, and `history` as a list of strings.
+    #[must_use]
+    pub fn to_graphql_extensions(&self) -> (String, Map<String, Value>) {
+        let mut extensions = Map::new();
+        if let Some(code) = &self.code {
+            extensions.insert("code".to_string(), Value::from(code.as_str()));
+        }
+        extensions.insert(
+            "correlation_id".to_string(),
+            Value::from(self.correlation_id.as_str()),
+        );
+        extensions.insert(
+            "history".to_string(),
+            self.history
+                .iter()
+                .map(|frame| Value::from(frame.message.as_str()))
+                .collect(),
+        );
+        (self.title.clone(), extensions)
+    }
+}
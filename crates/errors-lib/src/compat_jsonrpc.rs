@@ -0,0 +1,80 @@
+/*
+ * JSON-RPC 2.0 error objects (`jsonrpc` feature).
+ *
+ * A JSON-RPC error is `{ code, message, data }` with an integer code, so
+ * diagnostic codes need a numeric mapping. Services register one per code
+ * or namespace at startup; anything unregistered is the spec's generic
+ * server error. The title is the message and the whole ApiError rides in
+ * `data`, so clients still get the string code, correlation ID and history.
+ */
+
+use std::sync::{PoisonError, RwLock};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{ApiError, CodePattern};
+
+/// The code for reports no registration matches: the first of the
+/// implementation-defined server errors (-32000 to -32099).
+pub const JSONRPC_SERVER_ERROR: i64 = -32000;
+
+/// A JSON-RPC 2.0 error object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    pub data: Value,
+}
+
+fn registry() -> &'static RwLock<Vec<(CodePattern, i64)>> {
+    static CODES: RwLock<Vec<(CodePattern, i64)>> = RwLock::new(Vec::new());
+    &CODES
+}
+
+/// Maps diagnostic codes matching `pattern` to the JSON-RPC `code`.
+///
+/// As in [`ExitCodeMap`](crate::ExitCodeMap), a pattern is a
+/// [`CodePattern`]: a whole code (`config::invalid_format`) or a prefix
+/// ending in `::*` (`config::*`); an exact registration beats any prefix
+/// and a longer prefix beats a shorter one. Registering a pattern again
+/// replaces its code.
+pub fn register_jsonrpc_code(pattern: impl Into<CodePattern>, code: i64) {
+    let pattern = pattern.into();
+    let mut codes = registry().write().unwrap_or_else(PoisonError::into_inner);
+    codes.retain(|(existing, _)| *existing != pattern);
+    codes.push((pattern, code));
+}
+
+/// Removes every registration made with [`register_jsonrpc_code`].
+pub fn clear_jsonrpc_codes() {
+    registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+/// The JSON-RPC code registered for a diagnostic code, else
+/// [`JSONRPC_SERVER_ERROR`].
+#[must_use]
+pub fn jsonrpc_code_for(code: Option<&str>) -> i64 {
+    let codes = registry().read().unwrap_or_else(PoisonError::into_inner);
+    code.and_then(|code| CodePattern::best(codes.iter(), code))
+        .copied()
+        .unwrap_or(JSONRPC_SERVER_ERROR)
+}
+
+impl ApiError {
+    /// The JSON-RPC error object: the registered numeric code (see
+    /// [`register_jsonrpc_code`]), the title as `message`, and this
+    /// `ApiError` as `data`.
+    #[must_use]
+    pub fn to_jsonrpc_error(&self) -> JsonRpcError {
+        JsonRpcError {
+            code: jsonrpc_code_for(self.code.as_deref()),
+            message: self.title.clone(),
+            // ApiError's fields all serialize infallibly.
+            data: serde_json::to_value(self).unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+/*
+ * OpenTelemetry log records (`opentelemetry` feature).
+ *
+ * OTEL log records are created by the Logger that will emit them, so this
+ * fills one in rather than building it standalone: the title is the body,
+ * the level follows the ApiError's severity, and the fields support
+ * searches on (code, docs link, build hash, correlation ID, history)
+ * become attributes.
+ */
+
+use opentelemetry::logs::{AnyValue, LogRecord, Severity};
+use tracing::Level;
+
+use crate::ApiError;
+
+impl ApiError {
+    /// Fills `record` from this error: the title as body, severity text and
+    /// number from [`ApiError::level`], and `code` (when present),
+    /// `docs_url`, `git_hash`, `correlation_id` and `history` (a list of
+    /// strings) as attributes.
+    pub fn fill_log_record<R: LogRecord>(&self, record: &mut R) {
+        let (text, number) = match self.level() {
+            Level::WARN => ("WARN", Severity::Warn),
+            Level::INFO => ("INFO", Severity::Info),
+            Level::DEBUG => ("DEBUG", Severity::Debug),
+            Level::TRACE => ("TRACE", Severity::Trace),
+            Level::ERROR => ("ERROR", Severity::Error),
+        };
+        record.set_severity_text(text);
+        record.set_severity_number(number);
+        record.set_body(AnyValue::from(self.title.clone()));
+
+        if let Some(code) = &self.code {
+            record.add_attribute("code", code.to_string());
+        }
+        record.add_attribute("docs_url", self.docs_url.to_string());
+        record.add_attribute("git_hash", self.git_hash.to_string());
+        record.add_attribute("correlation_id", self.correlation_id.clone());
+        record.add_attribute(
+            "history",
+            AnyValue::ListAny(Box::new(
+                self.history
+                    .iter()
+                    .map(|frame| AnyValue::from(frame.message.clone()))
+                    .collect(),
+            )),
+        );
+    }
+}
@@ -0,0 +1,164 @@
+/*
+ * Sentry interop (`sentry` feature).
+ *
+ * An ApiError already carries everything a Sentry event needs: the title
+ * is the message, the code and correlation ID become searchable tags, and
+ * the history reads naturally as breadcrumbs leading up to the failure.
+ * The code also serves as the fingerprint, so Sentry groups issues by code
+ * rather than by message text, and the git hash identifies the release.
+ *
+ * Sentry event IDs are UUIDs while correlation IDs are short nanoids, so
+ * the correlation ID travels as a tag instead of replacing the event ID.
+ *
+ * From a report itself the event can say more: every node becomes an
+ * entry in the exception chain, typed by its context, and only the
+ * attachments are left over for breadcrumbs.
+ */
+
+use std::{any::type_name, borrow::Cow, io};
+
+use rootcause::{
+    Report, ReportRef,
+    markers::{Dynamic, Uncloneable},
+};
+use sentry::{
+    Client, Level,
+    protocol::{Breadcrumb, Event, Exception},
+    types::Uuid,
+};
+
+use crate::{ApiError, ApiErrorOptions, ReportExt, messages, walk::nodes_with_depth};
+
+/// Names a node's context type for a Sentry exception.
+pub type ContextTypeName = fn(ReportRef<'_, Dynamic, Uncloneable>) -> &'static str;
+
+/// Builds the `ApiError` for `report`, without emitting it to the sinks,
+/// and captures it on `client`, returning the event ID (nil when the
+/// client is disabled).
+#[must_use]
+pub fn capture_to_sentry(report: &impl ReportExt, client: &Client) -> Uuid {
+    let api_err = report.build_api_error_with_options(ApiErrorOptions::default());
+    client.capture_event(api_err.to_sentry_event(), None)
+}
+
+/// Sends [`ReportExt::to_sentry_event`] through the current Sentry hub and
+/// returns the event ID (nil when Sentry is disabled).
+#[must_use]
+pub fn capture_lib_report(report: &impl ReportExt) -> Uuid {
+    sentry::capture_event(report.to_sentry_event())
+}
+
+/// `E` for contexts of type `E`, `std::io::Error` for io errors, else
+/// `Error`.
+pub fn context_type_name<E: 'static>(node: ReportRef<'_, Dynamic, Uncloneable>) -> &'static str {
+    if node.downcast_current_context::<E>().is_some() {
+        type_name::<E>()
+    } else {
+        untyped_context_type_name(node)
+    }
+}
+
+/// `std::io::Error` for io errors, else `Error`: an untyped report can't
+/// name its contexts' types.
+pub fn untyped_context_type_name(node: ReportRef<'_, Dynamic, Uncloneable>) -> &'static str {
+    if node.downcast_current_context::<io::Error>().is_some() {
+        type_name::<io::Error>()
+    } else {
+        "Error"
+    }
+}
+
+/// [`ApiError::to_sentry_event`] for `api_err`, with `report`'s nodes as
+/// the exception chain and its attachments as the breadcrumbs.
+///
+/// Sentry lists a chain oldest first, so the deepest node comes first and
+/// the top-level one, whose exception Sentry titles the issue with, last.
+/// Breadcrumbs follow the same order.
+pub fn report_event<C: ?Sized, O>(
+    api_err: &ApiError,
+    report: &Report<C, O>,
+    type_name: ContextTypeName,
+) -> Event<'static> {
+    let mut event = api_err.to_sentry_event();
+    let nodes = nodes_with_depth(report);
+
+    event.exception = nodes
+        .iter()
+        .rev()
+        .map(|&(_, node)| Exception {
+            ty: type_name(node).to_string(),
+            value: Some(node.format_current_context().to_string()),
+            ..Exception::default()
+        })
+        .collect::<Vec<_>>()
+        .into();
+    event.breadcrumbs = nodes
+        .iter()
+        .rev()
+        .flat_map(|&(_, node)| messages(node))
+        .map(|message| Breadcrumb {
+            category: Some("attachment".into()),
+            level: event.level,
+            message: Some(message),
+            ..Breadcrumb::default()
+        })
+        .collect::<Vec<_>>()
+        .into();
+    event
+}
+
+impl ApiError {
+    /// The Sentry level matching `severity`: `Warning` for warnings,
+    /// `Info` for advice, `Error` otherwise.
+    #[must_use]
+    pub fn sentry_level(&self) -> Level {
+        match self.severity.as_deref() {
+            Some("warning") => Level::Warning,
+            Some("advice") => Level::Info,
+            _ => Level::Error,
+        }
+    }
+
+    /// Builds a Sentry event: `title` as the message, `code` and
+    /// `correlation_id` as tags, `code` as the fingerprint, `git_hash` as the
+    /// release and one breadcrumb per history frame.
+    #[must_use]
+    pub fn to_sentry_event(&self) -> Event<'static> {
+        let level = self.sentry_level();
+
+        let mut event = Event {
+            level,
+            message: Some(self.title.clone()),
+            release: Some(self.git_hash.clone()),
+            ..Event::default()
+        };
+        if let Some(code) = &self.code {
+            event.fingerprint = Cow::Owned(vec![Cow::Owned(code.to_string())]);
+        }
+        event
+            .tags
+            .insert("correlation_id".into(), self.correlation_id.clone());
+        if let Some(code) = &self.code {
+            event.tags.insert("code".into(), code.to_string());
+        }
+        event.breadcrumbs = self
+            .history
+            .iter()
+            .map(|frame| Breadcrumb {
+                category: Some("history".into()),
+                level,
+                message: Some(frame.message.clone()),
+                ..Breadcrumb::default()
+            })
+            .collect::<Vec<_>>()
+            .into();
+        event
+    }
+
+    /// Sends [`to_sentry_event`](Self::to_sentry_event) through the current
+    /// Sentry hub and returns the event ID (nil when Sentry is disabled).
+    #[must_use]
+    pub fn capture_to_sentry(&self) -> Uuid {
+        sentry::capture_event(self.to_sentry_event())
+    }
+}
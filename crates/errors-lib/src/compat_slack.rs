@@ -0,0 +1,118 @@
+/*
+ * Slack Block Kit messages (`slack` feature).
+ *
+ * On-call alerts post errors to a Slack incoming webhook. The payload is a
+ * Block Kit message: a header with the title, a section with code and
+ * help, the history as a bulleted list, and a context line with the build
+ * and correlation ID. `text` repeats the title for notifications and
+ * clients that can't render blocks.
+ *
+ * Slack reads `<…>` in mrkdwn as a link or mention, so a title like
+ * "<!channel> …" would page the whole channel. Every mrkdwn string has its
+ * `&`, `<` and `>` escaped; the header is plain text and is left alone.
+ */
+
+use serde_json::{Value, json};
+
+use crate::ApiError;
+
+/// Slack rejects header text longer than this.
+const HEADER_MAX_CHARS: usize = 150;
+/// Slack rejects section text longer than this.
+const SECTION_MAX_CHARS: usize = 3000;
+
+impl ApiError {
+    /// A Slack webhook payload in Block Kit form:
+    ///
+    /// - a `header` block with the title,
+    /// - a `section` block with the code and help, when either is set,
+    /// - a `section` block listing the history, when there is any,
+    /// - a `context` block with `git_hash` and `correlation_id`.
+    ///
+    /// Text over Slack's per-block limits is cut short with "…". `&`, `<`
+    /// and `>` are escaped in every mrkdwn string, `text` included.
+    #[must_use]
+    pub fn to_slack_message(&self) -> Value {
+        let mut blocks = vec![json!({
+            "type": "header",
+            "text": plain_text(&truncate(&self.title, HEADER_MAX_CHARS)),
+        })];
+
+        let mut details = Vec::new();
+        if let Some(code) = &self.code {
+            details.push(format!("*Code:* `{code}`"));
+        }
+        if let Some(help) = &self.help {
+            details.push(format!("*Help:* {help}"));
+        }
+        if !details.is_empty() {
+            blocks.push(section(&details.join("\n")));
+        }
+
+        if !self.history.is_empty() {
+            let items: Vec<_> = self
+                .history
+                .iter()
+                .map(|frame| format!("• {}", frame.message))
+                .collect();
+            blocks.push(section(&format!("*History*\n{}", items.join("\n"))));
+        }
+
+        blocks.push(json!({
+            "type": "context",
+            "elements": [
+                mrkdwn(&format!("*Build:* `{}`", self.git_hash)),
+                mrkdwn(&format!("*Correlation ID:* `{}`", self.correlation_id)),
+            ],
+        }));
+
+        json!({
+            "text": escape(&self.title),
+            "blocks": blocks,
+        })
+    }
+}
+
+fn plain_text(text: &str) -> Value {
+    json!({ "type": "plain_text", "text": text })
+}
+
+/// A mrkdwn text object for `text`, escaped and then cut to the section
+/// limit.
+fn mrkdwn(text: &str) -> Value {
+    let mut text = truncate(&escape(text), SECTION_MAX_CHARS);
+    // Every `&` is now the start of an entity; don't leave half of one
+    // before the "…".
+    if let Some(cut) = text.strip_suffix('…').map(str::len)
+        && let Some(amp) = text[..cut].rfind('&')
+        && !text[amp..cut].contains(';')
+    {
+        text.replace_range(amp..cut, "");
+    }
+    json!({ "type": "mrkdwn", "text": text })
+}
+
+fn section(text: &str) -> Value {
+    json!({
+        "type": "section",
+        "text": mrkdwn(text),
+    })
+}
+
+/// `text` with the characters Slack treats as control sequences in mrkdwn
+/// replaced by their entities.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// `text` cut to at most `max` characters, ending in "…" when cut.
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max - 1).collect();
+    cut.push('…');
+    cut
+}
@@ -0,0 +1,110 @@
+/*
+ * Per-namespace docs links.
+ *
+ * By default every code links into ERROR_DOCS_URL, or the base set with
+ * set_docs_url_override() for consumers hosting their own docs without
+ * rebuilding. Namespaces documented elsewhere (an internal wiki, another
+ * crate's docs) register an ErrorDocs resolver under a CodePattern;
+ * Diagnostic::url() and ApiError::code_url consult the registry first.
+ * ApiError::docs_url stays the base. A registry rather than a trait bound
+ * on the context keeps this working without specialization, for any
+ * report type.
+ */
+
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+use crate::{CodePattern, code_to_anchor};
+
+/// Resolves the docs link for codes in a namespace.
+///
+/// Implemented for `&'static str` and `String` as a base URL, linking to
+/// `{base}/#{anchor}` (see [`code_to_anchor`]); implement it directly for
+/// sites with a different layout.
+pub trait ErrorDocs {
+    /// The link for `code`, or `None` to fall back to the default docs.
+    fn docs_url(&self, code: &str) -> Option<String>;
+}
+
+impl ErrorDocs for &'static str {
+    fn docs_url(&self, code: &str) -> Option<String> {
+        Some(format!("{self}/#{}", code_to_anchor(code)))
+    }
+}
+
+impl ErrorDocs for String {
+    fn docs_url(&self, code: &str) -> Option<String> {
+        Some(format!("{self}/#{}", code_to_anchor(code)))
+    }
+}
+
+type Resolvers = Vec<(CodePattern, Arc<dyn ErrorDocs + Send + Sync>)>;
+
+fn resolvers() -> &'static RwLock<Resolvers> {
+    static RESOLVERS: OnceLock<RwLock<Resolvers>> = OnceLock::new();
+    RESOLVERS.get_or_init(RwLock::default)
+}
+
+/// Routes codes matching `pattern` (`config::*` covers
+/// `config::invalid_format` and `config::io::denied`) to `docs`, replacing
+/// any earlier registration for the same pattern. The most specific
+/// pattern wins; see [`CodePattern`].
+pub fn register_error_docs(
+    pattern: impl Into<CodePattern>,
+    docs: impl ErrorDocs + Send + Sync + 'static,
+) {
+    let pattern = pattern.into();
+    let mut resolvers = resolvers().write().unwrap_or_else(PoisonError::into_inner);
+    resolvers.retain(|(registered, _)| *registered != pattern);
+    resolvers.push((pattern, Arc::new(docs)));
+}
+
+/// Removes every registration made with [`register_error_docs`].
+pub fn clear_error_docs() {
+    resolvers()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+fn base_override() -> &'static RwLock<Option<&'static str>> {
+    static OVERRIDE: OnceLock<RwLock<Option<&'static str>>> = OnceLock::new();
+    OVERRIDE.get_or_init(RwLock::default)
+}
+
+/// Replaces the compile-time `ERROR_DOCS_URL` for every later link and
+/// [`ApiError`](crate::ApiError).
+pub fn set_docs_url_override(url: &'static str) {
+    *base_override()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = Some(url);
+}
+
+/// Undoes [`set_docs_url_override`], restoring `ERROR_DOCS_URL`.
+pub fn clear_docs_url_override() {
+    *base_override()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// The base docs URL: the override if one is set, else `ERROR_DOCS_URL`.
+#[must_use]
+pub fn docs_base_url() -> &'static str {
+    base_override()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .unwrap_or(env!("ERROR_DOCS_URL"))
+}
+
+/// The registered link for `code`, if a pattern covers it.
+pub fn registered_docs_url(code: &str) -> Option<String> {
+    let resolvers = resolvers().read().unwrap_or_else(PoisonError::into_inner);
+    CodePattern::best(resolvers.iter(), code).and_then(|docs| docs.docs_url(code))
+}
+
+/// The link for `code`: the registered one, else under
+/// [`docs_base_url`].
+#[must_use]
+pub fn docs_url_for(code: &str) -> String {
+    registered_docs_url(code)
+        .unwrap_or_else(|| format!("{}/#{}", docs_base_url(), code_to_anchor(code)))
+}
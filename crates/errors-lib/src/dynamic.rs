@@ -0,0 +1,298 @@
+/*
+ * DynLibReport: LibReport for chains whose top-level type doesn't matter.
+ *
+ * rootcause's untyped `Report` carries any mix of contexts. This wrapper
+ * gives it the same framework integration as LibReport<E> (miette
+ * rendering, ApiError conversion), degrading to `Display` output for
+ * nodes it cannot recognise as a Diagnostic. DynError boxes any Diagnostic
+ * behind one type, so reports built with from_error keep their codes and
+ * help whatever the original error type was.
+ */
+
+use std::{fmt, io};
+
+use miette::{Diagnostic, SourceCode};
+use rootcause::{
+    Report,
+    markers::{Cloneable, Mutable},
+};
+
+#[cfg(feature = "sentry")]
+use crate::compat_sentry;
+#[cfg(feature = "span-trace")]
+use crate::span_trace;
+use crate::{
+    ApiError, ApiErrorOptions, DiagnosticCache, DiagnosticView, ErrorTree, LibReport, ReportExt,
+    TopLevel, api_error_of, attachments, docs_link, exit_code, fmt_chain, related_iter, retry,
+    view_none,
+};
+
+/// Recovers the top-level context of an untyped report as a Diagnostic.
+type RootView = for<'a> fn(&'a Report) -> Option<&'a dyn Diagnostic>;
+
+/// A miette-compatible wrapper around an untyped rootcause error chain.
+///
+/// Build one from any `Report` (code and help are then unavailable) or
+/// from [`LibReport::into_dynamic`](crate::LibReport::into_dynamic), which
+/// remembers the original context type so its diagnostics survive.
+pub struct DynLibReport(pub Report, Views, DiagnosticCache);
+
+/// How to see typed contexts through the erased tree.
+struct Views {
+    root: RootView,
+    children: DiagnosticView,
+}
+
+impl DynLibReport {
+    /// Wraps an untyped rootcause report for framework integration.
+    #[must_use]
+    pub const fn new(report: Report) -> Self {
+        Self::with_views(report, view_none::<Mutable>, view_none::<Cloneable>)
+    }
+
+    /// A report whose context is `err` behind a [`DynError`], so errors of
+    /// different types can share one queue or channel and still keep
+    /// their codes, help and labels.
+    #[must_use]
+    #[track_caller]
+    pub fn from_error<E: Diagnostic + Send + Sync + 'static>(err: E) -> Self {
+        LibReport::new(Report::new(DynError::new(err))).into_dynamic()
+    }
+
+    pub(crate) const fn with_views(
+        report: Report,
+        root: RootView,
+        children: DiagnosticView,
+    ) -> Self {
+        Self(
+            report,
+            Views {
+                root,
+                children,
+            },
+            DiagnosticCache::new(),
+        )
+    }
+
+    pub(crate) fn context(&self) -> Option<&dyn Diagnostic> {
+        (self.1.root)(&self.0)
+    }
+
+    /// How child nodes are seen as diagnostics.
+    pub(crate) const fn children_view(&self) -> DiagnosticView {
+        self.1.children
+    }
+
+    /// The report as an [`ErrorTree`], preserving parent/child structure.
+    #[must_use]
+    pub fn to_error_tree(&self) -> ErrorTree {
+        let code = self
+            .context()
+            .and_then(Diagnostic::code)
+            .map(|c| c.to_string());
+        ErrorTree::build(&self.0, code, self.1.children)
+    }
+}
+
+impl From<Report> for DynLibReport {
+    fn from(report: Report) -> Self {
+        Self::new(report)
+    }
+}
+
+impl fmt::Debug for DynLibReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DynLibReport").field(&self.0).finish()
+    }
+}
+
+/// As for [`LibReport`]: `{:#}` lists the history line by line.
+impl fmt::Display for DynLibReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            fmt_chain(&self.0, f)
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+/// Any `Diagnostic`, boxed. Implements `Diagnostic` and `Error` by
+/// delegating to the boxed error.
+pub struct DynError(Box<dyn Diagnostic + Send + Sync>);
+
+impl DynError {
+    /// Boxes `err`.
+    #[must_use]
+    pub fn new<E: Diagnostic + Send + Sync + 'static>(err: E) -> Self {
+        Self(Box::new(err))
+    }
+
+    /// The boxed error.
+    #[must_use]
+    pub fn into_inner(self) -> Box<dyn Diagnostic + Send + Sync> {
+        self.0
+    }
+}
+
+impl From<Box<dyn Diagnostic + Send + Sync>> for DynError {
+    fn from(err: Box<dyn Diagnostic + Send + Sync>) -> Self {
+        Self(err)
+    }
+}
+
+impl fmt::Debug for DynError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for DynError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl std::error::Error for DynError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+impl Diagnostic for DynError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.0.code()
+    }
+
+    fn severity(&self) -> Option<miette::Severity> {
+        self.0.severity()
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.0.help()
+    }
+
+    fn url<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.0.url()
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        self.0.source_code()
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        self.0.labels()
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        self.0.related()
+    }
+
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        self.0.diagnostic_source()
+    }
+}
+
+/// Result alias for functions that carry a chain without naming its type.
+pub type DynLibResult<T> = std::result::Result<T, DynLibReport>;
+
+impl Diagnostic for DynLibReport {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.context()?.code()
+    }
+
+    fn severity(&self) -> Option<miette::Severity> {
+        attachments::severity_override(&self.0).or_else(|| self.context()?.severity())
+    }
+
+    /// As for [`LibReport`], with the span trace after the help.
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        let help = self.context().and_then(Diagnostic::help);
+        #[cfg(feature = "span-trace")]
+        let help = span_trace::help_with_trace(help, &self.0);
+        help
+    }
+
+    fn url<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.code().and_then(|c| docs_link(&c))
+    }
+
+    /// The context's source code, else the first a recognised descendant
+    /// carries, searching depth-first.
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        self.context()
+            .and_then(Diagnostic::source_code)
+            .or_else(|| {
+                self.2
+                    .descendant_source_code(self.0.children(), self.1.children)
+            })
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        self.context()?.labels()
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        related_iter(self.2.children(self.0.children(), self.1.children))
+    }
+
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        self.2
+            .diagnostic_source(self.0.children(), self.1.children, || {
+                self.0.current_context_error_source()
+            })
+    }
+}
+
+impl std::error::Error for DynLibReport {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.2.error_source(
+            self.0.children(),
+            self.1.children,
+            self.0.current_context_error_source(),
+        )
+    }
+}
+
+impl ReportExt for DynLibReport {
+    fn build_api_error_with_options(&self, options: ApiErrorOptions) -> ApiError {
+        let ctx = self.context();
+        let top = TopLevel {
+            title: self.0.format_current_context().to_string(),
+            code: ctx.and_then(Diagnostic::code).map(|c| c.to_string()),
+            help: ctx.and_then(Diagnostic::help).map(|h| h.to_string()),
+            severity: self.reported_severity(),
+            retryable: self.is_retryable(),
+        };
+        api_error_of(&self.0, top, self.1.children, options)
+    }
+
+    fn is_retryable(&self) -> bool {
+        retry::node_is_retryable(
+            self.context(),
+            self.0.downcast_current_context::<io::Error>(),
+            self.0.current_context_error_source(),
+        ) || self
+            .0
+            .children()
+            .iter()
+            .any(|child| retry::child_is_retryable(&child.clone_arc(), self.1.children))
+            || retry::retry_hint_of(&self.0).is_some()
+    }
+
+    /// Contexts are typed only as `std::io::Error` or `Error`: the erased
+    /// tree doesn't know the original context type's name.
+    #[cfg(feature = "sentry")]
+    fn to_sentry_event(&self) -> sentry::protocol::Event<'static> {
+        compat_sentry::report_event(
+            &self.build_api_error_with_options(ApiErrorOptions::default()),
+            &self.0,
+            compat_sentry::untyped_context_type_name,
+        )
+    }
+
+    fn process_exit_code(&self) -> i32 {
+        let code = self.code().map(|code| code.to_string());
+        exit_code::process_status(code.as_deref())
+    }
+}
@@ -0,0 +1,414 @@
+/*
+ * How ApiErrors are emitted to tracing.
+ *
+ * The event's target and field names used to be fixed by the tracing
+ * macros (`errors_lib::report`, `hash`, `docs`, `id`, ...), which clashes
+ * with platforms that reserve some of those names. EmitConfig makes them
+ * settings, and can keep the history out of the log line.
+ *
+ * tracing wants every callsite's metadata, target and field names
+ * included, to be 'static, and the macros can only build that from
+ * literals. So the callsites here are built by hand, once per level, and
+ * leaked: set_emit_config is meant to be called once at startup, and each
+ * call leaks one small set of callsites.
+ *
+ * Pairs attached with attach_field are emitted as fields of their own, so
+ * they can be queried by key. Their names aren't known up front either, so
+ * each distinct set of keys gets its callsites the same way, on first use.
+ * ApiError::fields is plain data, though, and may come from anywhere, so
+ * only keys already 'static are promoted: those passed to attach_field or
+ * register_field_key. The number of key sets with callsites is capped too;
+ * past it, every pair goes into the `fields` field.
+ */
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::{OnceLock, PoisonError, RwLock},
+};
+
+use tracing::{
+    Event, Level, Metadata,
+    field::{Field, Value, debug, display},
+};
+use tracing_core::{callsite::Callsite, identify_callsite, metadata::Kind, subscriber::Interest};
+
+use crate::ApiError;
+
+/// The target events had before it became configurable.
+pub const DEFAULT_EMIT_TARGET: &str = "errors_lib::report";
+
+const MESSAGE: &str = "Internal error reported to API sink";
+
+/// The fields every event has, `message` included.
+const BASE_FIELDS: usize = 12;
+
+/// At most this many attached pairs become fields of their own; the rest go
+/// into the `fields` field.
+const MAX_OWN_FIELDS: usize = 16;
+
+/// At most this many sets of keys get callsites of their own, across levels.
+const MAX_KEYED_CALLSITES: usize = 64;
+
+/// The field names of the emitted event, and of
+/// [`ApiError::to_log_fields`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldNames {
+    pub hash: &'static str,
+    pub docs: &'static str,
+    pub id: &'static str,
+    pub title: &'static str,
+    pub code: &'static str,
+    pub location: &'static str,
+    pub trace_id: &'static str,
+    pub span_id: &'static str,
+    /// Attached pairs that don't get a field of their own: those whose key
+    /// isn't registered (see [`register_field_key`]), those past the first
+    /// 16, and those whose key is another field's name.
+    pub fields: &'static str,
+    pub extra: &'static str,
+    pub history: &'static str,
+}
+
+impl FieldNames {
+    /// `hash`, `docs`, `id`, `title`, `code`, `location`, `trace_id`,
+    /// `span_id`, `fields`, `extra` and `history`.
+    pub const DEFAULT: Self = Self {
+        hash: "hash",
+        docs: "docs",
+        id: "id",
+        title: "title",
+        code: "code",
+        location: "location",
+        trace_id: "trace_id",
+        span_id: "span_id",
+        fields: "fields",
+        extra: "extra",
+        history: "history",
+    };
+
+    /// The event's field names, `message` first.
+    const fn event_fields(&self) -> [&'static str; BASE_FIELDS] {
+        [
+            "message",
+            self.hash,
+            self.docs,
+            self.id,
+            self.title,
+            self.code,
+            self.location,
+            self.trace_id,
+            self.span_id,
+            self.fields,
+            self.extra,
+            self.history,
+        ]
+    }
+}
+
+impl Default for FieldNames {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// How [`ApiError::log_to_tracing`] emits; see [`set_emit_config`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmitConfig {
+    /// The event's target, for filtering (`RUST_LOG=my_target=error`).
+    pub target: &'static str,
+    pub field_names: FieldNames,
+    /// Whether the history goes into the log line. The returned
+    /// `ApiError` keeps it either way.
+    pub include_history: bool,
+}
+
+impl EmitConfig {
+    /// [`DEFAULT_EMIT_TARGET`], [`FieldNames::DEFAULT`] and the history
+    /// included.
+    pub const DEFAULT: Self = Self {
+        target: DEFAULT_EMIT_TARGET,
+        field_names: FieldNames::DEFAULT,
+        include_history: true,
+    };
+}
+
+impl Default for EmitConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// An event callsite built at runtime.
+struct EmitCallsite {
+    metadata: OnceLock<Metadata<'static>>,
+}
+
+impl Callsite for EmitCallsite {
+    fn set_interest(&self, _: Interest) {}
+
+    fn metadata(&self) -> &Metadata<'_> {
+        self.metadata.get().expect("set when the callsite is built")
+    }
+}
+
+impl EmitCallsite {
+    fn leak(target: &'static str, level: Level, names: &'static [&'static str]) -> &'static Self {
+        let callsite: &'static Self = Box::leak(Box::new(Self {
+            metadata: OnceLock::new(),
+        }));
+        let _ = callsite.metadata.set(Metadata::new(
+            concat!("event ", file!(), ":", line!()),
+            target,
+            level,
+            Some(file!()),
+            Some(line!()),
+            Some(module_path!()),
+            tracing::field::FieldSet::new(names, identify_callsite!(callsite)),
+            Kind::EVENT,
+        ));
+        tracing_core::callsite::register(callsite);
+        callsite
+    }
+}
+
+/// A config with its callsites, one per level, plus one per level and set
+/// of attached keys.
+struct Emitter {
+    config: EmitConfig,
+    callsites: [&'static EmitCallsite; 5],
+    keyed: RwLock<HashMap<(usize, Vec<&'static str>), &'static EmitCallsite>>,
+}
+
+impl Emitter {
+    fn leak(config: EmitConfig) -> &'static Self {
+        let names: &'static [&'static str] = Box::leak(Box::new(config.field_names.event_fields()));
+        let callsite = |level| EmitCallsite::leak(config.target, level, names);
+        Box::leak(Box::new(Self {
+            config,
+            callsites: [
+                callsite(Level::ERROR),
+                callsite(Level::WARN),
+                callsite(Level::INFO),
+                callsite(Level::DEBUG),
+                callsite(Level::TRACE),
+            ],
+            keyed: RwLock::new(HashMap::new()),
+        }))
+    }
+
+    const fn callsite(&self, level: Level) -> &'static EmitCallsite {
+        self.callsites[level_index(level)]
+    }
+
+    /// The callsite whose fields are the usual ones followed by `keys`;
+    /// `None` for a new set of keys once [`MAX_KEYED_CALLSITES`] sets have
+    /// one.
+    fn keyed_callsite(
+        &self,
+        level: Level,
+        keys: Vec<&'static str>,
+    ) -> Option<&'static EmitCallsite> {
+        if keys.is_empty() {
+            return Some(self.callsite(level));
+        }
+        let key = (level_index(level), keys);
+        if let Some(&callsite) = self
+            .keyed
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+        {
+            return Some(callsite);
+        }
+        let mut keyed = self.keyed.write().unwrap_or_else(PoisonError::into_inner);
+        if keyed.len() >= MAX_KEYED_CALLSITES && !keyed.contains_key(&key) {
+            return None;
+        }
+        Some(*keyed.entry(key).or_insert_with_key(|(_, keys)| {
+            let names: Vec<&'static str> = self
+                .config
+                .field_names
+                .event_fields()
+                .into_iter()
+                .chain(keys.iter().copied())
+                .collect();
+            EmitCallsite::leak(
+                self.config.target,
+                level,
+                Box::leak(names.into_boxed_slice()),
+            )
+        }))
+    }
+}
+
+const fn level_index(level: Level) -> usize {
+    match level {
+        Level::ERROR => 0,
+        Level::WARN => 1,
+        Level::INFO => 2,
+        Level::DEBUG => 3,
+        Level::TRACE => 4,
+    }
+}
+
+fn field_keys() -> &'static RwLock<BTreeSet<&'static str>> {
+    static KEYS: RwLock<BTreeSet<&'static str>> = RwLock::new(BTreeSet::new());
+    &KEYS
+}
+
+/// Lets `ApiError::fields` entries under `key` be emitted as a field of
+/// their own, for errors whose fields weren't attached with
+/// [`LibReport::attach_field`](crate::LibReport::attach_field), which
+/// registers its keys itself: built by hand or deserialized from
+/// upstream. Other keys go into [`FieldNames::fields`].
+pub fn register_field_key(key: &'static str) {
+    if field_keys()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .contains(key)
+    {
+        return;
+    }
+    field_keys()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(key);
+}
+
+/// `key` as registered with [`register_field_key`].
+fn registered_key(key: &str) -> Option<&'static str> {
+    field_keys()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(key)
+        .copied()
+}
+
+/// `error.fields` split into the pairs emitted under their own key, in key
+/// order, and the rest; see [`FieldNames::fields`].
+#[must_use]
+pub fn split_fields<'a>(
+    error: &'a ApiError,
+    names: &FieldNames,
+) -> (Vec<(&'static str, &'a String)>, BTreeMap<&'a str, &'a str>) {
+    let reserved = names.event_fields();
+    let mut own = Vec::new();
+    let mut rest = BTreeMap::new();
+    for (key, value) in &error.fields {
+        let promote = own.len() < MAX_OWN_FIELDS && !reserved.contains(&key.as_str());
+        match registered_key(key).filter(|_| promote) {
+            Some(own_key) => own.push((own_key, value)),
+            None => {
+                rest.insert(key.as_str(), value.as_str());
+            },
+        }
+    }
+    (own, rest)
+}
+
+fn custom() -> &'static RwLock<Option<&'static Emitter>> {
+    static CUSTOM: RwLock<Option<&'static Emitter>> = RwLock::new(None);
+    &CUSTOM
+}
+
+fn emitter() -> &'static Emitter {
+    static DEFAULT: OnceLock<&'static Emitter> = OnceLock::new();
+    let custom = *custom().read().unwrap_or_else(PoisonError::into_inner);
+    custom.unwrap_or_else(|| DEFAULT.get_or_init(|| Emitter::leak(EmitConfig::DEFAULT)))
+}
+
+/// Replaces the target, field names and history inclusion of every
+/// emitted `ApiError` from now on.
+///
+/// ```
+/// use errors_lib::{EmitConfig, FieldNames, set_emit_config};
+///
+/// set_emit_config(EmitConfig {
+///     target: "api_errors",
+///     field_names: FieldNames {
+///         id: "correlation_id",
+///         ..FieldNames::DEFAULT
+///     },
+///     include_history: false,
+/// });
+/// ```
+pub fn set_emit_config(config: EmitConfig) {
+    let emitter = Emitter::leak(config);
+    *custom().write().unwrap_or_else(PoisonError::into_inner) = Some(emitter);
+}
+
+/// Restores [`EmitConfig::DEFAULT`].
+pub fn clear_emit_config() {
+    *custom().write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// The config in effect.
+#[must_use]
+pub fn emit_config() -> EmitConfig {
+    emitter().config
+}
+
+/// Emits `error` as an event at `level` through the current dispatcher.
+pub fn dispatch(error: &ApiError, level: Level) {
+    let emitter = emitter();
+    let (mut own, mut rest) = split_fields(error, &emitter.config.field_names);
+    let keys = own.iter().map(|&(key, _)| key).collect();
+    let callsite = emitter.keyed_callsite(level, keys).unwrap_or_else(|| {
+        rest.extend(own.drain(..).map(|(key, value)| (key, value.as_str())));
+        emitter.callsite(level)
+    });
+    let metadata = callsite.metadata();
+    tracing::dispatcher::get_default(|dispatch| {
+        if !dispatch.enabled(metadata) {
+            return;
+        }
+        let include_history = emitter.config.include_history;
+        let fields: Vec<Field> = metadata.fields().iter().collect();
+
+        let message = display(MESSAGE);
+        let hash = display(&error.git_hash);
+        let docs = display(&error.docs_url);
+        let id = display(&error.correlation_id);
+        let title = display(error.log_title());
+        let code = error.code.as_deref();
+        let location = error.location.as_deref();
+        let trace_id = error.trace_id.as_deref();
+        let span_id = error.span_id.as_deref();
+        let rest_fields = debug(&rest);
+        let extra = debug(&error.extra);
+        let history = debug(error.history_messages());
+
+        // Slots past the attached pairs repeat `message` with no value,
+        // which records nothing.
+        let mut values: [(&Field, Option<&dyn Value>); BASE_FIELDS + MAX_OWN_FIELDS] =
+            [(&fields[0], None); BASE_FIELDS + MAX_OWN_FIELDS];
+        let base: [(&Field, Option<&dyn Value>); BASE_FIELDS] = [
+            (&fields[0], Some(&message)),
+            (&fields[1], Some(&hash)),
+            (&fields[2], Some(&docs)),
+            (&fields[3], Some(&id)),
+            (&fields[4], Some(&title)),
+            (&fields[5], code.as_ref().map(|v| v as &dyn Value)),
+            (&fields[6], location.as_ref().map(|v| v as &dyn Value)),
+            (&fields[7], trace_id.as_ref().map(|v| v as &dyn Value)),
+            (&fields[8], span_id.as_ref().map(|v| v as &dyn Value)),
+            (
+                &fields[9],
+                (!rest.is_empty()).then_some(&rest_fields as &dyn Value),
+            ),
+            (&fields[10], Some(&extra)),
+            (
+                &fields[11],
+                include_history.then_some(&history as &dyn Value),
+            ),
+        ];
+        values[..BASE_FIELDS].copy_from_slice(&base);
+        for (slot, (field, (_, value))) in values[BASE_FIELDS..]
+            .iter_mut()
+            .zip(fields[BASE_FIELDS..].iter().zip(&own))
+        {
+            *slot = (field, Some(*value as &dyn Value));
+        }
+        dispatch.event(&Event::new(metadata, &metadata.fields().value_set(&values)));
+    });
+}
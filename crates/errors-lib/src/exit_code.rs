@@ -0,0 +1,188 @@
+/*
+ * Process exit codes derived from diagnostic codes.
+ *
+ * Scripts wrapping a CLI branch on its exit status, so one generic failure
+ * code is not enough. An ExitCodeMap turns diagnostic codes into statuses
+ * by rule (e.g. the BSD sysexits values), and run_with_exit_codes applies
+ * it at the boundary of a LibResult-returning main. A process-wide map,
+ * sysexits unless replaced, backs ReportExt::process_exit_code and
+ * run_and_exit for CLIs that don't pass one around.
+ */
+
+use std::{
+    fmt,
+    io::IsTerminal,
+    process::ExitCode,
+    sync::{OnceLock, PoisonError, RwLock},
+};
+
+use miette::Diagnostic;
+
+use crate::{CodePattern, LibReport, LibResult, ReportExt};
+
+/// Maps diagnostic codes to process exit statuses.
+///
+/// A rule's pattern is a [`CodePattern`]: either a whole code
+/// (`config::invalid_format`) or a prefix ending in `::*` (`config::*`,
+/// matching every `config::` code). An exact rule beats any prefix, a
+/// longer prefix beats a shorter one, and codes no rule matches — or
+/// reports without a code — get the fallback.
+///
+/// ```
+/// use errors_lib::ExitCodeMap;
+///
+/// let map = ExitCodeMap::new()
+///     .rule("config::*", 78) // EX_CONFIG
+///     .rule("io::*", 74); // EX_IOERR
+/// assert_eq!(map.status_for(Some("config::invalid_format")), 78);
+/// assert_eq!(map.status_for(Some("network::timeout")), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExitCodeMap {
+    rules: Vec<(CodePattern, u8)>,
+    fallback: u8,
+}
+
+impl Default for ExitCodeMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExitCodeMap {
+    /// No rules, and a fallback status of 1.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            fallback: 1,
+        }
+    }
+
+    /// The sysexits conventions: `config::*` is 78 (`EX_CONFIG`), `io::*`
+    /// is 74 (`EX_IOERR`), anything else 1.
+    #[must_use]
+    pub fn sysexits() -> Self {
+        Self::new().rule("config::*", 78).rule("io::*", 74)
+    }
+
+    /// Adds a rule; a later rule with the same pattern replaces it.
+    #[must_use]
+    pub fn rule(mut self, pattern: impl Into<CodePattern>, status: u8) -> Self {
+        let pattern = pattern.into();
+        self.rules.retain(|(existing, _)| *existing != pattern);
+        self.rules.push((pattern, status));
+        self
+    }
+
+    /// Sets the status for codes no rule matches.
+    #[must_use]
+    pub const fn fallback(mut self, status: u8) -> Self {
+        self.fallback = status;
+        self
+    }
+
+    /// The status for a diagnostic code.
+    #[must_use]
+    pub fn status_for(&self, code: Option<&str>) -> u8 {
+        code.and_then(|code| CodePattern::best(&self.rules, code))
+            .copied()
+            .unwrap_or(self.fallback)
+    }
+}
+
+impl<E, O> LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    /// The exit code `map` assigns to this report's diagnostic code.
+    #[must_use]
+    pub fn exit_code(&self, map: &ExitCodeMap) -> ExitCode {
+        ExitCode::from(self.exit_status(map))
+    }
+
+    /// [`LibReport::exit_code`] as a number.
+    #[must_use]
+    pub fn exit_status(&self, map: &ExitCodeMap) -> u8 {
+        let code = self.code().map(|code| code.to_string());
+        map.status_for(code.as_deref())
+    }
+}
+
+fn global() -> &'static RwLock<ExitCodeMap> {
+    static MAP: OnceLock<RwLock<ExitCodeMap>> = OnceLock::new();
+    MAP.get_or_init(|| RwLock::new(ExitCodeMap::sysexits()))
+}
+
+/// Replaces the process-wide map used by
+/// [`ReportExt::process_exit_code`] and [`run_and_exit`].
+pub fn set_exit_code_map(map: ExitCodeMap) {
+    *global().write().unwrap_or_else(PoisonError::into_inner) = map;
+}
+
+/// The process-wide map; [`ExitCodeMap::sysexits`] until
+/// [`set_exit_code_map`] is called.
+#[must_use]
+pub fn exit_code_map() -> ExitCodeMap {
+    global()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// The process-wide map's status for `code`, as `std::process::exit`
+/// takes it.
+pub fn process_status(code: Option<&str>) -> i32 {
+    i32::from(
+        global()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .status_for(code),
+    )
+}
+
+/// Renders a failed run's report to stderr and logs it as an `ApiError`.
+fn report_failure<E>(report: &LibReport<E>)
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    eprintln!("{}", report.render_pretty(std::io::stderr().is_terminal()));
+    let api_err = report.to_api_error();
+    eprintln!("[Diagnostic ID: {}]", api_err.correlation_id);
+}
+
+/// Runs a CLI's main body. On failure, renders the report to stderr,
+/// logs it as an `ApiError` and returns the exit code `map` assigns it;
+/// returning (rather than exiting) lets the caller's guards flush.
+pub fn run_with_exit_codes<E>(f: impl FnOnce() -> LibResult<(), E>, map: &ExitCodeMap) -> ExitCode
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    match f() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(report) => {
+            report_failure(&report);
+            report.exit_code(map)
+        },
+    }
+}
+
+/// Exits the process with `result`'s status: 0 on success, else the
+/// report's [`ReportExt::process_exit_code`] after rendering and logging
+/// it as [`run_with_exit_codes`] does.
+///
+/// Destructors do not run, so drop any logging guards first or prefer
+/// [`run_with_exit_codes`].
+pub fn run_and_exit<E>(result: LibResult<(), E>) -> !
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    match result {
+        Ok(()) => std::process::exit(0),
+        Err(report) => {
+            report_failure(&report);
+            std::process::exit(report.process_exit_code())
+        },
+    }
+}
@@ -0,0 +1,41 @@
+/*
+ * Process-wide ApiError fields.
+ *
+ * Deployment facts like the service name or environment belong on every
+ * ApiError but are known only at startup. They are set once, globally,
+ * and copied into ApiError::extra as each one is built, instead of being
+ * threaded through every call as attach_field pairs.
+ */
+
+use std::{
+    collections::BTreeMap,
+    sync::{OnceLock, PoisonError, RwLock},
+};
+
+fn fields() -> &'static RwLock<BTreeMap<String, String>> {
+    static FIELDS: OnceLock<RwLock<BTreeMap<String, String>>> = OnceLock::new();
+    FIELDS.get_or_init(|| RwLock::new(BTreeMap::new()))
+}
+
+/// Replaces the fields stamped into `ApiError::extra`, e.g.
+/// `service=payments` and `environment=prod`.
+pub fn set_global_fields(map: BTreeMap<String, String>) {
+    *fields().write().unwrap_or_else(PoisonError::into_inner) = map;
+}
+
+/// Removes every global field.
+pub fn clear_global_fields() {
+    fields()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+/// The fields currently stamped into `ApiError::extra`.
+#[must_use]
+pub fn global_fields() -> BTreeMap<String, String> {
+    fields()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
@@ -0,0 +1,133 @@
+/*
+ * FixedApiError: an ApiError for targets without a heap (`heapless`
+ * feature).
+ *
+ * Strings and history live in heapless buffers of fixed capacity, the
+ * correlation ID comes from a counter instead of nanoid, nothing is sent
+ * to tracing, and JSON is written into a caller-supplied buffer with
+ * serde-json-core. Text longer than a buffer is cut at a char boundary;
+ * history past its capacity is dropped.
+ */
+
+use core::{
+    fmt::Write,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use heapless::{String, Vec};
+use serde::Serialize;
+
+/// A fixed-capacity counterpart of [`ApiError`](crate::ApiError): `N`
+/// bytes per string, `H` history frames.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FixedApiError<const N: usize = 128, const H: usize = 8> {
+    pub git_hash: &'static str,
+    pub docs_url: &'static str,
+    /// Eight hex digits from a process-wide counter.
+    pub correlation_id: String<8>,
+    pub title: String<N>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String<N>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<String<N>>,
+    pub retryable: bool,
+    pub history: Vec<String<N>, H>,
+}
+
+impl<const N: usize, const H: usize> FixedApiError<N, H> {
+    /// An error titled `title`, with build metadata and the next
+    /// correlation ID filled in.
+    #[must_use]
+    pub fn new(title: &str) -> Self {
+        Self {
+            git_hash: env!("GIT_HASH"),
+            docs_url: env!("ERROR_DOCS_URL"),
+            correlation_id: next_correlation_id(),
+            title: truncated(title),
+            code: None,
+            help: None,
+            retryable: false,
+            history: Vec::new(),
+        }
+    }
+
+    /// Sets the diagnostic code.
+    #[must_use]
+    pub fn with_code(mut self, code: &str) -> Self {
+        self.code = Some(truncated(code));
+        self
+    }
+
+    /// Sets the help text.
+    #[must_use]
+    pub fn with_help(mut self, help: &str) -> Self {
+        self.help = Some(truncated(help));
+        self
+    }
+
+    /// Sets whether the failure is transient.
+    #[must_use]
+    pub const fn with_retryable(mut self, retryable: bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// Appends a history frame; `false` when history is already full and
+    /// the frame was dropped.
+    pub fn push_history(&mut self, message: &str) -> bool {
+        self.history.push(truncated(message)).is_ok()
+    }
+
+    /// Writes the error as JSON into `buf`, returning the length written.
+    ///
+    /// # Errors
+    ///
+    /// If `buf` is too small for the encoded error.
+    pub fn to_json(&self, buf: &mut [u8]) -> Result<usize, serde_json_core::ser::Error> {
+        serde_json_core::to_slice(self, buf)
+    }
+
+    /// [`to_json`](Self::to_json) with camelCase keys (`correlationId`,
+    /// `gitHash`, ...).
+    ///
+    /// # Errors
+    ///
+    /// If `buf` is too small for the encoded error.
+    pub fn to_json_camel_case(&self, buf: &mut [u8]) -> Result<usize, serde_json_core::ser::Error> {
+        serde_json_core::to_slice(&crate::CamelCase(self), buf)
+    }
+}
+
+/// Keeps the title, code, help and the first `H` history frames, each cut
+/// to `N` bytes.
+#[cfg(feature = "alloc")]
+impl<const N: usize, const H: usize> From<&crate::ApiError> for FixedApiError<N, H> {
+    fn from(api_err: &crate::ApiError) -> Self {
+        let mut fixed = Self::new(&api_err.title).with_retryable(api_err.retryable);
+        fixed.code = api_err.code.as_deref().map(truncated);
+        fixed.help = api_err.help.as_deref().map(truncated);
+        for frame in &api_err.history {
+            if !fixed.push_history(&frame.message) {
+                break;
+            }
+        }
+        fixed
+    }
+}
+
+fn next_correlation_id() -> String<8> {
+    static NEXT: AtomicU32 = AtomicU32::new(1);
+    let mut id = String::new();
+    // Eight hex digits always fit.
+    let _ = write!(id, "{:08x}", NEXT.fetch_add(1, Ordering::Relaxed));
+    id
+}
+
+/// `text` cut to at most `N` bytes, at a char boundary.
+fn truncated<const N: usize>(text: &str) -> String<N> {
+    let mut end = text.len().min(N);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    String::try_from(&text[..end]).unwrap_or_default()
+}
@@ -0,0 +1,49 @@
+/*
+ * Reports for failed futures (`async` feature).
+ *
+ * At an async boundary the interesting question is often how long the
+ * operation ran before it failed: a timeout that fires after 30s and a
+ * refused connection after 2ms are different incidents. instrument_future
+ * wraps a future's error in a LibReport and attaches the elapsed time, so
+ * it lands in ApiError history next to the error.
+ */
+
+use std::{fmt, future::Future, time::Instant};
+
+use futures_util::TryFutureExt;
+use miette::Diagnostic;
+use rootcause::Report;
+
+use crate::{LibReport, LibResult, ReportLocation};
+
+impl<E> LibReport<E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    /// Runs `fut`, wrapping its error in a `LibReport` with a
+    /// `failed after {elapsed:?}` attachment, timed from this call.
+    ///
+    /// The report's location is this call rather than the point where
+    /// the future is polled.
+    ///
+    /// ```
+    /// # use errors_lib::{LibReport, lib_error, miette};
+    /// # lib_error! { pub enum AppError { Timeout => { display: "timed out" } } }
+    /// # async fn fetch() -> Result<String, AppError> { Err(AppError::Timeout) }
+    /// # async fn run() {
+    /// let result = LibReport::instrument_future(fetch()).await;
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn instrument_future<F, T>(fut: F) -> impl Future<Output = LibResult<T, E>>
+    where
+        F: Future<Output = Result<T, E>>,
+    {
+        let location = ReportLocation::caller();
+        let start = Instant::now();
+        fut.map_err(move |err| {
+            Self::located(Report::new(err), location)
+                .attach(format!("failed after {:?}", start.elapsed()))
+        })
+    }
+}
@@ -0,0 +1,213 @@
+/*
+ * Code-keyed error handlers.
+ *
+ * Reactions that depend only on a diagnostic code (trip a circuit breaker
+ * on network::timeout, reload defaults on any config:: code) are
+ * registered once, typically in a static, and run by dispatching a report
+ * through the registry — no downcasts at each call site. Patterns are
+ * CodePatterns, as for ExitCodeMap: a whole code, or a prefix ending in
+ * `::*` — or just `::`, so `network::` covers network::timeout too.
+ */
+
+use std::{
+    fmt,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use miette::Diagnostic;
+
+use crate::{CodePattern, ErrorNode, LibReport};
+
+type Handler = Arc<dyn Fn(&MatchedNode<'_>) + Send + Sync>;
+
+/// Identifies a handler within its [`ErrorHandlerRegistry`]; ids count up
+/// in registration order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandlerId(usize);
+
+/// The node a handler fired for: its code, plus the node itself for the
+/// context's message and attachments.
+#[derive(Debug, Clone, Copy)]
+pub struct MatchedNode<'a> {
+    code: &'a str,
+    node: ErrorNode<'a>,
+}
+
+impl<'a> MatchedNode<'a> {
+    /// The diagnostic code that matched.
+    #[must_use]
+    pub const fn code(&self) -> &'a str {
+        self.code
+    }
+
+    /// The report node carrying the code.
+    #[must_use]
+    pub const fn node(&self) -> &ErrorNode<'a> {
+        &self.node
+    }
+}
+
+/// The context's message.
+impl fmt::Display for MatchedNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.node, f)
+    }
+}
+
+/// One handler invocation during [`ErrorHandlerRegistry::dispatch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FiredHandler {
+    /// The handler that ran.
+    pub id: HandlerId,
+    /// The pattern it was registered under.
+    pub pattern: String,
+    /// The code of the node it ran for.
+    pub code: String,
+}
+
+/// What [`ErrorHandlerRegistry::dispatch`] did, in firing order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DispatchOutcome {
+    pub fired: Vec<FiredHandler>,
+}
+
+impl DispatchOutcome {
+    /// Whether any handler ran.
+    #[must_use]
+    pub const fn is_handled(&self) -> bool {
+        !self.fired.is_empty()
+    }
+
+    /// Whether the handler `id` ran at least once.
+    #[must_use]
+    pub fn fired(&self, id: HandlerId) -> bool {
+        self.fired.iter().any(|fired| fired.id == id)
+    }
+}
+
+struct Registration {
+    id: HandlerId,
+    pattern: CodePattern,
+    handler: Handler,
+}
+
+/// Handlers keyed by diagnostic code.
+///
+/// A pattern is a [`CodePattern`], as for [`ExitCodeMap`](crate::ExitCodeMap):
+/// either a whole code (`network::timeout`) or a prefix ending in `::*`
+/// (`config::*`, matching every `config::` code; `config::` works too).
+/// Every matching handler runs: exact patterns first, then prefixes, each
+/// group in registration order.
+///
+/// Registration takes `&self`, so a registry can live in a static and be
+/// shared across threads; handlers run outside the lock and may register
+/// further handlers.
+///
+/// ```
+/// use errors_lib::ErrorHandlerRegistry;
+///
+/// static HANDLERS: ErrorHandlerRegistry = ErrorHandlerRegistry::new();
+///
+/// HANDLERS.on("network::timeout", |node| eprintln!("tripping breaker: {node}"));
+/// HANDLERS.on("config::*", |_| eprintln!("reloading defaults"));
+/// ```
+#[derive(Default)]
+pub struct ErrorHandlerRegistry {
+    handlers: RwLock<Vec<Registration>>,
+}
+
+impl ErrorHandlerRegistry {
+    /// An empty registry.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            handlers: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Registers `handler` for codes matching `pattern`.
+    pub fn on<F>(&self, pattern: impl Into<CodePattern>, handler: F) -> HandlerId
+    where
+        F: Fn(&MatchedNode<'_>) + Send + Sync + 'static,
+    {
+        let mut handlers = self
+            .handlers
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        let id = HandlerId(handlers.len());
+        handlers.push(Registration {
+            id,
+            pattern: pattern.into(),
+            handler: Arc::new(handler),
+        });
+        id
+    }
+
+    /// Runs the matching handlers for every node of `report` whose context
+    /// is an `E` with a code, top-level node first.
+    pub fn dispatch<E, O>(&self, report: &LibReport<E, O>) -> DispatchOutcome
+    where
+        E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+        O: 'static,
+    {
+        let mut outcome = DispatchOutcome::default();
+        for node in report.0.iter_reports() {
+            let Some(code) = node
+                .downcast_current_context::<E>()
+                .and_then(Diagnostic::code)
+                .map(|code| code.to_string())
+            else {
+                continue;
+            };
+
+            let matched = MatchedNode {
+                code: &code,
+                node: ErrorNode::new::<E>(node),
+            };
+            for (id, pattern, handler) in self.matching(&code) {
+                handler(&matched);
+                outcome.fired.push(FiredHandler {
+                    id,
+                    pattern,
+                    code: code.clone(),
+                });
+            }
+        }
+        outcome
+    }
+
+    /// The handlers for `code` in firing order, copied out so none run
+    /// under the lock.
+    fn matching(&self, code: &str) -> Vec<(HandlerId, String, Handler)> {
+        let mut matching: Vec<_> = self
+            .handlers
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|r| r.pattern.matches(code))
+            .map(|r| {
+                (
+                    r.pattern.is_prefix(),
+                    r.id,
+                    r.pattern.to_string(),
+                    Arc::clone(&r.handler),
+                )
+            })
+            .collect();
+        // Stable, so each group keeps registration order.
+        matching.sort_by_key(|&(is_prefix, ..)| is_prefix);
+        matching
+            .into_iter()
+            .map(|(_, id, pattern, handler)| (id, pattern, handler))
+            .collect()
+    }
+}
+
+impl fmt::Debug for ErrorHandlerRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let handlers = self.handlers.read().unwrap_or_else(PoisonError::into_inner);
+        f.debug_list()
+            .entries(handlers.iter().map(|r| r.pattern.as_str()))
+            .finish()
+    }
+}
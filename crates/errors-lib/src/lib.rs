@@ -3,10 +3,93 @@
  *
  * This crate provides:
  * 1. LibReport   — a miette-compatible wrapper around rootcause::Report<E>
- * 2. LibResult   — a Result alias using LibReport as the error type
+ *    (renders to terminal, plain text or Markdown)
+ * 2. LibResult   — a Result alias using LibReport as the error type;
+ *    LibResultExt adds wrap_err / with_wrap_err to it
  * 3. ApiError    — machine-readable error struct for API/log sinks
- * 4. ReportExt   — trait to convert a LibReport into an ApiError
- * 5. handle_error_logic — example of typed introspection via rootcause
+ * 4. ReportExt   — trait to convert a LibReport into an ApiError and
+ *    classify it as retryable; RetryHint carries a delay and attempt budget
+ * 5. ErrorVisitor / classify_error — depth-first visitors over a report, and
+ *    the ErrorActions (missing file, timeout, ...) it calls for;
+ *    handle_error_logic says which was handled, and how
+ * 6. testing     — snapshot helpers for ApiError (`test-helpers` feature)
+ * 7. lib_error!   — declares a consumer error enum with the usual derives;
+ *    lib_bail! / lib_ensure! return early from a LibResult
+ * 8. color-eyre interop — LibReport -> color_eyre::Report -> ApiError
+ *    (`color-eyre` feature)
+ * 9. RedactionConfig — masks PII in an ApiError, or with
+ *    set_sink_redaction in every ApiError the sinks see
+ * 10. ErrorDocs   — per-namespace docs links for diagnostic codes, and a
+ *    runtime override of the default docs base URL
+ * 11. Sentry interop — LibReport / ApiError -> sentry event, with the
+ *    report's exception chain (`sentry` feature)
+ * 12. error-stack interop — LibReport <-> error_stack::Report
+ *    (`error-stack` feature)
+ * 13. SpanTrace capture — active tracing spans in ApiError, optionally
+ *    captured when the report is created (`span-trace` feature)
+ * 14. MultiError  — joins independent failures into one report
+ * 15. LoggingBuilder — global subscriber to stderr and/or a rotated file;
+ *    init_logging_with_writer — scoped JSON subscriber, e.g. for tests
+ * 16. ApiError::size_budget — trims an ApiError to fit a byte limit
+ * 17. install_panic_to_api_error — logs panics as ApiError events
+ * 18. ExitCodeMap / run_with_exit_codes / run_and_exit — process exit codes
+ *    per diagnostic code (sysexits by default), for CLIs
+ * 19. ariadne rendering — LibReport::render_ariadne (`ariadne` feature)
+ * 20. ErrorStats  — process-wide counts of the codes seen by to_api_error
+ * 21. CBOR        — ApiError::to_cbor / from_cbor (`cbor` feature)
+ * 22. Query helpers — find_context / any_context / contains_code / codes
+ *    / contexts
+ * 23. FixedApiError — heapless ApiError with JSON into a fixed buffer
+ *    (`heapless` feature)
+ * 24. ErrorHandlerRegistry — handlers run by diagnostic code or prefix
+ * 25. iter_nodes / iter_contexts / iter_attachment_strings — tree walks
+ *    with depth, depth- or breadth-first
+ * 26. set_global_fields — service / environment / ... on every ApiError
+ * 27. GraphQL     — ApiError / LibReport -> message + extensions
+ *    (`graphql` feature)
+ * 28. BatchReportExt — many reports to ApiErrors with one correlation prefix
+ * 29. max_severity — the worst severity anywhere in the tree, which sets
+ *    ApiError::severity and the tracing level (set_level_mapper overrides)
+ * 30. JSON-RPC    — ApiError / LibReport -> { code, message, data }, with
+ *    numeric codes registered per diagnostic code (`jsonrpc` feature)
+ * 31. ErrorSinkRegistry — every to_api_error() goes to each registered
+ *    sink; tracing by default, BufferSink for the last N errors
+ * 32. summary     — the whole tree on one line, for terse log lines
+ * 33. OpenTelemetry — ApiError / LibReport -> OTEL log record
+ *    (`opentelemetry` feature)
+ * 34. Slack       — ApiError / LibReport -> Block Kit webhook payload
+ *    (`slack` feature)
+ * 35. OTel IDs    — the active span's trace_id / span_id on every ApiError
+ *    (`otel` feature)
+ * 36. set_emit_rate_limit — at most N emissions per code per window, with
+ *    a summary ApiError (SUPPRESSED_CODE) of what was suppressed
+ * 37. metrics     — errors_total{code, severity} and history length per
+ *    emitted error (`metrics` feature)
+ * 38. ErrorCode   — validated `namespace::variant` codes, as
+ *    ApiError::code and LibReport::error_code
+ * 39. instrument_future — a future's error as a LibReport, with how long
+ *    it ran (`async` feature)
+ * 40. ApiErrorBuilder — an ApiError without a report, e.g. relayed from
+ *    another system, with its title and code checked
+ * 41. set_emit_config — the tracing target and field names of emitted
+ *    ApiErrors, and whether history goes into the log line
+ * 42. CamelCase   — serializes ApiError / FixedApiError with
+ *    `correlationId`, `gitHash`, ... keys, for JS consumers
+ * 43. attach_location! — attaches the call site as `at file:line:column`
+ * 44. CodePattern — the `code` / `ns::*` patterns everything keyed by
+ *    diagnostic code takes
+ * 45. ClonableLibReport — a LibReport that can be cloned and shared
+ * 46. DynLibReport / DynLibResult — LibReport and LibResult for untyped
+ *    chains
+ * 47. DynError    — boxes any Diagnostic, so mixed error types fit one
+ *    queue
+ *
+ * Everything but the data models needs the default `std` feature. Without
+ * it the crate is `no_std`:
+ *   `alloc`    : ApiError / ErrorFrame / ErrorTree / ErrorCode and their
+ *                serde impls;
+ *                construct them by hand
+ *   `heapless` : FixedApiError, which needs no allocator at all
  *
  * Consuming crates define their own error enums (with snafu + miette),
  * then wrap them in LibReport<YourError> for full framework integration.
@@ -16,188 +99,217 @@
  *   miette    : structured diagnostics and terminal rendering
  *   snafu     : ergonomic error definition (used by consumers, re-exported)
  *   tracing   : structured log emission on error
+ *   tracing-appender : rotated, non-blocking log files
  *   nanoid    : correlation ID generation
+ *   regex     : PII redaction patterns
+ *   percent-encoding : docs anchors for diagnostic codes
  */
 
-use std::fmt;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
+#[cfg(feature = "std")]
 pub use miette;
-use miette::{Diagnostic, SourceCode};
-use nanoid::nanoid;
+#[cfg(feature = "std")]
 pub use rootcause;
-use rootcause::Report;
-use serde::{Serialize, Serializer};
+#[cfg(feature = "std")]
 pub use snafu::{self, Snafu}; // This re-exports the crate AND the macro
-use tracing::error;
-
-// ---------------------------------------------------------------------------
-// Core types
-// ---------------------------------------------------------------------------
-
-/// A miette-compatible wrapper around a rootcause error chain.
-///
-/// `E` is the top-level error context type — defined by the consuming crate,
-/// not by this library. It must implement `Diagnostic` (for miette rendering)
-/// and `std::error::Error`.
-#[derive(Debug)]
-pub struct LibReport<E>(pub Report<E>)
-where
-    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static;
-
-/// Result alias. Consuming crates alias this with their own error type:
-///
-/// ```rust
-/// struct AppError;
-/// type AppResult<T> = errors_lib::LibResult<T, AppError>;
-/// ```
-pub type LibResult<T, E> = std::result::Result<T, LibReport<E>>;
-
-// ---------------------------------------------------------------------------
-// API / log sink types
-// ---------------------------------------------------------------------------
-
-#[derive(Debug, Serialize)]
-pub struct ErrorFrame {
-    pub message: String,
-}
-
-#[derive(Debug, Serialize)]
-pub struct ApiError {
-    pub git_hash: String,
-    pub docs_url: String,
-    pub correlation_id: String,
-    pub title: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub code: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub help: Option<String>,
-    #[serde(serialize_with = "serialize_history_flat")]
-    pub history: Vec<ErrorFrame>,
-}
-
-fn serialize_history_flat<S>(history: &[ErrorFrame], serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    let flat: Vec<&str> = history.iter().map(|f| f.message.as_str()).collect();
-    flat.serialize(serializer)
-}
-
-// ---------------------------------------------------------------------------
-// Diagnostic impl — delegates to the inner error context
-// ---------------------------------------------------------------------------
-
-impl<E> Diagnostic for LibReport<E>
-where
-    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
-{
-    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
-        self.0.current_context().code()
-    }
-
-    fn severity(&self) -> Option<miette::Severity> {
-        self.0.current_context().severity()
-    }
-
-    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
-        self.0.current_context().help()
-    }
-
-    /// Maps the error code to a clickable docs link in the terminal.
-    fn url<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
-        let base = env!("ERROR_DOCS_URL");
-        self.code().map(|c| {
-            let link = format!("{base}/#{c}");
-            Box::new(link) as Box<dyn fmt::Display>
-        })
-    }
-
-    fn source_code(&self) -> Option<&dyn SourceCode> {
-        self.0.current_context().source_code()
-    }
-
-    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
-        self.0.current_context().labels()
-    }
-}
-
-impl<E> fmt::Display for LibReport<E>
-where
-    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
-{
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
-    }
-}
-
-impl<E> std::error::Error for LibReport<E> where
-    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static
-{
-}
-
-// ---------------------------------------------------------------------------
-// ReportExt — converts a LibReport into an ApiError for logging/API sinks
-// ---------------------------------------------------------------------------
-
-pub trait ReportExt {
-    fn to_api_error(&self) -> ApiError;
-}
-
-impl<E> ReportExt for LibReport<E>
-where
-    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
-{
-    fn to_api_error(&self) -> ApiError {
-        let mut history = Vec::new();
-        for node in self.0.iter_reports() {
-            for attachment in node.attachments() {
-                history.push(ErrorFrame {
-                    message: attachment.to_string(),
-                });
-            }
-        }
-
-        let ctx = self.0.current_context();
-        let api_err = ApiError {
-            git_hash: env!("GIT_HASH").to_string(),
-            docs_url: env!("ERROR_DOCS_URL").to_string(),
-            correlation_id: nanoid!(8),
-            title: ctx.to_string(),
-            code: ctx.code().map(|c| c.to_string()),
-            help: ctx.help().map(|h| h.to_string()),
-            history,
-        };
-
-        error!(
-            hash = %api_err.git_hash,
-            docs = %api_err.docs_url,
-            id = %api_err.correlation_id,
-            title = %api_err.title,
-            code = api_err.code.as_deref(),
-            history = ?api_err.history.iter().map(|h| &h.message).collect::<Vec<_>>(),
-            "Internal error reported to API sink"
-        );
-
-        api_err
-    }
-}
 
-// ---------------------------------------------------------------------------
-// handle_error_logic — example typed introspection via rootcause
-// ---------------------------------------------------------------------------
+#[cfg(feature = "std")]
+mod action;
+#[cfg(feature = "std")]
+mod anchor;
+#[cfg(feature = "alloc")]
+mod api_error;
+#[cfg(feature = "std")]
+mod attachments;
+#[cfg(feature = "std")]
+mod batch;
+#[cfg(feature = "std")]
+mod budget;
+#[cfg(feature = "std")]
+mod builder;
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+mod camel_case;
+#[cfg(feature = "cbor")]
+mod cbor;
+#[cfg(feature = "alloc")]
+mod code;
+#[cfg(feature = "ariadne")]
+mod compat_ariadne;
+#[cfg(feature = "error-stack")]
+mod compat_error_stack;
+#[cfg(feature = "color-eyre")]
+mod compat_eyre;
+#[cfg(feature = "graphql")]
+mod compat_graphql;
+#[cfg(feature = "jsonrpc")]
+mod compat_jsonrpc;
+#[cfg(feature = "opentelemetry")]
+mod compat_opentelemetry;
+#[cfg(feature = "sentry")]
+mod compat_sentry;
+#[cfg(feature = "slack")]
+mod compat_slack;
+#[cfg(feature = "std")]
+mod docs;
+#[cfg(feature = "std")]
+mod dynamic;
+#[cfg(feature = "std")]
+mod emit;
+#[cfg(feature = "std")]
+mod exit_code;
+#[cfg(feature = "std")]
+mod extra;
+#[cfg(feature = "heapless")]
+mod fixed;
+#[cfg(feature = "async")]
+mod future;
+#[cfg(feature = "std")]
+mod handlers;
+#[cfg(feature = "std")]
+mod logging;
+#[cfg(feature = "std")]
+mod macros;
+#[cfg(feature = "std")]
+mod markdown;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "std")]
+mod multi;
+#[cfg(feature = "otel")]
+mod otel_context;
+#[cfg(feature = "std")]
+mod panic;
+#[cfg(feature = "std")]
+mod pattern;
+#[cfg(feature = "std")]
+mod query;
+#[cfg(feature = "std")]
+mod redact;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "std")]
+mod retry;
+#[cfg(feature = "std")]
+mod severity;
+#[cfg(feature = "std")]
+mod sink;
+#[cfg(feature = "span-trace")]
+mod span_trace;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
+mod summary;
+#[cfg(any(test, feature = "test-helpers"))]
+pub mod testing;
+#[cfg(feature = "std")]
+mod throttle;
+#[cfg(feature = "alloc")]
+mod tree;
+#[cfg(feature = "std")]
+mod visit;
+#[cfg(feature = "std")]
+mod walk;
 
-/// Walk the error chain and react to specific error types.
-/// This is the pattern for "smart" error handling — not just logging,
-/// but branching on what actually went wrong.
-pub fn handle_error_logic<E>(report: &LibReport<E>)
-where
-    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
-{
-    for node in report.0.iter_reports() {
-        if let Some(io_err) = node.downcast_current_context::<std::io::Error>()
-            && matches!(io_err.kind(), std::io::ErrorKind::NotFound)
-        {
-            println!("--- LOGIC CHECK: Missing file detected ---");
-        }
-    }
-}
+#[cfg(feature = "std")]
+pub use action::{
+    ActionHandler, DefaultActionHandler, ErrorAction, HandleOutcome, classify_error,
+    handle_error_logic,
+};
+#[cfg(feature = "std")]
+pub use anchor::{
+    DEFAULT_ANCHOR_SEPARATOR, anchor_separator, code_to_anchor, set_anchor_separator,
+};
+#[cfg(feature = "alloc")]
+pub use api_error::{ApiError, ErrorFrame};
+#[cfg(feature = "std")]
+pub use attachments::ReportLocation;
+#[cfg(feature = "std")]
+pub use batch::{BatchReportExt, CorrelationId};
+#[cfg(feature = "std")]
+pub use budget::HISTORY_TRUNCATED;
+#[cfg(feature = "std")]
+pub use builder::{ApiErrorBuilder, ApiErrorBuilderError};
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+pub use camel_case::CamelCase;
+#[cfg(feature = "alloc")]
+pub use code::{ErrorCode, ErrorCodeError};
+#[cfg(feature = "jsonrpc")]
+pub use compat_jsonrpc::{
+    JSONRPC_SERVER_ERROR, JsonRpcError, clear_jsonrpc_codes, jsonrpc_code_for,
+    register_jsonrpc_code,
+};
+#[cfg(feature = "sentry")]
+pub use compat_sentry::{capture_lib_report, capture_to_sentry};
+#[cfg(feature = "std")]
+pub use docs::{
+    ErrorDocs, clear_docs_url_override, clear_error_docs, docs_base_url, docs_url_for,
+    register_error_docs, set_docs_url_override,
+};
+#[cfg(feature = "std")]
+pub use dynamic::{DynError, DynLibReport, DynLibResult};
+#[cfg(feature = "std")]
+pub use emit::{
+    DEFAULT_EMIT_TARGET, EmitConfig, FieldNames, clear_emit_config, emit_config,
+    register_field_key, set_emit_config,
+};
+#[cfg(feature = "std")]
+pub use exit_code::{
+    ExitCodeMap, exit_code_map, run_and_exit, run_with_exit_codes, set_exit_code_map,
+};
+#[cfg(feature = "std")]
+pub use extra::{clear_global_fields, global_fields, set_global_fields};
+#[cfg(feature = "heapless")]
+pub use fixed::FixedApiError;
+#[cfg(feature = "std")]
+pub use handlers::{DispatchOutcome, ErrorHandlerRegistry, FiredHandler, HandlerId, MatchedNode};
+#[cfg(feature = "std")]
+pub use logging::{LogFormat, LogRotation, LoggingBuilder, LoggingGuard, init_logging_with_writer};
+#[cfg(feature = "std")]
+pub use multi::MultiError;
+#[cfg(feature = "std")]
+pub use panic::{PANIC_CODE, install_panic_to_api_error, panic_to_api_error};
+#[cfg(feature = "std")]
+pub use pattern::CodePattern;
+#[cfg(feature = "std")]
+pub use redact::{REDACTED, RedactionConfig, clear_sink_redaction, set_sink_redaction};
+#[cfg(feature = "std")]
+pub use report::{
+    ApiErrorOptions, ApiErrorWriter, ClonableLibReport, HistoryDedup, HistoryOrder, LevelMapper,
+    LibReport, LibResult, LibResultExt, ReportExt, clear_level_mapper, set_level_mapper,
+};
+#[cfg(feature = "std")]
+use report::{
+    DiagnosticCache, DiagnosticView, TopLevel, api_error_of, build_api_error, docs_link, fmt_chain,
+    history_of, related_iter, view_as, view_none,
+};
+#[cfg(feature = "std")]
+pub use retry::{
+    DEFAULT_TRANSIENT_CODES, RetryHint, RetryPolicy, Retryable, set_transient_codes,
+    transient_codes,
+};
+#[cfg(feature = "std")]
+pub use sink::{BufferSink, ErrorSink, ErrorSinkRegistry, TracingSink};
+#[cfg(feature = "span-trace")]
+pub use span_trace::{ReportSpanTrace, set_span_trace_capture, span_trace_capture};
+#[cfg(feature = "std")]
+pub use stats::ErrorStats;
+#[cfg(feature = "std")]
+use summary::summary_of;
+#[cfg(feature = "std")]
+pub use throttle::{
+    SUPPRESSED_CODE, clear_emit_rate_limit, flush_emit_summaries, set_emit_rate_limit,
+};
+#[cfg(feature = "alloc")]
+pub use tree::ErrorTree;
+#[cfg(feature = "std")]
+use visit::source_of;
+#[cfg(feature = "std")]
+pub use visit::{ErrorNode, ErrorVisitor};
+#[cfg(feature = "std")]
+pub use walk::{NodeContext, NodeView, Walk};
+#[cfg(feature = "std")]
+use walk::{messages, walk_report};
@@ -0,0 +1,268 @@
+/*
+ * Logging setup for applications, tests and embedders.
+ *
+ * ApiError::log_to_tracing emits through whatever subscriber is installed.
+ * Applications install theirs once, globally, with LoggingBuilder: stderr
+ * and/or a rotated file, written off-thread. The file writer buffers, so
+ * build() hands back a LoggingGuard that flushes it on drop; the caller
+ * holds it for the life of the program. Tests want a scoped subscriber
+ * that writes somewhere they can read back: init_logging_with_writer
+ * installs a JSON one for the current thread only, until the guard drops.
+ * Both add tracing-error's ErrorLayer when span traces are captured.
+ */
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use tracing::subscriber::DefaultGuard;
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{RollingFileAppender, Rotation},
+};
+use tracing_subscriber::{EnvFilter, Layer, Registry, fmt, prelude::*};
+
+/// How a [`LoggingBuilder`] output renders events.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// One JSON object per line.
+    #[default]
+    Json,
+    /// tracing-subscriber's single-line human format.
+    Compact,
+}
+
+/// When a [`LoggingBuilder`] log file starts a new file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogRotation {
+    /// `<name>.<YYYY-MM-DD>`.
+    #[default]
+    Daily,
+    /// `<name>.<YYYY-MM-DD-HH>`.
+    Hourly,
+    /// Always `<name>`.
+    Never,
+}
+
+impl LogRotation {
+    const fn to_appender(self) -> Rotation {
+        match self {
+            Self::Daily => Rotation::DAILY,
+            Self::Hourly => Rotation::HOURLY,
+            Self::Never => Rotation::NEVER,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FileOutput {
+    directory: PathBuf,
+    name: String,
+    rotation: LogRotation,
+    format: LogFormat,
+}
+
+/// Installs the process-wide subscriber.
+///
+/// By default events go to stderr in the compact format, filtered by
+/// `RUST_LOG` or else `"error"`. [`LoggingBuilder::file`] adds a rotated
+/// file, JSON by default, which records every event: the filter only
+/// limits stderr, so the console can stay quiet while the file keeps the
+/// full record.
+///
+/// ```no_run
+/// use errors_lib::{LogRotation, LoggingBuilder};
+///
+/// let _logging = LoggingBuilder::new()
+///     .file("logs", "api-errors.log")
+///     .rotation(LogRotation::Hourly)
+///     .default_filter("off")
+///     .build()
+///     .expect("logging is set up once");
+/// ```
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct LoggingBuilder {
+    stderr: Option<LogFormat>,
+    file: Option<FileOutput>,
+    default_filter: String,
+    #[cfg(feature = "span-trace")]
+    error_layer: bool,
+}
+
+impl Default for LoggingBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LoggingBuilder {
+    /// Compact stderr output, no file, and `"error"` when `RUST_LOG` is
+    /// unset.
+    pub fn new() -> Self {
+        Self {
+            stderr: Some(LogFormat::Compact),
+            file: None,
+            default_filter: "error".to_owned(),
+            #[cfg(feature = "span-trace")]
+            error_layer: true,
+        }
+    }
+
+    /// Writes to stderr in `format`.
+    pub const fn stderr(mut self, format: LogFormat) -> Self {
+        self.stderr = Some(format);
+        self
+    }
+
+    /// Leaves stderr alone.
+    pub const fn without_stderr(mut self) -> Self {
+        self.stderr = None;
+        self
+    }
+
+    /// Also writes to `name` in `directory`, created if missing: JSON,
+    /// rotated daily, unless changed with [`LoggingBuilder::file_format`]
+    /// and [`LoggingBuilder::rotation`].
+    pub fn file(mut self, directory: impl AsRef<Path>, name: impl Into<String>) -> Self {
+        self.file = Some(FileOutput {
+            directory: directory.as_ref().to_path_buf(),
+            name: name.into(),
+            rotation: LogRotation::default(),
+            format: LogFormat::default(),
+        });
+        self
+    }
+
+    /// The file's format; no effect without [`LoggingBuilder::file`].
+    pub const fn file_format(mut self, format: LogFormat) -> Self {
+        if let Some(file) = &mut self.file {
+            file.format = format;
+        }
+        self
+    }
+
+    /// The file's rotation; no effect without [`LoggingBuilder::file`].
+    pub const fn rotation(mut self, rotation: LogRotation) -> Self {
+        if let Some(file) = &mut self.file {
+            file.rotation = rotation;
+        }
+        self
+    }
+
+    /// The stderr filter when `RUST_LOG` is unset, in `EnvFilter` syntax;
+    /// an unparsable one falls back to `"off"`.
+    pub fn default_filter(mut self, directives: impl Into<String>) -> Self {
+        self.default_filter = directives.into();
+        self
+    }
+
+    /// Whether to add tracing-error's `ErrorLayer`, which
+    /// `ApiError::span_trace` needs; on by default.
+    #[cfg(feature = "span-trace")]
+    pub const fn error_layer(mut self, include: bool) -> Self {
+        self.error_layer = include;
+        self
+    }
+
+    /// Installs the subscriber globally.
+    ///
+    /// # Errors
+    ///
+    /// When the log directory cannot be created or another global
+    /// subscriber is already installed.
+    #[must_use = "buffered file output is lost when the guard is dropped"]
+    pub fn build(self) -> io::Result<LoggingGuard> {
+        let mut layers: Vec<Box<dyn Layer<Registry> + Send + Sync>> = Vec::new();
+        let mut worker = None;
+
+        if let Some(file) = self.file {
+            let appender = RollingFileAppender::builder()
+                .rotation(file.rotation.to_appender())
+                .filename_prefix(file.name)
+                .build(&file.directory)
+                .map_err(io::Error::other)?;
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            worker = Some(guard);
+            layers.push(match file.format {
+                LogFormat::Json => fmt::layer().json().with_writer(writer).boxed(),
+                LogFormat::Compact => fmt::layer()
+                    .compact()
+                    .with_ansi(false)
+                    .with_writer(writer)
+                    .boxed(),
+            });
+        }
+
+        if let Some(format) = self.stderr {
+            let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+                EnvFilter::try_new(&self.default_filter).unwrap_or_else(|_| EnvFilter::new("off"))
+            });
+            layers.push(match format {
+                LogFormat::Json => fmt::layer()
+                    .json()
+                    .with_writer(io::stderr)
+                    .with_filter(filter)
+                    .boxed(),
+                LogFormat::Compact => fmt::layer()
+                    .compact()
+                    .with_writer(io::stderr)
+                    .with_filter(filter)
+                    .boxed(),
+            });
+        }
+
+        #[cfg(feature = "span-trace")]
+        if self.error_layer {
+            layers.push(tracing_error::ErrorLayer::default().boxed());
+        }
+
+        tracing_subscriber::registry()
+            .with(layers)
+            .try_init()
+            .map_err(io::Error::other)?;
+        Ok(LoggingGuard {
+            _worker: worker,
+        })
+    }
+}
+
+/// Keeps [`LoggingBuilder`]'s file writer running; dropping it emits any
+/// pending [rate-limit summaries](crate::flush_emit_summaries), then
+/// flushes what is buffered and stops it.
+#[derive(Debug)]
+#[must_use = "buffered file output is lost when the guard is dropped"]
+pub struct LoggingGuard {
+    _worker: Option<WorkerGuard>,
+}
+
+impl Drop for LoggingGuard {
+    fn drop(&mut self) {
+        crate::flush_emit_summaries();
+    }
+}
+
+/// Installs a thread-local subscriber writing one JSON object per event to
+/// `writer`, filtered by `filter` (`EnvFilter` syntax, e.g. `"error"` or
+/// `"errors_lib=warn"`). Dropping the guard uninstalls it.
+///
+/// An unparsable `filter` falls back to `"off"`. With the `span-trace`
+/// feature the subscriber also records spans for `ApiError::span_trace`.
+#[must_use = "the subscriber is uninstalled when the guard is dropped"]
+pub fn init_logging_with_writer<W>(writer: W, filter: &str) -> DefaultGuard
+where
+    W: io::Write + Send + Sync + 'static,
+{
+    let filter = EnvFilter::try_new(filter).unwrap_or_else(|_| EnvFilter::new("off"));
+    let subscriber = tracing_subscriber::registry().with(
+        fmt::layer()
+            .json()
+            .with_writer(Mutex::new(writer))
+            .with_filter(filter),
+    );
+    #[cfg(feature = "span-trace")]
+    let subscriber = subscriber.with(tracing_error::ErrorLayer::default());
+    tracing::subscriber::set_default(subscriber)
+}
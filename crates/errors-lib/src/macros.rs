@@ -0,0 +1,166 @@
+/*
+ * Declarative helpers for consuming crates.
+ *
+ * lib_error! writes the snafu + miette boilerplate every consumer repeats
+ * (see errors-cli's errors.rs) and always adds the `Io` variant, so `?` on
+ * an io::Error works in functions returning the generated enum.
+ * lib_bail! and lib_ensure! are the early returns for functions returning
+ * a LibResult, taking the error context value itself. attach_location!
+ * records where a report passed through, which #[track_caller] can't do
+ * for attachments made across async boundaries.
+ */
+
+/// Defines an error enum ready to wrap in a [`LibReport`](crate::LibReport).
+///
+/// Each variant lists its display string and, optionally, a diagnostic code
+/// and help text. The enum derives `Debug`, `Snafu` and `Diagnostic`, and
+/// gains a final `Io { source: std::io::Error }` variant with
+/// `#[snafu(context(false))]` (code `io::error`), which provides
+/// `From<std::io::Error>`.
+///
+/// ```rust
+/// use errors_lib::{lib_error, miette};
+///
+/// lib_error! {
+///     #[snafu(visibility(pub))]
+///     pub enum AppError {
+///         /// Config file could not be parsed.
+///         ConfigParseError { path: String } => {
+///             display: "Failed to parse config at {path}",
+///             code: config::invalid_format,
+///             help: "Ensure the configuration file is valid JSON.",
+///         },
+///         NetworkTimeout { timeout: u64 } => {
+///             display: "Network timeout after {timeout}s",
+///             code: network::timeout,
+///         },
+///         Cancelled => { display: "Cancelled by user" },
+///     }
+/// }
+///
+/// fn read() -> Result<String, AppError> {
+///     Ok(std::fs::read_to_string("config.json")?)
+/// }
+/// # let _ = read();
+/// ```
+///
+/// miette's derive refers to `miette::...`, so the calling module needs
+/// `miette` in scope — as a dependency or via `use errors_lib::miette;`.
+#[macro_export]
+macro_rules! lib_error {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$vmeta:meta])*
+                $variant:ident $({ $($field:ident : $fty:ty),* $(,)? })? => {
+                    display: $display:literal
+                    $(, code: $($code:ident)::+)?
+                    $(, help: $help:literal)?
+                    $(,)?
+                }
+            ),* $(,)?
+        }
+    ) => {
+        #[derive(Debug, $crate::Snafu, $crate::miette::Diagnostic)]
+        #[snafu(crate_root($crate::snafu))]
+        $(#[$meta])*
+        $vis enum $name {
+            $(
+                $(#[$vmeta])*
+                #[snafu(display($display))]
+                $(#[diagnostic(code($($code)::+))])?
+                $(#[diagnostic(help($help))])?
+                $variant $({ $($field : $fty),* })?,
+            )*
+
+            /// Wraps `std::io::Error`; provides `From<std::io::Error>`.
+            #[snafu(context(false))]
+            #[snafu(display("IO error: {source}"))]
+            #[diagnostic(code(io::error))]
+            Io { source: ::std::io::Error },
+        }
+    };
+}
+
+/// Returns early with `err` wrapped in a [`LibReport`](crate::LibReport),
+/// from a function returning [`LibResult`](crate::LibResult).
+///
+/// The report's location is the macro call.
+///
+/// ```rust
+/// use errors_lib::{LibResult, lib_bail, lib_error, miette};
+///
+/// lib_error! {
+///     pub enum AppError {
+///         NetworkTimeout { timeout: u64 } => {
+///             display: "Network timeout after {timeout}s",
+///             code: network::timeout,
+///         },
+///     }
+/// }
+///
+/// fn fetch() -> LibResult<String, AppError> {
+///     lib_bail!(AppError::NetworkTimeout { timeout: 30 });
+/// }
+/// assert!(fetch().is_err());
+/// ```
+#[macro_export]
+macro_rules! lib_bail {
+    ($err:expr $(,)?) => {
+        return ::core::result::Result::Err($crate::LibReport::new($crate::rootcause::Report::new(
+            $err,
+        )))
+    };
+}
+
+/// Returns early with `err`, as [`lib_bail!`], unless `cond` holds.
+///
+/// ```rust
+/// use errors_lib::{LibResult, lib_ensure, lib_error, miette};
+///
+/// lib_error! {
+///     pub enum AppError {
+///         Empty => { display: "Nothing to parse", code: config::empty },
+///     }
+/// }
+///
+/// fn parse(input: &str) -> LibResult<usize, AppError> {
+///     lib_ensure!(!input.is_empty(), AppError::Empty);
+///     Ok(input.len())
+/// }
+/// assert_eq!(parse("{}").unwrap(), 2);
+/// assert!(parse("").is_err());
+/// ```
+#[macro_export]
+macro_rules! lib_ensure {
+    ($cond:expr, $err:expr $(,)?) => {
+        if !$cond {
+            $crate::lib_bail!($err);
+        }
+    };
+}
+
+/// Attaches `at file:line:column` for the macro call to `report`, via
+/// [`LibReport::attach_source_location`](crate::LibReport::attach_source_location).
+///
+/// ```rust
+/// use errors_lib::{LibReport, ReportExt, attach_location, lib_error, miette, rootcause::Report};
+///
+/// lib_error! {
+///     pub enum AppError {
+///         Empty => { display: "Nothing to parse", code: config::empty },
+///     }
+/// }
+///
+/// let report: LibReport<AppError> = Report::new(AppError::Empty).into();
+/// let report = attach_location!(report);
+/// let history = report.to_api_error().history;
+/// assert!(history.iter().any(|frame| frame.message.starts_with("at ")));
+/// ```
+#[macro_export]
+macro_rules! attach_location {
+    ($report:expr $(,)?) => {
+        $report.attach_source_location(::core::file!(), ::core::line!(), ::core::column!())
+    };
+}
@@ -0,0 +1,106 @@
+/*
+ * Markdown rendering for LibReport.
+ *
+ * For posting errors to GitHub Issues, Jira, Notion and the like: plain
+ * Markdown with no ANSI escapes, built from the same pieces miette renders
+ * (title, code, help, labelled source) plus the attachment history.
+ */
+
+use std::fmt::{self, Write};
+
+use miette::{Diagnostic, LabeledSpan, SourceCode};
+
+use crate::{LibReport, history_of};
+
+impl<E, O> LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    /// Renders the report as Markdown:
+    ///
+    /// ~~~text
+    /// ## Error: Failed to parse config at config.json
+    ///
+    /// **Code:** `config::invalid_format`
+    /// **Help:** Ensure the configuration file is valid JSON.
+    ///
+    /// ```
+    /// { "key": !!invalid }
+    ///           ^^^^^^^^^ syntax error here
+    /// ```
+    ///
+    /// ### History
+    ///
+    /// - The application cannot proceed without a valid config.
+    /// ~~~
+    ///
+    /// Code, help, source and history are omitted when absent.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let ctx = self.0.current_context();
+        let mut out = String::new();
+
+        // Writing into a String cannot fail.
+        let _ = writeln!(out, "## Error: {ctx}");
+
+        let code = ctx.code();
+        let help = ctx.help();
+        if code.is_some() || help.is_some() {
+            out.push('\n');
+        }
+        if let Some(code) = code {
+            let _ = writeln!(out, "**Code:** `{code}`");
+        }
+        if let Some(help) = help {
+            let _ = writeln!(out, "**Help:** {help}");
+        }
+
+        if let (Some(source), Some(labels)) = (ctx.source_code(), ctx.labels()) {
+            for label in labels {
+                write_snippet(&mut out, source, &label);
+            }
+        }
+
+        let history = history_of(&self.0);
+        if !history.is_empty() {
+            out.push_str("\n### History\n\n");
+            for frame in history {
+                let _ = writeln!(out, "- {}", frame.message);
+            }
+        }
+
+        out
+    }
+}
+
+/// Writes the label's line, with one line of context either side, as a
+/// fenced block. The label text underlines its span.
+fn write_snippet(out: &mut String, source: &dyn SourceCode, label: &LabeledSpan) {
+    let Ok(contents) = source.read_span(label.inner(), 1, 1) else {
+        return;
+    };
+    let snippet = String::from_utf8_lossy(contents.data());
+
+    let _ = writeln!(out, "\n```{}", contents.language().unwrap_or_default());
+    let mut line_start = contents.span().offset();
+    for line in snippet.split_inclusive('\n') {
+        let _ = writeln!(out, "{}", line.trim_end());
+
+        let line_end = line_start + line.len();
+        if (line_start..line_end).contains(&label.offset()) {
+            let _ = write!(
+                out,
+                "{}{}",
+                " ".repeat(label.offset() - line_start),
+                "^".repeat(label.len().max(1))
+            );
+            if let Some(text) = label.label() {
+                let _ = write!(out, " {text}");
+            }
+            out.push('\n');
+        }
+        line_start = line_end;
+    }
+    out.push_str("```\n");
+}
@@ -0,0 +1,48 @@
+/*
+ * Metrics per error code (`metrics` feature).
+ *
+ * Every emitted ApiError is counted through the `metrics` facade, so
+ * dashboards get errors_total{code, severity} without parsing logs. The
+ * count is taken before any emit rate limit, so throttled logging doesn't
+ * skew it. Whichever recorder the application installs (Prometheus,
+ * StatsD, ...) receives the values.
+ */
+
+use ::metrics::{Unit, counter, describe_counter, describe_histogram, histogram};
+
+use crate::ApiError;
+
+/// Counter of emitted errors, labelled by `code` (`uncoded` without one)
+/// and `severity` (`error` unless the report declares another).
+pub const ERRORS_TOTAL: &str = "errors_total";
+
+/// Histogram of history frames per emitted error, labelled by `code`.
+pub const ERROR_HISTORY_LENGTH: &str = "error_history_length";
+
+/// Registers the metric descriptions with the installed recorder; call it
+/// once, after installing the recorder.
+pub fn describe() {
+    describe_counter!(
+        ERRORS_TOTAL,
+        Unit::Count,
+        "Errors emitted through to_api_error, by code and severity"
+    );
+    describe_histogram!(
+        ERROR_HISTORY_LENGTH,
+        Unit::Count,
+        "History frames per emitted error"
+    );
+}
+
+/// Counts `error` and records its history length.
+pub(crate) fn record(error: &ApiError) {
+    let code = error.code.as_deref().unwrap_or("uncoded").to_string();
+    let severity = error
+        .severity
+        .clone()
+        .unwrap_or_else(|| "error".to_string());
+    let frames = u32::try_from(error.history.len()).unwrap_or(u32::MAX);
+
+    counter!(ERRORS_TOTAL, "code" => code.clone(), "severity" => severity).increment(1);
+    histogram!(ERROR_HISTORY_LENGTH, "code" => code).record(f64::from(frames));
+}
@@ -0,0 +1,111 @@
+/*
+ * MultiError: several independent failures as one report.
+ *
+ * Validation and batch code collects failures rather than stopping at the
+ * first. LibReport::join puts them side by side under a MultiError root:
+ * each one is a child in the rootcause tree, so history, retry
+ * classification and classify_error reach every branch. The context
+ * keeps typed handles to the same reports so miette renders each with its
+ * own code, help and labels.
+ */
+
+use std::fmt;
+
+use miette::Diagnostic;
+
+use crate::{ClonableLibReport, LibReport};
+
+/// The context of a joined report: "N errors occurred", with each error
+/// exposed through [`Diagnostic::related`].
+pub struct MultiError<E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    errors: Vec<ClonableLibReport<E>>,
+}
+
+impl<E> MultiError<E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    /// The joined reports, in the order given to [`LibReport::join`].
+    #[must_use]
+    pub fn errors(&self) -> &[ClonableLibReport<E>] {
+        &self.errors
+    }
+
+    /// How many reports were joined.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Always false: [`LibReport::join`] never builds an empty one.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl<E> fmt::Debug for MultiError<E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.errors).finish()
+    }
+}
+
+impl<E> fmt::Display for MultiError<E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} errors occurred", self.errors.len())
+    }
+}
+
+impl<E> std::error::Error for MultiError<E> where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static
+{
+}
+
+impl<E> Diagnostic for MultiError<E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        Some(Box::new(
+            self.errors.iter().map(|report| report as &dyn Diagnostic),
+        ))
+    }
+}
+
+impl<E> LibReport<MultiError<E>>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    /// Joins independent failures into one report whose children are
+    /// `reports`, in order; `None` when there is nothing to join.
+    #[track_caller]
+    #[must_use]
+    pub fn join(reports: Vec<LibReport<E>>) -> Option<Self> {
+        if reports.is_empty() {
+            return None;
+        }
+
+        let errors: Vec<_> = reports.into_iter().map(LibReport::into_cloneable).collect();
+        let children: Vec<_> = errors
+            .iter()
+            .map(|report| report.0.clone().into_dynamic())
+            .collect();
+
+        let mut report = rootcause::Report::new(MultiError {
+            errors,
+        });
+        for child in children {
+            report.children_mut().push(child);
+        }
+        Some(Self::new(report))
+    }
+}
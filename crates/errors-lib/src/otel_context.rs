@@ -0,0 +1,24 @@
+/*
+ * OpenTelemetry trace and span IDs (`otel` feature).
+ *
+ * With tracing-opentelemetry's layer installed, every tracing span has an
+ * OTel span behind it. Stamping its IDs on an ApiError links the JSON
+ * error straight to the distributed trace it happened in.
+ */
+
+use opentelemetry::trace::TraceContextExt;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// The current span's trace and span IDs as lowercase hex, or `None`
+/// outside any span or without the OpenTelemetry layer.
+pub fn capture() -> Option<(String, String)> {
+    let context = tracing::Span::current().context();
+    let span = context.span();
+    let span_context = span.span_context();
+    span_context.is_valid().then(|| {
+        (
+            span_context.trace_id().to_string(),
+            span_context.span_id().to_string(),
+        )
+    })
+}
@@ -0,0 +1,83 @@
+/*
+ * Panics as ApiError log lines.
+ *
+ * Handled errors reach the logs as structured ApiError events; panics
+ * bypass that path entirely. The hook installed here builds the same
+ * ApiError for a panic — message as title, code `panic`, backtrace as
+ * history — and emits it to the ErrorSinkRegistry like any to_api_error
+ * call, before handing over to the previous hook, so color-eyre or miette
+ * still print their reports.
+ */
+
+use std::{
+    any::Any,
+    backtrace::{Backtrace, BacktraceStatus},
+    panic::{self, PanicHookInfo},
+};
+
+use crate::{ApiError, ErrorFrame, ErrorSinkRegistry, ErrorStats, build_api_error, sink};
+
+/// The code given to `ApiError`s built from panics.
+pub const PANIC_CODE: &str = "panic";
+
+/// Chains a panic hook that emits each panic as an `ApiError` to every
+/// sink in the [`ErrorSinkRegistry`] (the default [`TracingSink`] logs it
+/// at `ERROR`), then runs whichever hook was installed before.
+///
+/// Install it last: hooks that don't chain (like miette's) would otherwise
+/// replace it. The backtrace follows `RUST_BACKTRACE` as usual.
+///
+/// Sinks must not panic once this is installed. A panic inside a panic
+/// hook can't be caught, so a sink panicking on the hook's `ApiError`
+/// aborts the process. A sink panicking on an ordinary error is still
+/// caught and skipped: the hook sees sinks already running on the thread
+/// and leaves that panic to the previous hook alone.
+///
+/// [`TracingSink`]: crate::TracingSink
+pub fn install_panic_to_api_error() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if !sink::delivering() {
+            let api_err = panic_to_api_error(info);
+            ErrorStats::global().record(PANIC_CODE);
+            ErrorSinkRegistry::emit(&api_err);
+        }
+        previous(info);
+    }));
+}
+
+/// Builds the `ApiError` for a panic without emitting it.
+#[must_use]
+pub fn panic_to_api_error(info: &PanicHookInfo<'_>) -> ApiError {
+    let backtrace = Backtrace::capture();
+    let history = if backtrace.status() == BacktraceStatus::Captured {
+        backtrace
+            .to_string()
+            .lines()
+            .map(|line| ErrorFrame {
+                message: line.trim().to_owned(),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    build_api_error(
+        history,
+        panic_message(info.payload()),
+        Some(PANIC_CODE.to_owned()),
+        None,
+        None,
+        false,
+        info.location().map(ToString::to_string),
+    )
+}
+
+/// The `panic!` message, for the payload types `panic!` produces.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| (*message).to_owned())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_owned())
+}
@@ -0,0 +1,114 @@
+/*
+ * Diagnostic code patterns.
+ *
+ * Everything keyed by diagnostic code — exit statuses, JSON-RPC codes,
+ * handlers, docs links, code queries — takes the same patterns: a whole
+ * code, or a namespace prefix ending in `::*`. That prefix only matches at
+ * a `::` boundary, so `config::*` covers `config::io::denied` but not
+ * `configuration::missing`; `config::` is read the same way, since a code
+ * never ends in `::`. Any other trailing `*` is a plain prefix: `conf*`
+ * covers both. Where several patterns match, an exact one beats any
+ * prefix and a longer prefix beats a shorter one.
+ */
+
+use std::fmt;
+
+/// A whole code (`config::invalid_format`) or a prefix: a namespace ending
+/// in `::*` (`config::*`, matching every `config::` code) or `::`
+/// (`config::`, the same), or anything else ending in `*` (`conf*`,
+/// matching every code starting with `conf`).
+///
+/// ```
+/// use errors_lib::CodePattern;
+///
+/// let config = CodePattern::new("config::*");
+/// assert!(config.matches("config::io::denied"));
+/// assert!(!config.matches("configuration::missing"));
+/// assert!(CodePattern::new("config::").matches("config::io::denied"));
+/// assert!(CodePattern::new("config*").matches("configuration::missing"));
+///
+/// let rules = [(CodePattern::new("config::*"), 78), (CodePattern::new("config::io::*"), 74)];
+/// assert_eq!(CodePattern::best(&rules, "config::io::denied"), Some(&74));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CodePattern(String);
+
+impl CodePattern {
+    /// Parses `pattern`; every string is a valid pattern.
+    #[must_use]
+    pub fn new(pattern: impl Into<String>) -> Self {
+        Self(pattern.into())
+    }
+
+    /// The pattern as written.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// For a prefix pattern, what codes must start with: `ns::` for
+    /// `ns::*` or `ns::`, `conf` for `conf*`.
+    #[must_use]
+    pub fn prefix(&self) -> Option<&str> {
+        prefix_of(&self.0)
+    }
+
+    /// Whether the pattern is a prefix rather than a whole code.
+    #[must_use]
+    pub fn is_prefix(&self) -> bool {
+        self.prefix().is_some()
+    }
+
+    /// Whether `code` matches.
+    #[must_use]
+    pub fn matches(&self, code: &str) -> bool {
+        self.rank(code).is_some()
+    }
+
+    /// How closely `code` matches, when it does: higher is more specific.
+    fn rank(&self, code: &str) -> Option<usize> {
+        match self.prefix() {
+            Some(prefix) => code.starts_with(prefix).then_some(prefix.len()),
+            None => (self.0 == code).then_some(usize::MAX),
+        }
+    }
+
+    /// The value of the most specific pattern in `rules` matching `code`.
+    #[must_use]
+    pub fn best<'a, T>(rules: impl IntoIterator<Item = &'a (Self, T)>, code: &str) -> Option<&'a T>
+    where
+        T: 'a,
+    {
+        rules
+            .into_iter()
+            .filter_map(|(pattern, value)| Some((pattern.rank(code)?, value)))
+            .max_by_key(|&(rank, _)| rank)
+            .map(|(_, value)| value)
+    }
+}
+
+/// What codes matching a prefix pattern start with, for matching without a
+/// [`CodePattern`] in hand.
+pub(crate) fn prefix_of(pattern: &str) -> Option<&str> {
+    pattern
+        .strip_suffix('*')
+        .or_else(|| pattern.ends_with("::").then_some(pattern))
+}
+
+impl From<&str> for CodePattern {
+    fn from(pattern: &str) -> Self {
+        Self::new(pattern)
+    }
+}
+
+impl From<String> for CodePattern {
+    fn from(pattern: String) -> Self {
+        Self::new(pattern)
+    }
+}
+
+impl fmt::Display for CodePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
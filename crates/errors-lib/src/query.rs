@@ -0,0 +1,240 @@
+/*
+ * Query helpers: "is there an X anywhere in here?"
+ *
+ * Contexts are matched by type on every node. Codes come from the nodes
+ * whose type the report knows (E for a LibReport<E>, the original context
+ * type for a DynLibReport made by into_dynamic), plus whatever those
+ * diagnostics list as related, e.g. the members of a MultiError.
+ * LibReport::contexts hands those typed nodes out directly. find_error
+ * also looks inside each context's source() chain, where a wrapped
+ * io::Error usually sits. code_matches checks only the top-level code
+ * against a CodePattern, comparing against its Display output as it is
+ * written rather than rendering it to a String first.
+ */
+
+use std::{error::Error, fmt};
+
+use miette::Diagnostic;
+use rootcause::{
+    ReportCollection,
+    markers::{Cloneable, Dynamic},
+};
+
+use crate::{
+    DiagnosticView, DynLibReport, ErrorCode, LibReport, pattern::prefix_of, source_of, view_as,
+};
+
+impl<E, O> LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    /// The first context of type `T`, searching depth-first from the
+    /// top-level node.
+    #[must_use]
+    pub fn find_context<T: 'static>(&self) -> Option<&T> {
+        self.0
+            .iter_reports()
+            .find_map(|node| node.downcast_current_context())
+    }
+
+    /// Whether any context of type `T` satisfies `pred`.
+    pub fn any_context<T: 'static>(&self, pred: impl Fn(&T) -> bool) -> bool {
+        self.0
+            .iter_reports()
+            .filter_map(|node| node.downcast_current_context())
+            .any(pred)
+    }
+
+    /// The first error of type `T`, searching depth-first from the
+    /// top-level node: a node's context, or any error in its `source()`
+    /// chain.
+    #[must_use]
+    pub fn find_error<T: Error + 'static>(&self) -> Option<&T> {
+        self.0.iter_reports().find_map(|node| {
+            node.downcast_current_context().or_else(|| {
+                std::iter::successors(source_of::<E>(node), |&err| err.source())
+                    .find_map(|err| err.downcast_ref())
+            })
+        })
+    }
+
+    /// The context of every node whose context is an `E`, depth-first
+    /// from the top-level node, as diagnostics, e.g. for a custom renderer.
+    pub fn contexts(&self) -> impl Iterator<Item = &dyn Diagnostic> {
+        self.0
+            .iter_reports()
+            .filter_map(|node| node.downcast_current_context::<E>())
+            .map(|ctx| ctx as &dyn Diagnostic)
+    }
+
+    /// The top-level diagnostic code as an [`ErrorCode`], well-formed or
+    /// not; `None` without a code.
+    #[must_use]
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        self.code()
+            .and_then(|code| ErrorCode::lenient(code.to_string()))
+    }
+
+    /// Whether the top-level diagnostic code matches `pattern`, read as a
+    /// [`CodePattern`](crate::CodePattern): the whole code, or a prefix
+    /// ending in `::*` (`network::*` matches `network::timeout`, not
+    /// `networking::down`) or in a bare `*` (`network*` matches both). A
+    /// report without a code matches nothing.
+    #[must_use]
+    pub fn code_matches(&self, pattern: &str) -> bool {
+        self.code()
+            .is_some_and(|code| display_matches(&code, pattern))
+    }
+
+    /// Whether any node in the tree carries the diagnostic code `code`.
+    #[must_use]
+    pub fn contains_code(&self, code: &str) -> bool {
+        self.codes().iter().any(|c| c == code)
+    }
+
+    /// Every diagnostic code in the tree, depth-first, each listed once.
+    #[must_use]
+    pub fn codes(&self) -> Vec<String> {
+        let mut codes = Vec::new();
+        collect_codes(
+            Some(self.0.current_context()),
+            self.0.children(),
+            view_as::<E, Cloneable>,
+            &mut codes,
+        );
+        codes
+    }
+}
+
+impl DynLibReport {
+    /// The first context of type `T`, searching depth-first from the
+    /// top-level node.
+    #[must_use]
+    pub fn find_context<T: 'static>(&self) -> Option<&T> {
+        self.0
+            .iter_reports()
+            .find_map(|node| node.downcast_current_context())
+    }
+
+    /// Whether any context of type `T` satisfies `pred`.
+    pub fn any_context<T: 'static>(&self, pred: impl Fn(&T) -> bool) -> bool {
+        self.0
+            .iter_reports()
+            .filter_map(|node| node.downcast_current_context())
+            .any(pred)
+    }
+
+    /// The first error of type `T`, searching depth-first from the
+    /// top-level node: a node's context, or any error in its `source()`
+    /// chain.
+    #[must_use]
+    pub fn find_error<T: Error + 'static>(&self) -> Option<&T> {
+        self.0.iter_reports().enumerate().find_map(|(depth, node)| {
+            let source = match self.context() {
+                Some(root) if depth == 0 => Error::source(root),
+                _ => node.current_context_error_source(),
+            };
+            node.downcast_current_context().or_else(|| {
+                std::iter::successors(source, |&err| err.source())
+                    .find_map(|err| err.downcast_ref())
+            })
+        })
+    }
+
+    /// The top-level diagnostic code as an [`ErrorCode`]; see
+    /// [`LibReport::error_code`].
+    #[must_use]
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        self.code()
+            .and_then(|code| ErrorCode::lenient(code.to_string()))
+    }
+
+    /// Whether the top-level diagnostic code matches `pattern`; see
+    /// [`LibReport::code_matches`].
+    #[must_use]
+    pub fn code_matches(&self, pattern: &str) -> bool {
+        self.code()
+            .is_some_and(|code| display_matches(&code, pattern))
+    }
+
+    /// Whether any node whose type is known carries the diagnostic code
+    /// `code`.
+    #[must_use]
+    pub fn contains_code(&self, code: &str) -> bool {
+        self.codes().iter().any(|c| c == code)
+    }
+
+    /// Every diagnostic code among the nodes whose type is known,
+    /// depth-first, each listed once.
+    #[must_use]
+    pub fn codes(&self) -> Vec<String> {
+        let mut codes = Vec::new();
+        collect_codes(
+            self.context(),
+            self.0.children(),
+            self.children_view(),
+            &mut codes,
+        );
+        codes
+    }
+}
+
+/// Adds the codes of one node (and its related diagnostics), then of its
+/// children, seen through `view`.
+fn collect_codes(
+    diagnostic: Option<&dyn Diagnostic>,
+    children: &ReportCollection<Dynamic, Cloneable>,
+    view: DiagnosticView,
+    codes: &mut Vec<String>,
+) {
+    if let Some(diagnostic) = diagnostic {
+        collect_diagnostic_codes(diagnostic, codes);
+    }
+    for child in children.iter() {
+        let child = child.clone_arc();
+        collect_codes(view(&child), child.children(), view, codes);
+    }
+}
+
+fn collect_diagnostic_codes(diagnostic: &dyn Diagnostic, codes: &mut Vec<String>) {
+    if let Some(code) = diagnostic.code().map(|code| code.to_string())
+        && !codes.contains(&code)
+    {
+        codes.push(code);
+    }
+    for related in diagnostic.related().into_iter().flatten() {
+        collect_diagnostic_codes(related, codes);
+    }
+}
+
+/// Whether `value`'s Display output matches `pattern` (see
+/// [`LibReport::code_matches`]), checked chunk by chunk as it is written.
+fn display_matches(value: &dyn fmt::Display, pattern: &str) -> bool {
+    let (expected, prefix) = prefix_of(pattern).map_or((pattern, false), |prefix| (prefix, true));
+    let mut matcher = Matcher {
+        rest: expected.as_bytes(),
+        prefix,
+    };
+    // The matcher fails the write at the first mismatch.
+    fmt::write(&mut matcher, format_args!("{value}")).is_ok() && matcher.rest.is_empty()
+}
+
+/// The part of a pattern not yet matched by the written output.
+struct Matcher<'a> {
+    rest: &'a [u8],
+    /// Output past the end of `rest` is allowed.
+    prefix: bool,
+}
+
+impl fmt::Write for Matcher<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let s = s.as_bytes();
+        let n = s.len().min(self.rest.len());
+        if s[..n] != self.rest[..n] || (n < s.len() && !self.prefix) {
+            return Err(fmt::Error);
+        }
+        self.rest = &self.rest[n..];
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+/*
+ * PII redaction for ApiError.
+ *
+ * Error messages tend to pick up user input — emails, account IDs, paths
+ * under a home directory. A RedactionConfig masks every match in the
+ * human-readable fields. The tree repeats every message and attachment,
+ * so it is redacted node by node.
+ *
+ * Sinks see an ApiError as soon as it is built, before the caller could
+ * redact it, so logs are covered by a process-wide config set with
+ * set_sink_redaction(): every error is redacted on its way to the sinks.
+ * ApiError::redact is for the copy the caller returns, e.g. in an API
+ * response. Stats and metrics count codes only and see no messages.
+ */
+
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use regex::Regex;
+
+use crate::{ApiError, ErrorFrame, ErrorTree};
+
+/// The text that replaces each redacted match.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Patterns whose matches [`ApiError::redact`] replaces with [`REDACTED`].
+#[derive(Debug, Clone, Default)]
+pub struct RedactionConfig {
+    /// Applied in order, each to the previous one's output.
+    pub patterns: Vec<Regex>,
+}
+
+impl RedactionConfig {
+    /// A config with the given patterns.
+    #[must_use]
+    pub const fn new(patterns: Vec<Regex>) -> Self {
+        Self {
+            patterns,
+        }
+    }
+
+    /// Common PII: email addresses and UUIDs.
+    ///
+    /// # Panics
+    ///
+    /// Never; the built-in patterns are known to compile.
+    #[must_use]
+    pub fn default_pii() -> Self {
+        let patterns = [
+            r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}",
+            r"(?i)\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b",
+        ];
+        Self::new(
+            patterns
+                .iter()
+                .map(|p| Regex::new(p).expect("built-in PII pattern"))
+                .collect(),
+        )
+    }
+
+    /// Adds a pattern.
+    #[must_use]
+    pub fn with_pattern(mut self, pattern: Regex) -> Self {
+        self.patterns.push(pattern);
+        self
+    }
+
+    fn apply(&self, text: &str) -> String {
+        self.patterns.iter().fold(text.to_owned(), |acc, pattern| {
+            pattern.replace_all(&acc, REDACTED).into_owned()
+        })
+    }
+
+    fn apply_all(&self, texts: &[String]) -> Vec<String> {
+        texts.iter().map(|text| self.apply(text)).collect()
+    }
+
+    fn apply_values(&self, map: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+        map.iter()
+            .map(|(key, value)| (key.clone(), self.apply(value)))
+            .collect()
+    }
+
+    fn apply_tree(&self, tree: &ErrorTree) -> ErrorTree {
+        match tree {
+            ErrorTree::Leaf {
+                message,
+                code,
+                attachments,
+            } => ErrorTree::Leaf {
+                message: self.apply(message),
+                code: code.clone(),
+                attachments: self.apply_all(attachments),
+            },
+            ErrorTree::Node {
+                message,
+                code,
+                attachments,
+                children,
+            } => ErrorTree::Node {
+                message: self.apply(message),
+                code: code.clone(),
+                attachments: self.apply_all(attachments),
+                children: children.iter().map(|child| self.apply_tree(child)).collect(),
+            },
+        }
+    }
+}
+
+fn sink_redaction() -> &'static RwLock<Option<Arc<RedactionConfig>>> {
+    static CONFIG: RwLock<Option<Arc<RedactionConfig>>> = RwLock::new(None);
+    &CONFIG
+}
+
+/// Redacts every `ApiError` with `config` before it reaches the sinks
+/// ([`ErrorSinkRegistry`](crate::ErrorSinkRegistry)), tracing included.
+/// What `to_api_error` returns to the caller is left as built.
+pub fn set_sink_redaction(config: RedactionConfig) {
+    *sink_redaction()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(config));
+}
+
+/// Undoes [`set_sink_redaction`]; sinks see errors unredacted again.
+pub fn clear_sink_redaction() {
+    *sink_redaction()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// `error` as the sinks should see it.
+pub(crate) fn for_sinks(error: &ApiError) -> Cow<'_, ApiError> {
+    let config = sink_redaction()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    config.map_or(Cow::Borrowed(error), |config| {
+        Cow::Owned(error.redact(&config))
+    })
+}
+
+impl ApiError {
+    /// A copy with every `config` match in `title`, `summary`, `help`, the
+    /// history messages, the values of `fields` and `extra`, the span trace
+    /// and the tree's messages and attachments replaced by [`REDACTED`].
+    #[must_use]
+    pub fn redact(&self, config: &RedactionConfig) -> Self {
+        Self {
+            title: config.apply(&self.title),
+            summary: self.summary.as_deref().map(|s| config.apply(s)),
+            help: self.help.as_deref().map(|h| config.apply(h)),
+            history: self
+                .history
+                .iter()
+                .map(|frame| ErrorFrame {
+                    message: config.apply(&frame.message),
+                })
+                .collect(),
+            fields: config.apply_values(&self.fields),
+            extra: config.apply_values(&self.extra),
+            span_trace: self.span_trace.as_deref().map(|s| config.apply_all(s)),
+            tree: self.tree.as_ref().map(|t| config.apply_tree(t)),
+            ..self.clone()
+        }
+    }
+}
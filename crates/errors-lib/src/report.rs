@@ -0,0 +1,1392 @@
+/*
+ * LibReport and the std machinery around it.
+ *
+ * Rendering, Diagnostic plumbing, and building an ApiError from a report
+ * (build metadata via env!, correlation IDs via nanoid, emission via
+ * tracing) all need std, so they live here behind the `std` feature; the
+ * ApiError data model itself is in api_error.rs.
+ */
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
+    fmt,
+    io::{self, IsTerminal},
+    panic::{RefUnwindSafe, UnwindSafe},
+    sync::{OnceLock, PoisonError, RwLock},
+};
+
+use miette::{
+    Diagnostic, GraphicalReportHandler, GraphicalTheme, NarratableReportHandler, SourceCode,
+};
+use nanoid::nanoid;
+use rootcause::{
+    Report, ReportCollection,
+    markers::{Cloneable, Dynamic, Mutable},
+};
+use tracing::Level;
+
+#[cfg(feature = "sentry")]
+use crate::compat_sentry;
+#[cfg(feature = "otel")]
+use crate::otel_context;
+#[cfg(feature = "span-trace")]
+use crate::span_trace;
+use crate::{
+    ApiError, CorrelationId, DynLibReport, ErrorCode, ErrorFrame, ErrorSinkRegistry, ErrorStats,
+    ErrorTree, ReportLocation, RetryPolicy, attachments, docs, docs_url_for, emit, exit_code,
+    global_fields, messages, retry, summary_of, walk_report,
+};
+
+// ---------------------------------------------------------------------------
+// Core types
+// ---------------------------------------------------------------------------
+
+/// A miette-compatible wrapper around a rootcause error chain.
+///
+/// `E` is the top-level error context type — defined by the consuming crate,
+/// not by this library. It must implement `Diagnostic` (for miette rendering)
+/// and `std::error::Error`.
+///
+/// Construct with [`LibReport::new`] or `Report::into()`, which also record
+/// the calling location (see [`ReportLocation`]) and attach the cache that
+/// miette borrows the children's diagnostics from (`related()`,
+/// `diagnostic_source()`). A report wrapped directly, as `LibReport(report)`,
+/// has no cache to borrow from, so miette shows the descendants whose
+/// context is an `E` (the first as the source, the rest as related) and
+/// leaves out the others, and the context's own `source()` chain.
+///
+/// `O` is the rootcause ownership marker. The default, `Mutable`, can still
+/// be extended with attachments and context; see [`ClonableLibReport`] for
+/// the shareable form.
+pub struct LibReport<E, O = Mutable>(pub Report<E, O>)
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static;
+
+/// A [`LibReport`] over a cloneable rootcause report.
+///
+/// Cloning is cheap — the tree is reference-counted — so one error can be
+/// logged on one thread and returned on another. Every clone is the same
+/// error: `to_api_error()` yields the same title and history, but each
+/// call still mints a fresh correlation ID.
+pub type ClonableLibReport<E> = LibReport<E, Cloneable>;
+
+impl<E, O> LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    /// The diagnostic cache on the top-level node, if the report was built
+    /// through [`LibReport::wrap`].
+    fn cache(&self) -> Option<&DiagnosticCache> {
+        self.0
+            .attachments()
+            .iter()
+            .find_map(|attachment| attachment.downcast_inner::<DiagnosticCache>())
+    }
+
+    /// Renders the report through miette's graphical handler, including
+    /// source snippets and labels, for capture into logs.
+    ///
+    /// Pass `ansi = false` for plain output suitable for log files.
+    #[must_use]
+    pub fn render_pretty(&self, ansi: bool) -> String {
+        let handler = if ansi {
+            GraphicalReportHandler::new_themed(GraphicalTheme::unicode())
+        } else {
+            // Syntax highlighting emits its own escapes regardless of theme.
+            GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+                .with_links(false)
+                .without_syntax_highlighting()
+        };
+
+        let mut out = String::new();
+        // Writing into a String cannot fail.
+        let _ = handler.render_report(&mut out, self);
+        out
+    }
+}
+
+impl<E> LibReport<E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    /// Wraps `report`, attaching an empty diagnostic cache to the top-level
+    /// node unless it already carries one. The cache is a typed payload, so
+    /// it stays out of `ApiError::history`, and cloneable reports share it.
+    pub(crate) fn wrap(report: Report<E>) -> Self {
+        let wrapped = Self(report);
+        if wrapped.cache().is_some() {
+            return wrapped;
+        }
+        Self(attachments::attach_opaque(wrapped.0, DiagnosticCache::new()))
+    }
+
+    /// Wraps a rootcause report for framework integration, attaching the
+    /// caller's location as a [`ReportLocation`], and the active spans as a
+    /// `ReportSpanTrace` when `set_span_trace_capture` is on (`span-trace`
+    /// feature).
+    #[must_use]
+    #[track_caller]
+    pub fn new(report: Report<E>) -> Self {
+        Self::located(report, ReportLocation::caller())
+    }
+
+    /// [`LibReport::new`] with an explicit creation location, for reports
+    /// built away from the call that should be blamed.
+    pub(crate) fn located(report: Report<E>, location: ReportLocation) -> Self {
+        let report = Self::wrap(report).attach_typed(location);
+        #[cfg(feature = "span-trace")]
+        if span_trace::span_trace_capture()
+            && let Some(trace) = span_trace::ReportSpanTrace::capture()
+        {
+            return report.attach_typed(trace);
+        }
+        report
+    }
+
+    /// `Err(self)`, for `return report.into_err();` where `T` comes from
+    /// the function's return type.
+    ///
+    /// # Errors
+    ///
+    /// Always returns `Err(self)`.
+    pub const fn into_err<T>(self) -> LibResult<T, E> {
+        Err(self)
+    }
+
+    /// `Ok(value)` as a [`LibResult`] over `E`, the counterpart of
+    /// [`LibReport::into_err`].
+    ///
+    /// # Errors
+    ///
+    /// Never; always returns `Ok(value)`.
+    pub const fn ok<T>(value: T) -> LibResult<T, E> {
+        Ok(value)
+    }
+
+    /// Erases the context type, keeping the whole chain.
+    ///
+    /// The result still exposes `E`'s code, help and labels wherever a node
+    /// downcasts to `E`; other nodes render by `Display` alone.
+    #[must_use]
+    pub fn into_dynamic(self) -> DynLibReport {
+        DynLibReport::with_views(
+            self.0.into_dynamic(),
+            view_as::<E, Mutable>,
+            view_as::<E, Cloneable>,
+        )
+    }
+
+    /// Converts into the cheaply cloneable form. The report can no longer
+    /// be mutated afterwards.
+    #[must_use]
+    pub fn into_cloneable(self) -> ClonableLibReport<E> {
+        LibReport(self.0.into_cloneable())
+    }
+
+    /// Converts the context in place, keeping its attachments (including
+    /// the original [`ReportLocation`]) and children.
+    ///
+    /// Use when the new context replaces the old one outright; to record
+    /// the old one as the cause, use [`LibReport::change_context`].
+    #[must_use]
+    pub fn map_context<F>(self, f: impl FnOnce(E) -> F) -> LibReport<F>
+    where
+        F: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    {
+        let (context, children, attachments) = self.0.into_parts();
+        LibReport::wrap(Report::from_parts(f(context), children, attachments))
+    }
+
+    /// Puts a new context on top, pushing this report down as its child —
+    /// the error-stack style for crossing module boundaries.
+    #[must_use]
+    #[track_caller]
+    pub fn change_context<F>(self, context: F) -> LibReport<F>
+    where
+        F: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    {
+        self.0.context(context).into()
+    }
+}
+
+impl<E, O> LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    /// Hands the report's [`ApiError`] to `f` and returns the report
+    /// unchanged, for `map_err` chains. The `ApiError` is built with
+    /// [`ReportExt::to_api_error`], so it is also logged.
+    #[must_use]
+    pub fn inspect<F: FnOnce(&ApiError)>(self, f: F) -> Self {
+        f(&self.to_api_error());
+        self
+    }
+
+    /// Hands the report itself to `f` and returns it unchanged.
+    #[must_use]
+    pub fn inspect_report<F: FnOnce(&Self)>(self, f: F) -> Self {
+        f(&self);
+        self
+    }
+}
+
+impl<E> Clone for ClonableLibReport<E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    /// Shares the underlying tree, diagnostic cache included.
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+/// Borrows the top-level context, e.g. for `matches!` on its variant
+/// without reaching through `.0`.
+impl<E, O> AsRef<E> for LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    fn as_ref(&self) -> &E {
+        self.0.current_context()
+    }
+}
+
+/// Mutably borrows the top-level context. Only uncloned reports can hand
+/// out `&mut E`.
+impl<E> AsMut<E> for LibReport<E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    fn as_mut(&mut self) -> &mut E {
+        self.0.current_context_mut()
+    }
+}
+
+impl<E> From<Report<E>> for LibReport<E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    #[track_caller]
+    fn from(report: Report<E>) -> Self {
+        Self::new(report)
+    }
+}
+
+impl<E> From<Report<E, Cloneable>> for ClonableLibReport<E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    fn from(report: Report<E, Cloneable>) -> Self {
+        Self(report)
+    }
+}
+
+impl<E, O> fmt::Debug for LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    /// The miette report, so `{:?}` in test failures and `unwrap` panics
+    /// reads like the rendered error: graphical when stderr is a terminal,
+    /// narrated text otherwise. `{:#?}` shows the underlying rootcause
+    /// report instead.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.debug_tuple("LibReport").field(&self.0).finish()
+        } else if io::stderr().is_terminal() {
+            GraphicalReportHandler::new_themed(GraphicalTheme::unicode()).render_report(f, self)
+        } else {
+            NarratableReportHandler::new().render_report(f, self)
+        }
+    }
+}
+
+/// Result alias. Consuming crates alias this with their own error type:
+///
+/// ```rust
+/// struct AppError;
+/// type AppResult<T> = errors_lib::LibResult<T, AppError>;
+/// ```
+pub type LibResult<T, E> = std::result::Result<T, LibReport<E>>;
+
+/// eyre-style context for [`LibResult`]s: on `Err`, attach a message to the
+/// report (it shows up in `ApiError::history`); `Ok` passes through.
+///
+/// ```rust,ignore
+/// let config = load_config().wrap_err("loading config")?;
+/// ```
+pub trait LibResultExt<T, E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    /// Attaches `msg` to the report on the `Err` path.
+    ///
+    /// # Errors
+    ///
+    /// Returns the original report, with `msg` attached, if `self` is `Err`.
+    fn wrap_err<M>(self, msg: M) -> LibResult<T, E>
+    where
+        M: fmt::Display + fmt::Debug + Send + Sync + 'static;
+
+    /// Like [`LibResultExt::wrap_err`], but only builds the message on the
+    /// `Err` path.
+    ///
+    /// # Errors
+    ///
+    /// Returns the original report, with the message attached, if `self`
+    /// is `Err`.
+    fn with_wrap_err<M, F>(self, f: F) -> LibResult<T, E>
+    where
+        M: fmt::Display + fmt::Debug + Send + Sync + 'static,
+        F: FnOnce() -> M;
+}
+
+impl<T, E> LibResultExt<T, E> for LibResult<T, E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    fn wrap_err<M>(self, msg: M) -> Self
+    where
+        M: fmt::Display + fmt::Debug + Send + Sync + 'static,
+    {
+        self.map_err(|report| report.attach(msg))
+    }
+
+    fn with_wrap_err<M, F>(self, f: F) -> Self
+    where
+        M: fmt::Display + fmt::Debug + Send + Sync + 'static,
+        F: FnOnce() -> M,
+    {
+        self.map_err(|report| report.attach(f()))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ApiError emission
+// ---------------------------------------------------------------------------
+
+/// Picks the tracing level an `ApiError` is emitted at; see
+/// [`set_level_mapper`].
+pub type LevelMapper = fn(&ApiError) -> Level;
+
+fn level_mapper() -> &'static RwLock<Option<LevelMapper>> {
+    static MAPPER: OnceLock<RwLock<Option<LevelMapper>>> = OnceLock::new();
+    MAPPER.get_or_init(RwLock::default)
+}
+
+/// Replaces the severity-based choice in [`ApiError::level`] for every
+/// later emission, e.g. to log a noisy code at `DEBUG`. A mapper can fall
+/// back to [`ApiError::severity_level`].
+pub fn set_level_mapper(mapper: LevelMapper) {
+    *level_mapper()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = Some(mapper);
+}
+
+/// Undoes [`set_level_mapper`], restoring the severity-based levels.
+pub fn clear_level_mapper() {
+    *level_mapper()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+impl ApiError {
+    /// The tracing level this error is emitted at: the
+    /// [`set_level_mapper`] policy's choice if one is set, else
+    /// [`ApiError::severity_level`].
+    #[must_use]
+    pub fn level(&self) -> Level {
+        let mapper = *level_mapper()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        mapper.map_or_else(|| self.severity_level(), |mapper| mapper(self))
+    }
+
+    /// The tracing level matching `severity`: `WARN` for warnings, `INFO`
+    /// for advice, `ERROR` otherwise.
+    #[must_use]
+    pub fn severity_level(&self) -> Level {
+        match self.severity.as_deref() {
+            Some("warning") => Level::WARN,
+            Some("advice") => Level::INFO,
+            _ => Level::ERROR,
+        }
+    }
+
+    /// Emits this error as a structured tracing event at `level`, with the
+    /// target and field names of the [`EmitConfig`](crate::EmitConfig) in
+    /// effect.
+    pub fn log_to_tracing(&self, level: Level) {
+        emit::dispatch(self, level);
+    }
+
+    /// The key-value pairs emitted by [`ApiError::log_to_tracing`], for
+    /// logging frameworks other than tracing, named as configured with
+    /// [`set_emit_config`](crate::set_emit_config). `code`, `location`,
+    /// `trace_id`, `span_id`, `fields` and `extra` are omitted when absent,
+    /// and `history` when the config leaves it out. Attached pairs come
+    /// under their own keys; see
+    /// [`FieldNames::fields`](crate::FieldNames::fields).
+    #[must_use]
+    pub fn to_log_fields(&self) -> Vec<(&'static str, String)> {
+        let config = emit::emit_config();
+        let names = config.field_names;
+        let mut fields = vec![
+            (names.hash, self.git_hash.to_string()),
+            (names.docs, self.docs_url.to_string()),
+            (names.id, self.correlation_id.clone()),
+            (names.title, self.log_title().to_owned()),
+        ];
+        if let Some(code) = &self.code {
+            fields.push((names.code, code.to_string()));
+        }
+        if let Some(location) = &self.location {
+            fields.push((names.location, location.clone()));
+        }
+        if let Some(trace_id) = &self.trace_id {
+            fields.push((names.trace_id, trace_id.clone()));
+        }
+        if let Some(span_id) = &self.span_id {
+            fields.push((names.span_id, span_id.clone()));
+        }
+        let (own, rest) = emit::split_fields(self, &names);
+        fields.extend(own.into_iter().map(|(key, value)| (key, value.clone())));
+        if !rest.is_empty() {
+            fields.push((names.fields, format!("{rest:?}")));
+        }
+        if !self.extra.is_empty() {
+            fields.push((names.extra, format!("{:?}", self.extra)));
+        }
+        if config.include_history {
+            fields.push((names.history, format!("{:?}", self.history_messages())));
+        }
+        fields
+    }
+
+    /// The summary when there is one: a bare title would hide the causes.
+    pub(crate) fn log_title(&self) -> &str {
+        self.summary.as_deref().unwrap_or(&self.title)
+    }
+
+    pub(crate) fn history_messages(&self) -> Vec<&str> {
+        self.history.iter().map(|h| h.message.as_str()).collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ApiErrorWriter — JSON Lines output for batches of errors
+// ---------------------------------------------------------------------------
+
+/// Streams `ApiError`s as JSON Lines: one compact object per line.
+///
+/// Lets batch jobs append failures as they happen instead of collecting a
+/// JSON array in memory.
+pub struct ApiErrorWriter<W: io::Write> {
+    inner: W,
+}
+
+impl<W: io::Write> ApiErrorWriter<W> {
+    /// Wraps a writer, e.g. a file opened in append mode.
+    pub const fn new(inner: W) -> Self {
+        Self {
+            inner,
+        }
+    }
+
+    /// Serializes `err` compactly and terminates it with `\n`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization or the underlying write fails.
+    pub fn write(&mut self, err: &ApiError) -> io::Result<()> {
+        serde_json::to_writer(&mut self.inner, err)?;
+        self.inner.write_all(b"\n")
+    }
+
+    /// Flushes the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying flush fails.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Unwraps the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Diagnostic impl — delegates to the inner error context
+// ---------------------------------------------------------------------------
+
+impl<E, O> Diagnostic for LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.0.current_context().code()
+    }
+
+    /// A [`LibReport::with_severity`] override, else the context's own.
+    fn severity(&self) -> Option<miette::Severity> {
+        attachments::severity_override(&self.0).or_else(|| self.0.current_context().severity())
+    }
+
+    /// The context's help, followed by the creation-time span trace when
+    /// one was captured (`span-trace` feature).
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        let help = self.0.current_context().help();
+        #[cfg(feature = "span-trace")]
+        let help = span_trace::help_with_trace(help, &self.0);
+        help
+    }
+
+    /// Maps the error code to a clickable docs link in the terminal.
+    fn url<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.code().and_then(|c| docs_link(&c))
+    }
+
+    /// The context's source code, else the first a descendant carries,
+    /// searching depth-first, so a snippet below the top still renders.
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        self.0
+            .current_context()
+            .source_code()
+            .or_else(|| self.contexts().skip(1).find_map(Diagnostic::source_code))
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        self.0.current_context().labels()
+    }
+
+    /// The context's own related diagnostics when it declares any (as
+    /// [`MultiError`](crate::MultiError) does), else every direct child
+    /// report after the first, which is already the
+    /// [`diagnostic_source`](Diagnostic::diagnostic_source).
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        if let Some(related) = self.0.current_context().related() {
+            return Some(related);
+        }
+        let Some(cache) = self.cache() else {
+            let mut rest = self.contexts().skip(2).peekable();
+            rest.peek()?;
+            return Some(Box::new(rest));
+        };
+        related_iter(cache.children(self.0.children(), view_as::<E, Cloneable>))
+    }
+
+    /// The first child report, or the context's own `source()` chain when
+    /// the report has no children. A context that declares its own related
+    /// diagnostics already shows the children there, so it only gets its
+    /// `source()` chain.
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        let context = self.0.current_context();
+        let context_source = || std::error::Error::source(context);
+        let Some(cache) = self.cache() else {
+            if context.related().is_some() {
+                return None;
+            }
+            return self.contexts().nth(1);
+        };
+        if context.related().is_some() {
+            return cache.context_source(context_source);
+        }
+        cache.diagnostic_source(self.0.children(), view_as::<E, Cloneable>, context_source)
+    }
+}
+
+/// Builds the docs link for a diagnostic code; see [`docs_url_for`].
+/// A code that renders blank has no link.
+pub fn docs_link(code: &dyn fmt::Display) -> Option<Box<dyn fmt::Display>> {
+    let code = code.to_string();
+    if code.trim().is_empty() {
+        return None;
+    }
+    Some(Box::new(docs_url_for(&code)))
+}
+
+// ---------------------------------------------------------------------------
+// Diagnostic adapters — owned views miette can borrow from a report
+// ---------------------------------------------------------------------------
+
+/// Recovers a typed context from a type-erased node as `&dyn Diagnostic`.
+///
+/// rootcause only downcasts to concrete types, so the owning report
+/// supplies a function that knows which type to try.
+pub type DiagnosticView = for<'a> fn(&'a Report<Dynamic, Cloneable>) -> Option<&'a dyn Diagnostic>;
+
+/// Downcasts a node's context to `E`, the view used for typed reports.
+pub fn view_as<E, O>(report: &Report<Dynamic, O>) -> Option<&dyn Diagnostic>
+where
+    E: Diagnostic + 'static,
+{
+    report
+        .downcast_current_context::<E>()
+        .map(|ctx| ctx as &dyn Diagnostic)
+}
+
+/// The view for nodes whose context type is unknown: `Display` only.
+pub const fn view_none<O>(_report: &Report<Dynamic, O>) -> Option<&dyn Diagnostic> {
+    None
+}
+
+/// Lazily built adapters for the parts of the tree miette borrows as
+/// `&dyn Diagnostic`. rootcause nodes are not themselves `Diagnostic`, so
+/// the views are created on first use and kept alongside the report.
+///
+/// The tree can change after the views were built (`children_mut()`,
+/// `as_mut()`, `map_context`), so each view is keyed by what it was built
+/// from, and a changed tree gets views of its own rather than the old ones.
+pub struct DiagnosticCache {
+    /// Keyed by the child count and the view they were built through.
+    children: Generations<(usize, usize), Vec<ChildDiagnostic>>,
+    /// Keyed by the captured chain itself.
+    source: Generations<Option<ErrorSource>, ()>,
+}
+
+impl DiagnosticCache {
+    pub(crate) const fn new() -> Self {
+        Self {
+            children: Generations::new(),
+            source: Generations::new(),
+        }
+    }
+
+    pub(crate) fn children(
+        &self,
+        collection: &ReportCollection<Dynamic, Cloneable>,
+        view: DiagnosticView,
+    ) -> &[ChildDiagnostic] {
+        let key = (collection.iter().count(), view as usize);
+        self.children
+            .get_or_insert_with(key, || ChildDiagnostic::collect(collection, view))
+            .1
+    }
+
+    pub(crate) fn diagnostic_source<'a>(
+        &'a self,
+        collection: &ReportCollection<Dynamic, Cloneable>,
+        view: DiagnosticView,
+        context_source: impl FnOnce() -> Option<&'a (dyn std::error::Error + 'static)>,
+    ) -> Option<&'a dyn Diagnostic> {
+        if let Some(first) = self.children(collection, view).first() {
+            return Some(first);
+        }
+        self.context_source(context_source)
+    }
+
+    /// The context's own `source()` chain, ignoring the children.
+    pub(crate) fn context_source<'a>(
+        &'a self,
+        context_source: impl FnOnce() -> Option<&'a (dyn std::error::Error + 'static)>,
+    ) -> Option<&'a dyn Diagnostic> {
+        let captured = context_source().map(ErrorSource::capture);
+        self.source
+            .get_or_insert_with(captured, || ())
+            .0
+            .as_ref()
+            .map(|source| source as &dyn Diagnostic)
+    }
+
+    /// The first source code among the descendants, depth-first. The
+    /// children are borrowed from the cache, so the reference lives as
+    /// long as the report.
+    pub(crate) fn descendant_source_code(
+        &self,
+        collection: &ReportCollection<Dynamic, Cloneable>,
+        view: DiagnosticView,
+    ) -> Option<&dyn SourceCode> {
+        self.children(collection, view).iter().find_map(|child| {
+            child.source_code().or_else(|| {
+                child
+                    .cache
+                    .descendant_source_code(child.report.children(), child.view)
+            })
+        })
+    }
+
+    /// The `std::error::Error` counterpart of `diagnostic_source`: the first
+    /// child, else the context's own source, borrowed directly.
+    pub(crate) fn error_source<'a>(
+        &'a self,
+        collection: &ReportCollection<Dynamic, Cloneable>,
+        view: DiagnosticView,
+        context_source: Option<&'a (dyn std::error::Error + 'static)>,
+    ) -> Option<&'a (dyn std::error::Error + 'static)> {
+        self.children(collection, view)
+            .first()
+            .map_or(context_source, |first| Some(first))
+    }
+}
+
+/// An append-only list of values, each built for a key, so references
+/// handed out for an earlier key stay valid once the key changes.
+struct Generations<K, V> {
+    head: OnceLock<Box<Generation<K, V>>>,
+}
+
+struct Generation<K, V> {
+    key: K,
+    value: V,
+    next: OnceLock<Box<Generation<K, V>>>,
+}
+
+impl<K: Clone + PartialEq, V> Generations<K, V> {
+    const fn new() -> Self {
+        Self {
+            head: OnceLock::new(),
+        }
+    }
+
+    /// The key and value stored for `key`, building the value with `make`
+    /// the first time the key is seen.
+    fn get_or_insert_with(&self, key: K, make: impl FnOnce() -> V) -> (&K, &V) {
+        let mut make = Some(make);
+        let mut built = None;
+        let mut slot = &self.head;
+        loop {
+            if let Some(generation) = slot.get() {
+                if generation.key == key {
+                    return (&generation.key, &generation.value);
+                }
+                slot = &generation.next;
+                continue;
+            }
+            let generation = built.take().unwrap_or_else(|| {
+                let make = make.take().expect("a value is built at most once");
+                Box::new(Generation {
+                    key: key.clone(),
+                    value: make(),
+                    next: OnceLock::new(),
+                })
+            });
+            // Another thread may have filled the slot meanwhile; keep the
+            // value for the next empty one.
+            if let Err(generation) = slot.set(generation) {
+                built = Some(generation);
+            }
+        }
+    }
+}
+
+/// A child report rendered as a standalone diagnostic.
+///
+/// Children are type-erased in rootcause, so this delegates to the child's
+/// context when the view recognises it and falls back to its `Display`
+/// output otherwise (e.g. an `io::Error` child).
+pub struct ChildDiagnostic {
+    report: Report<Dynamic, Cloneable>,
+    view: DiagnosticView,
+    cache: DiagnosticCache,
+}
+
+impl ChildDiagnostic {
+    fn collect(children: &ReportCollection<Dynamic, Cloneable>, view: DiagnosticView) -> Vec<Self> {
+        children
+            .iter()
+            .map(|child| Self {
+                report: child.clone_arc(),
+                view,
+                cache: DiagnosticCache::new(),
+            })
+            .collect()
+    }
+
+    fn context(&self) -> Option<&dyn Diagnostic> {
+        (self.view)(&self.report)
+    }
+}
+
+/// The children after the first, as related diagnostics. The first is the
+/// `diagnostic_source`, and miette renders both, so listing it here too
+/// would show it twice.
+pub fn related_iter(
+    children: &[ChildDiagnostic],
+) -> Option<Box<dyn Iterator<Item = &dyn Diagnostic> + '_>> {
+    let rest = children.get(1..).filter(|rest| !rest.is_empty())?;
+    Some(Box::new(rest.iter().map(|child| child as &dyn Diagnostic)))
+}
+
+impl Diagnostic for ChildDiagnostic {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.context()?.code()
+    }
+
+    fn severity(&self) -> Option<miette::Severity> {
+        attachments::severity_override(&self.report).or_else(|| self.context()?.severity())
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.context()?.help()
+    }
+
+    fn url<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.code().and_then(|c| docs_link(&c))
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        self.context()?.source_code()
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        self.context()?.labels()
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        related_iter(self.cache.children(self.report.children(), self.view))
+    }
+
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        self.cache
+            .diagnostic_source(self.report.children(), self.view, || {
+                self.report.current_context_error_source()
+            })
+    }
+}
+
+impl fmt::Display for ChildDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.report.format_current_context())
+    }
+}
+
+impl fmt::Debug for ChildDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChildDiagnostic")
+            .field("context", &self.report.format_current_context())
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::error::Error for ChildDiagnostic {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.cache.error_source(
+            self.report.children(),
+            self.view,
+            self.report.current_context_error_source(),
+        )
+    }
+}
+
+/// Owned snapshot of a `std::error::Error::source()` chain.
+///
+/// Sources are plain `Error`s borrowed from the context, so the chain is
+/// captured by message to give miette something to render as "Caused by".
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ErrorSource {
+    message: String,
+    source: Option<Box<Self>>,
+}
+
+impl ErrorSource {
+    fn capture(err: &(dyn std::error::Error + 'static)) -> Self {
+        Self {
+            message: err.to_string(),
+            source: err.source().map(|s| Box::new(Self::capture(s))),
+        }
+    }
+}
+
+impl fmt::Display for ErrorSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ErrorSource {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|s| s as &(dyn std::error::Error + 'static))
+    }
+}
+
+impl Diagnostic for ErrorSource {
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        self.source.as_deref().map(|s| s as &dyn Diagnostic)
+    }
+}
+
+/// rootcause's own format; with `{:#}`, the top-level message followed by
+/// the history (attachments and causes, top-level first), one indented
+/// entry per line.
+impl<E, O> fmt::Display for LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            fmt_chain(&self.0, f)
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+/// The `{:#}` layout shared by `LibReport` and `DynLibReport`.
+pub fn fmt_chain<C: ?Sized, O>(report: &Report<C, O>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", report.format_current_context())?;
+    for frame in history_of(report) {
+        write!(f, "\n  {}", frame.message)?;
+    }
+    Ok(())
+}
+
+/// Exposes the rootcause tree to anything walking the standard source chain:
+/// the first child report, else the current context's own `source()`. As
+/// with `diagnostic_source`, a context that declares related diagnostics
+/// only gets its own `source()`.
+impl<E, O> std::error::Error for LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        let context = self.0.current_context();
+        let context_source = std::error::Error::source(context);
+        if context.related().is_some() {
+            return context_source;
+        }
+        match self.cache() {
+            Some(cache) => {
+                cache.error_source(self.0.children(), view_as::<E, Cloneable>, context_source)
+            },
+            None => self
+                .0
+                .iter_reports()
+                .skip(1)
+                .find_map(|node| node.downcast_current_context::<E>())
+                .map_or(context_source, |child| {
+                    Some(child as &(dyn std::error::Error + 'static))
+                }),
+        }
+    }
+}
+
+/// A panic can't leave a report half-updated: the tree only grows by value
+/// (`attach`, `context` and friends consume the report), and the
+/// diagnostic cache only ever appends through `OnceLock`s. So a report can cross
+/// `catch_unwind` whenever its context can, though the rootcause tree's
+/// type-erased nodes aren't marked unwind safe themselves. Contexts holding
+/// an `io::Error` can't, so closures over those reports need
+/// `AssertUnwindSafe`.
+impl<E, O> UnwindSafe for LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + UnwindSafe + 'static,
+    O: 'static,
+{
+}
+
+/// As for `UnwindSafe`: `&LibReport` only reads the tree, or appends to
+/// the cache.
+impl<E, O> RefUnwindSafe for LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + RefUnwindSafe + 'static,
+    O: 'static,
+{
+}
+
+// ---------------------------------------------------------------------------
+// ReportExt — converts a LibReport into an ApiError for logging/API sinks
+// ---------------------------------------------------------------------------
+
+/// Order of `ApiError::history`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HistoryOrder {
+    /// Outermost wrapper first, as the tree is traversed.
+    #[default]
+    ContextFirst,
+    /// Root cause first: the traversal order reversed.
+    RootFirst,
+}
+
+/// Which repeated messages to drop from `ApiError::history`, e.g. when a
+/// report was re-wrapped with the same context at several layers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HistoryDedup {
+    /// Keep every frame.
+    #[default]
+    Keep,
+    /// Collapse runs of identical messages into one.
+    Adjacent,
+    /// Keep only the first occurrence of each message, wherever the others
+    /// are.
+    All,
+}
+
+/// Options for [`ReportExt::to_api_error_with_options`].
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct ApiErrorOptions {
+    pub history_order: HistoryOrder,
+    pub history_dedup: HistoryDedup,
+    /// Also fill `ApiError::tree` with the report's
+    /// [`ErrorTree`](crate::ErrorTree).
+    pub include_tree: bool,
+    /// Applied with [`ApiError::with_retry_policy`].
+    pub retry_policy: Option<RetryPolicy>,
+    /// Used instead of a fresh random ID.
+    pub correlation_id: Option<CorrelationId>,
+}
+
+impl ApiErrorOptions {
+    /// Sets the order of `ApiError::history`.
+    #[must_use]
+    pub const fn history_order(mut self, order: HistoryOrder) -> Self {
+        self.history_order = order;
+        self
+    }
+
+    /// Sets which repeated messages `ApiError::history` drops.
+    #[must_use]
+    pub const fn history_dedup(mut self, dedup: HistoryDedup) -> Self {
+        self.history_dedup = dedup;
+        self
+    }
+
+    /// Embeds the report's [`ErrorTree`](crate::ErrorTree) alongside the flat
+    /// history.
+    #[must_use]
+    pub const fn include_tree(mut self, include: bool) -> Self {
+        self.include_tree = include;
+        self
+    }
+
+    /// Fills `ApiError::retry_after_ms` from `policy`.
+    #[must_use]
+    pub const fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Stamps `id` instead of a fresh random correlation ID.
+    #[must_use]
+    pub const fn correlation_id(mut self, id: CorrelationId) -> Self {
+        self.correlation_id = Some(id);
+        self
+    }
+}
+
+pub trait ReportExt {
+    /// Builds an `ApiError` with default options and emits it to the
+    /// registered [`ErrorSink`](crate::ErrorSink)s (tracing, at
+    /// [`ApiError::level`], unless replaced).
+    fn to_api_error(&self) -> ApiError {
+        self.to_api_error_with_options(ApiErrorOptions::default())
+    }
+
+    /// Builds an `ApiError` and emits it to every sink, for when only the
+    /// side effect is wanted; [`ReportExt::to_api_error`] without the
+    /// result.
+    fn emit(&self) {
+        let _ = self.to_api_error();
+    }
+
+    /// [`ReportExt::to_api_error`] with the root cause first in history.
+    fn to_api_error_root_first(&self) -> ApiError {
+        self.to_api_error_with_options(
+            ApiErrorOptions::default().history_order(HistoryOrder::RootFirst),
+        )
+    }
+
+    /// Builds an `ApiError` as configured, counts its code in
+    /// [`ErrorStats::global`] and emits it to every sink in the
+    /// [`ErrorSinkRegistry`].
+    fn to_api_error_with_options(&self, options: ApiErrorOptions) -> ApiError {
+        let api_err = self.build_api_error_with_options(options);
+        if let Some(code) = &api_err.code {
+            ErrorStats::global().record(code);
+        }
+
+        ErrorSinkRegistry::emit(&api_err);
+        api_err
+    }
+
+    /// Builds an `ApiError` as configured, without counting or emitting
+    /// it: the same error [`ReportExt::to_api_error_with_options`] would
+    /// return, with its own correlation ID. The format conversions below
+    /// use this, so converting a report that was already emitted doesn't
+    /// log or count it again.
+    fn build_api_error_with_options(&self, options: ApiErrorOptions) -> ApiError;
+
+    /// Whether retrying might succeed: true when any node in the chain has
+    /// a code in [`transient_codes`](crate::transient_codes), or is (or was
+    /// caused by) an io error of kind `WouldBlock` or `TimedOut`, or when
+    /// the report carries a [`RetryHint`](crate::RetryHint).
+    fn is_retryable(&self) -> bool;
+
+    /// Builds an `ApiError` without emitting it and returns its GraphQL
+    /// message and extensions; see [`ApiError::to_graphql_extensions`].
+    #[cfg(feature = "graphql")]
+    fn to_graphql_extensions(&self) -> (String, serde_json::Map<String, serde_json::Value>) {
+        self.build_api_error_with_options(ApiErrorOptions::default())
+            .to_graphql_extensions()
+    }
+
+    /// Builds an `ApiError` without emitting it and returns it as a
+    /// JSON-RPC error object; see [`ApiError::to_jsonrpc_error`].
+    #[cfg(feature = "jsonrpc")]
+    fn to_jsonrpc_error(&self) -> crate::JsonRpcError {
+        self.build_api_error_with_options(ApiErrorOptions::default())
+            .to_jsonrpc_error()
+    }
+
+    /// Builds an `ApiError` without emitting it and returns it as a log
+    /// record created by `logger`, ready for `logger.emit`; see
+    /// [`ApiError::fill_log_record`].
+    #[cfg(feature = "opentelemetry")]
+    fn to_opentelemetry_event<L>(&self, logger: &L) -> L::LogRecord
+    where
+        L: opentelemetry::logs::Logger,
+        Self: Sized,
+    {
+        let mut record = logger.create_log_record();
+        self.build_api_error_with_options(ApiErrorOptions::default())
+            .fill_log_record(&mut record);
+        record
+    }
+
+    /// Builds an `ApiError` without emitting it and returns it as a Slack
+    /// webhook payload; see [`ApiError::to_slack_message`].
+    #[cfg(feature = "slack")]
+    fn to_slack_message(&self) -> serde_json::Value {
+        self.build_api_error_with_options(ApiErrorOptions::default())
+            .to_slack_message()
+    }
+
+    /// Builds an `ApiError` without emitting it and returns it as a Sentry
+    /// event whose exception chain has one entry per node, typed by its
+    /// context, with the attachments as breadcrumbs; see
+    /// [`capture_lib_report`](crate::capture_lib_report).
+    #[cfg(feature = "sentry")]
+    fn to_sentry_event(&self) -> sentry::protocol::Event<'static>;
+
+    /// The exit status the process-wide
+    /// [`exit_code_map`](crate::exit_code_map) assigns the top-level code:
+    /// sysexits values (`config::*` 78, `io::*` 74, otherwise 1) unless
+    /// replaced with [`set_exit_code_map`](crate::set_exit_code_map).
+    fn process_exit_code(&self) -> i32;
+}
+
+impl<E, O> ReportExt for LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    fn build_api_error_with_options(&self, options: ApiErrorOptions) -> ApiError {
+        let ctx = self.0.current_context();
+        let top = TopLevel {
+            title: ctx.to_string(),
+            code: ctx.code().map(|c| c.to_string()),
+            help: ctx.help().map(|h| h.to_string()),
+            severity: self.reported_severity(),
+            retryable: self.is_retryable(),
+        };
+        api_error_of(&self.0, top, view_as::<E, Cloneable>, options)
+    }
+
+    fn is_retryable(&self) -> bool {
+        let ctx = self.0.current_context();
+        retry::node_is_retryable(Some(ctx), None, std::error::Error::source(ctx))
+            || self
+                .0
+                .children()
+                .iter()
+                .any(|child| retry::child_is_retryable(&child.clone_arc(), view_as::<E, Cloneable>))
+            || retry::retry_hint_of(&self.0).is_some()
+    }
+
+    #[cfg(feature = "sentry")]
+    fn to_sentry_event(&self) -> sentry::protocol::Event<'static> {
+        compat_sentry::report_event(
+            &self.build_api_error_with_options(ApiErrorOptions::default()),
+            &self.0,
+            compat_sentry::context_type_name::<E>,
+        )
+    }
+
+    fn process_exit_code(&self) -> i32 {
+        let code = self.code().map(|code| code.to_string());
+        exit_code::process_status(code.as_deref())
+    }
+}
+
+/// Collects the tree in traversal order: every attachment, each preceded
+/// by its node's context message — except the root's, which is the title.
+/// Attachments that render as nothing (typed payloads) are skipped.
+pub fn history_of<C: ?Sized, O>(report: &Report<C, O>) -> Vec<ErrorFrame> {
+    walk_report(report, |depth, node| {
+        let context = (depth > 0).then(|| node.format_current_context().to_string());
+        context.into_iter().chain(messages(node)).collect()
+    })
+    .map(|message| ErrorFrame {
+        message,
+    })
+    .collect()
+}
+
+/// The outermost [`ReportLocation`] in the tree, formatted.
+pub fn location_of<C: ?Sized, O>(report: &Report<C, O>) -> Option<String> {
+    report
+        .iter_reports()
+        .flat_map(|node| {
+            node.attachments()
+                .iter()
+                .filter_map(|attachment| attachment.downcast_inner::<ReportLocation>())
+        })
+        .next()
+        .map(ToString::to_string)
+}
+
+/// [`history_of`] in the requested order, with duplicates collapsed as
+/// requested.
+pub fn ordered_history<C: ?Sized, O>(
+    report: &Report<C, O>,
+    options: ApiErrorOptions,
+) -> Vec<ErrorFrame> {
+    let mut history = history_of(report);
+    match options.history_dedup {
+        HistoryDedup::Keep => {},
+        HistoryDedup::Adjacent => history.dedup_by(|a, b| a.message == b.message),
+        HistoryDedup::All => {
+            let mut seen = HashSet::new();
+            history.retain(|frame| seen.insert(frame.message.clone()));
+        },
+    }
+    if options.history_order == HistoryOrder::RootFirst {
+        history.reverse();
+    }
+    history
+}
+
+/// What an `ApiError` takes from a report's top-level context, read through
+/// its type or, for a [`DynLibReport`], through whatever view applies.
+pub struct TopLevel {
+    pub title: String,
+    pub code: Option<String>,
+    pub help: Option<String>,
+    pub severity: Option<miette::Severity>,
+    pub retryable: bool,
+}
+
+/// [`ReportExt::build_api_error_with_options`] for any report: `top` and
+/// the report's history, fields, summary and tree as `options` ask, with
+/// children's codes read through `children`.
+pub fn api_error_of<C: ?Sized, O>(
+    report: &Report<C, O>,
+    top: TopLevel,
+    children: DiagnosticView,
+    options: ApiErrorOptions,
+) -> ApiError {
+    let TopLevel {
+        title,
+        code,
+        help,
+        severity,
+        retryable,
+    } = top;
+    let mut api_err = build_api_error(
+        ordered_history(report, options),
+        title,
+        code.clone(),
+        help,
+        severity,
+        retryable,
+        location_of(report),
+    );
+    api_err.fields = attachments::fields_of(report);
+    #[cfg(feature = "span-trace")]
+    if let Some(trace) = span_trace::trace_of(report) {
+        api_err.span_trace = Some(trace.spans());
+    }
+    if !report.children().is_empty() {
+        api_err.summary = Some(summary_of(report, code.as_deref()));
+    }
+    if options.include_tree {
+        api_err.tree = Some(ErrorTree::build(report, code, children));
+    }
+    if let Some(hint) = retry::retry_hint_of(report) {
+        api_err = api_err.with_retry_hint(hint);
+    }
+    if let Some(policy) = options.retry_policy {
+        api_err = api_err.with_retry_policy(policy);
+    }
+    if let Some(id) = options.correlation_id {
+        api_err.correlation_id = id.to_string();
+    }
+    api_err
+}
+
+/// Stamps build metadata and a fresh correlation ID onto the report's
+/// details.
+pub fn build_api_error(
+    history: Vec<ErrorFrame>,
+    title: String,
+    code: Option<String>,
+    help: Option<String>,
+    severity: Option<miette::Severity>,
+    retryable: bool,
+    location: Option<String>,
+) -> ApiError {
+    // A blank code is no code, so no docs link or domain either. Other codes
+    // are kept; only a `namespace::variant` one has a domain.
+    let code = code.and_then(ErrorCode::lenient);
+    #[cfg(feature = "otel")]
+    let (trace_id, span_id) = otel_context::capture().unzip();
+    #[cfg(not(feature = "otel"))]
+    let (trace_id, span_id) = (None, None);
+    let thread = std::thread::current();
+    ApiError {
+        git_hash: Cow::Borrowed(env!("GIT_HASH")),
+        git_branch: Cow::Borrowed(env!("GIT_BRANCH")),
+        git_dirty: env!("GIT_DIRTY") == "true",
+        build_timestamp: Cow::Borrowed(env!("BUILD_TIMESTAMP")),
+        rustc_version: Cow::Borrowed(env!("RUSTC_VERSION")),
+        target: Cow::Borrowed(env!("TARGET_TRIPLE")),
+        docs_url: Cow::Borrowed(docs::docs_base_url()),
+        code_url: code.as_deref().map(docs_url_for),
+        correlation_id: nanoid!(8),
+        title,
+        summary: None,
+        domain: code
+            .as_ref()
+            .and_then(ErrorCode::namespace)
+            .map(ToString::to_string),
+        code,
+        help,
+        severity: severity.map(|severity| {
+            match severity {
+                miette::Severity::Advice => "advice",
+                miette::Severity::Warning => "warning",
+                miette::Severity::Error => "error",
+            }
+            .to_string()
+        }),
+        location,
+        thread_name: thread.name().map(ToString::to_string),
+        thread_id: format!("{:?}", thread.id()),
+        fields: BTreeMap::new(),
+        extra: global_fields(),
+        retryable,
+        retry_after_ms: None,
+        max_retries: None,
+        history,
+        #[cfg(feature = "span-trace")]
+        span_trace: span_trace::capture(),
+        #[cfg(not(feature = "span-trace"))]
+        span_trace: None,
+        trace_id,
+        span_id,
+        tree: None,
+    }
+}
@@ -0,0 +1,251 @@
+/*
+ * Retry classification: is an error worth retrying?
+ *
+ * A chain is retryable when any node in it is transient — its diagnostic
+ * code is in the configured set, or it is (or was caused by) an io::Error
+ * of a transient kind. The code set is process-wide so `to_api_error()`
+ * can fill `ApiError::retryable` without extra arguments.
+ *
+ * Error types that know better implement Retryable per variant; the
+ * resulting RetryPolicy also says how long to wait, for Retry-After. Code
+ * that only learns this at the failure site (a 429 with Retry-After, say)
+ * attaches a RetryHint to the report instead.
+ */
+
+use std::{
+    error::Error,
+    fmt, io,
+    sync::{OnceLock, PoisonError, RwLock},
+    time::Duration,
+};
+
+use miette::Diagnostic;
+use rootcause::{
+    Report,
+    markers::{Cloneable, Dynamic},
+};
+
+use crate::{ApiError, ApiErrorOptions, DiagnosticView, LibReport, ReportExt};
+
+/// How soon, if ever, a failed operation may be retried.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RetryPolicy {
+    /// Retrying cannot help.
+    #[default]
+    Never,
+    /// Retry right away.
+    Immediate,
+    /// Retry once the duration has passed.
+    After(Duration),
+}
+
+impl RetryPolicy {
+    /// Whether the policy allows a retry at all.
+    #[must_use]
+    pub const fn is_retryable(self) -> bool {
+        !matches!(self, Self::Never)
+    }
+
+    /// The wait in milliseconds: `Some(0)` for `Immediate`, `None` for
+    /// `Never`.
+    #[must_use]
+    pub fn retry_after_ms(self) -> Option<u64> {
+        match self {
+            Self::Never => None,
+            Self::Immediate => Some(0),
+            Self::After(wait) => Some(u64::try_from(wait.as_millis()).unwrap_or(u64::MAX)),
+        }
+    }
+
+    /// Combines the policies of two nodes in one chain; see
+    /// [`LibReport::retry_policy`] for the precedence.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::After(a), Self::After(b)) => Self::After(a.max(b)),
+            (Self::After(_), _) | (Self::Immediate, Self::Never) => self,
+            _ => other,
+        }
+    }
+}
+
+/// How a caller should retry a transient failure, attached to a report with
+/// [`LibReport::with_retry_hint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryHint {
+    /// How long to wait before the next attempt.
+    pub delay: Duration,
+    /// How many attempts are worth making in total.
+    pub max_attempts: u32,
+}
+
+impl<E> LibReport<E>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    /// Attaches `hint`, marking the report retryable. `to_api_error()`
+    /// fills `retry_after_ms` and `max_retries` from the outermost node's
+    /// hint; on one node the latest hint wins.
+    #[must_use]
+    pub fn with_retry_hint(self, hint: RetryHint) -> Self {
+        self.attach_typed(hint)
+    }
+}
+
+/// The latest [`RetryHint`] on the outermost node that has one.
+pub fn retry_hint_of<C: ?Sized, O>(report: &Report<C, O>) -> Option<RetryHint> {
+    report
+        .iter_reports()
+        .flat_map(|node| {
+            node.attachments()
+                .iter()
+                .rev()
+                .filter_map(|attachment| attachment.downcast_inner::<RetryHint>())
+        })
+        .next()
+        .copied()
+}
+
+/// Per-variant retry classification, implemented by consumer error types.
+pub trait Retryable {
+    /// How this error may be retried.
+    fn retry_policy(&self) -> RetryPolicy;
+}
+
+impl<E, O> LibReport<E, O>
+where
+    E: Retryable + Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    /// The policy for the whole chain, from every node whose context is an
+    /// `E`.
+    ///
+    /// Any retryable node makes the chain retryable, as with
+    /// [`ReportExt::is_retryable`]: one transient cause is worth another
+    /// attempt. Among retryable nodes, a delay beats `Immediate` and the
+    /// longest delay wins, so no node's backoff is cut short.
+    #[must_use]
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.0
+            .iter_reports()
+            .filter_map(|node| node.downcast_current_context::<E>())
+            .map(Retryable::retry_policy)
+            .fold(RetryPolicy::Never, RetryPolicy::merge)
+    }
+
+    /// [`ReportExt::to_api_error`] with `retry_after_ms` filled from
+    /// [`LibReport::retry_policy`].
+    pub fn to_api_error_with_retry_policy(&self) -> ApiError {
+        self.to_api_error_with_options(ApiErrorOptions::default().retry_policy(self.retry_policy()))
+    }
+}
+
+impl ApiError {
+    /// Records `policy`: sets `retry_after_ms`, and marks the error
+    /// retryable when the policy allows it.
+    #[must_use]
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_after_ms = policy.retry_after_ms();
+        self.retryable |= policy.is_retryable();
+        self
+    }
+
+    /// Records `hint`: sets `retry_after_ms` and `max_retries`, and marks
+    /// the error retryable.
+    #[must_use]
+    pub fn with_retry_hint(mut self, hint: RetryHint) -> Self {
+        self.retry_after_ms = Some(u64::try_from(hint.delay.as_millis()).unwrap_or(u64::MAX));
+        self.max_retries = Some(hint.max_attempts);
+        self.retryable = true;
+        self
+    }
+}
+
+/// Codes treated as transient until [`set_transient_codes`] is called.
+pub const DEFAULT_TRANSIENT_CODES: &[&str] = &["network::timeout"];
+
+fn codes() -> &'static RwLock<Vec<String>> {
+    static CODES: OnceLock<RwLock<Vec<String>>> = OnceLock::new();
+    CODES.get_or_init(|| {
+        RwLock::new(
+            DEFAULT_TRANSIENT_CODES
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        )
+    })
+}
+
+/// Replaces the set of diagnostic codes considered transient.
+///
+/// io errors of kind `WouldBlock` or `TimedOut` are always transient,
+/// whatever this set contains.
+pub fn set_transient_codes<I>(new_codes: I)
+where
+    I: IntoIterator,
+    I::Item: Into<String>,
+{
+    let new_codes = new_codes.into_iter().map(Into::into).collect();
+    *codes().write().unwrap_or_else(PoisonError::into_inner) = new_codes;
+}
+
+/// The diagnostic codes currently considered transient.
+#[must_use]
+pub fn transient_codes() -> Vec<String> {
+    codes()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Whether one node of a chain is transient, given its diagnostic view (if
+/// its type is known) and its context's own `source()`.
+pub fn node_is_retryable(
+    diagnostic: Option<&dyn Diagnostic>,
+    io_context: Option<&io::Error>,
+    source: Option<&(dyn Error + 'static)>,
+) -> bool {
+    let by_code = diagnostic.is_some_and(|diagnostic| {
+        let codes = codes().read().unwrap_or_else(PoisonError::into_inner);
+        has_transient_code(diagnostic, &codes)
+    });
+
+    by_code || io_context.is_some_and(is_transient_io) || chain_is_transient_io(source)
+}
+
+/// Whether `diagnostic`, or any diagnostic it lists as related (e.g. the
+/// members of a [`MultiError`](crate::MultiError)), has a code in `codes`.
+fn has_transient_code(diagnostic: &dyn Diagnostic, codes: &[String]) -> bool {
+    diagnostic
+        .code()
+        .is_some_and(|code| codes.contains(&code.to_string()))
+        || diagnostic
+            .related()
+            .is_some_and(|mut related| related.any(|d| has_transient_code(d, codes)))
+}
+
+/// Whether a child report, or anything beneath it, is transient.
+pub fn child_is_retryable(report: &Report<Dynamic, Cloneable>, view: DiagnosticView) -> bool {
+    node_is_retryable(
+        view(report),
+        report.downcast_current_context::<io::Error>(),
+        report.current_context_error_source(),
+    ) || report
+        .children()
+        .iter()
+        .any(|child| child_is_retryable(&child.clone_arc(), view))
+}
+
+/// Whether any error in a `source()` chain is a transient io error.
+pub fn chain_is_transient_io(source: Option<&(dyn Error + 'static)>) -> bool {
+    std::iter::successors(source, |&err| err.source())
+        .filter_map(|err| err.downcast_ref::<io::Error>())
+        .any(is_transient_io)
+}
+
+fn is_transient_io(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
@@ -0,0 +1,123 @@
+/*
+ * Severity aggregated over a whole report tree.
+ *
+ * A report's own Diagnostic::severity is its top-level node's, which is
+ * what miette renders. Logging and HTTP status want the worst severity
+ * anywhere in the tree instead: a warning wrapped around a hard failure is
+ * still a failure. Each node counts as
+ *   - its with_severity override, if it has one;
+ *   - else its Diagnostic severity, when the node's type is known and it
+ *     declares one (Error when it declares none, as miette assumes);
+ *   - else Advice for a bare message context (a String or &str, which
+ *     carries no failure of its own);
+ *   - else Error, for any other error type.
+ */
+
+use std::fmt;
+
+use miette::{Diagnostic, Severity};
+use rootcause::{
+    Report, ReportCollection,
+    markers::{Cloneable, Dynamic},
+};
+
+use crate::{DiagnosticView, DynLibReport, LibReport, attachments, view_as};
+
+/// The running maximum, and whether any node stated its severity rather
+/// than defaulting to Error.
+#[derive(Clone, Copy)]
+struct Aggregate {
+    max: Severity,
+    declared: bool,
+}
+
+impl Aggregate {
+    const fn new() -> Self {
+        Self {
+            max: Severity::Advice,
+            declared: false,
+        }
+    }
+
+    fn add(&mut self, stated: Option<Severity>) {
+        self.declared |= stated.is_some();
+        self.max = self.max.max(stated.unwrap_or(Severity::Error));
+    }
+
+    fn add_children(
+        &mut self,
+        children: &ReportCollection<Dynamic, Cloneable>,
+        view: DiagnosticView,
+    ) {
+        for child in children.iter() {
+            let child = child.clone_arc();
+            self.add(stated(&child, view(&child)));
+            self.add_children(child.children(), view);
+        }
+    }
+
+    /// `Some` when the tree says something `ApiError::severity` should
+    /// record; a tree of undeclared errors stays `None`, i.e. an error.
+    const fn reported(self) -> Option<Severity> {
+        if self.declared { Some(self.max) } else { None }
+    }
+}
+
+/// A node's own severity, if it states one.
+fn stated<O>(report: &Report<Dynamic, O>, diagnostic: Option<&dyn Diagnostic>) -> Option<Severity> {
+    attachments::severity_override(report)
+        .or_else(|| diagnostic.and_then(Diagnostic::severity))
+        .or_else(|| is_message(report).then_some(Severity::Advice))
+}
+
+fn is_message<O>(report: &Report<Dynamic, O>) -> bool {
+    report.downcast_current_context::<String>().is_some()
+        || report.downcast_current_context::<&'static str>().is_some()
+}
+
+impl<E, O> LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    /// The most severe node in the tree; see the module notes for how each
+    /// node counts. Picks the tracing level and `ApiError::severity`.
+    #[must_use]
+    pub fn max_severity(&self) -> Severity {
+        self.aggregate_severity().max
+    }
+
+    /// [`LibReport::max_severity`], or `None` when no node states one.
+    pub(crate) fn reported_severity(&self) -> Option<Severity> {
+        self.aggregate_severity().reported()
+    }
+
+    fn aggregate_severity(&self) -> Aggregate {
+        let mut aggregate = Aggregate::new();
+        aggregate.add(self.severity());
+        aggregate.add_children(self.0.children(), view_as::<E, Cloneable>);
+        aggregate
+    }
+}
+
+impl DynLibReport {
+    /// The most severe node whose type is known, with the same defaults as
+    /// [`LibReport::max_severity`]; a report with no diagnostics at all is
+    /// an `Error`.
+    #[must_use]
+    pub fn max_severity(&self) -> Severity {
+        self.aggregate_severity().max
+    }
+
+    /// [`DynLibReport::max_severity`], or `None` when no node states one.
+    pub(crate) fn reported_severity(&self) -> Option<Severity> {
+        self.aggregate_severity().reported()
+    }
+
+    fn aggregate_severity(&self) -> Aggregate {
+        let mut aggregate = Aggregate::new();
+        aggregate.add(stated(&self.0, self.context()));
+        aggregate.add_children(self.0.children(), self.children_view());
+        aggregate
+    }
+}
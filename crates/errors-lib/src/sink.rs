@@ -0,0 +1,240 @@
+/*
+ * Pluggable destinations for emitted ApiErrors.
+ *
+ * to_api_error() hands each ApiError to every registered ErrorSink, so one
+ * call can reach a log file, an HTTP collector and Sentry at once. The
+ * registry starts out holding a TracingSink, so logging works with no
+ * setup; clear it to take tracing out of the loop. BufferSink keeps the
+ * last few errors in memory, e.g. for a debug endpoint.
+ *
+ * Errors are redacted on the way in when a sink redaction config is set
+ * (redact.rs). A panicking sink is caught and logged, and the sinks after
+ * it still run.
+ * While sinks run, a thread-local flag is set, so the panic hook (panic.rs)
+ * doesn't hand a sink's own panic back to the sinks. Emission can be
+ * rate-limited per code; see throttle.rs.
+ */
+
+use std::{
+    cell::Cell,
+    collections::VecDeque,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError, RwLock},
+};
+
+use crate::{ApiError, redact, throttle};
+
+/// A destination for the `ApiError`s built by
+/// [`ReportExt::to_api_error`](crate::ReportExt::to_api_error).
+pub trait ErrorSink: Send + Sync {
+    /// Receives one error. Runs after the rate limit has admitted it and
+    /// after sink redaction, on the thread that built it — possibly inside
+    /// the panic hook, while that thread is panicking.
+    ///
+    /// Must not panic or block: a panic is caught but loses this error for
+    /// this sink, and a blocked sink stalls the caller (and, from the hook,
+    /// the panic). Hand slow work to another thread.
+    fn emit(&self, error: &ApiError);
+}
+
+/// Logs each error through tracing at its [`ApiError::level`], via
+/// [`ApiError::log_to_tracing`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingSink;
+
+impl ErrorSink for TracingSink {
+    fn emit(&self, error: &ApiError) {
+        error.log_to_tracing(error.level());
+    }
+}
+
+impl<S: ErrorSink + ?Sized> ErrorSink for Box<S> {
+    fn emit(&self, error: &ApiError) {
+        (**self).emit(error);
+    }
+}
+
+/// Keeps the last `capacity` errors in memory, oldest first.
+///
+/// Clones share one buffer, so register a clone and read from the
+/// original:
+///
+/// ```
+/// use errors_lib::{BufferSink, ErrorSinkRegistry};
+///
+/// let recent = BufferSink::new(50);
+/// ErrorSinkRegistry::register(recent.clone());
+/// // ... later, from a debug endpoint:
+/// let errors = recent.errors();
+/// ```
+#[derive(Debug, Clone)]
+pub struct BufferSink {
+    capacity: usize,
+    errors: Arc<Mutex<VecDeque<ApiError>>>,
+}
+
+impl BufferSink {
+    /// A buffer holding at most `capacity` errors; with 0 it holds none.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            errors: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    /// The most errors kept at once.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The buffered errors, oldest first.
+    #[must_use]
+    pub fn errors(&self) -> Vec<ApiError> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// How many errors are buffered.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether no errors are buffered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Drops every buffered error.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<ApiError>> {
+        self.errors.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl ErrorSink for BufferSink {
+    fn emit(&self, error: &ApiError) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut errors = self.lock();
+        if errors.len() == self.capacity {
+            errors.pop_front();
+        }
+        errors.push_back(error.clone());
+    }
+}
+
+type Sinks = RwLock<Vec<Arc<dyn ErrorSink>>>;
+
+fn sinks() -> &'static Sinks {
+    static SINKS: OnceLock<Sinks> = OnceLock::new();
+    SINKS.get_or_init(|| RwLock::new(vec![Arc::new(TracingSink)]))
+}
+
+/// The process-wide list of [`ErrorSink`]s, initially just a
+/// [`TracingSink`]. Sinks run in registration order.
+///
+/// ```
+/// use errors_lib::{ApiError, ErrorSink, ErrorSinkRegistry};
+///
+/// struct Stderr;
+///
+/// impl ErrorSink for Stderr {
+///     fn emit(&self, error: &ApiError) {
+///         eprintln!("[{}] {}", error.correlation_id, error.title);
+///     }
+/// }
+///
+/// ErrorSinkRegistry::register(Stderr);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorSinkRegistry;
+
+impl ErrorSinkRegistry {
+    /// Adds `sink` after the sinks already registered. A
+    /// `Box<dyn ErrorSink>` is a sink too.
+    pub fn register(sink: impl ErrorSink + 'static) {
+        sinks()
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Arc::new(sink));
+    }
+
+    /// Removes every sink, the default [`TracingSink`] included; register
+    /// it again to keep logging.
+    pub fn clear() {
+        sinks()
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Hands `error` to every sink, unless the
+    /// [emit rate limit](crate::set_emit_rate_limit) holds it back.
+    pub(crate) fn emit(error: &ApiError) {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record(error);
+        if throttle::admit(error) {
+            Self::deliver(error);
+        }
+    }
+
+    /// Hands `error` to every sink, past the rate limit, redacted as
+    /// [`set_sink_redaction`](crate::set_sink_redaction) says. The sinks
+    /// are copied out first, so none runs under the lock and a sink may
+    /// register others. A sink that panics is logged and skipped; the rest
+    /// still run.
+    pub(crate) fn deliver(error: &ApiError) {
+        let _delivering = Delivering::enter();
+        let error = redact::for_sinks(error);
+        let sinks: Vec<_> = sinks()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(Arc::clone)
+            .collect();
+        for sink in sinks {
+            if panic::catch_unwind(AssertUnwindSafe(|| sink.emit(&error))).is_err() {
+                tracing::error!(
+                    correlation_id = %error.correlation_id,
+                    "error sink panicked while emitting"
+                );
+            }
+        }
+    }
+}
+
+thread_local! {
+    static DELIVERING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Marks the current thread as running sinks until dropped, unwinding
+/// included.
+struct Delivering {
+    outer: bool,
+}
+
+impl Delivering {
+    fn enter() -> Self {
+        Self {
+            outer: DELIVERING.replace(true),
+        }
+    }
+}
+
+impl Drop for Delivering {
+    fn drop(&mut self) {
+        DELIVERING.set(self.outer);
+    }
+}
+
+/// Whether sinks are running on this thread, e.g. when a sink panics.
+pub(crate) fn delivering() -> bool {
+    DELIVERING.get()
+}
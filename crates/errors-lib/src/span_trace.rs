@@ -0,0 +1,116 @@
+/*
+ * SpanTrace capture (`span-trace` feature).
+ *
+ * With tracing-error's ErrorLayer installed, the spans active when an
+ * ApiError is built say what the process was doing when it failed. They
+ * are recorded as `name{fields}`, innermost span first — the order
+ * tracing-error itself prints them in.
+ *
+ * By then the spans that were active where the error arose may have
+ * closed, so with set_span_trace_capture(true) LibReport::new also takes a
+ * SpanTrace and keeps it as a typed attachment; ApiError::span_trace and
+ * the rendered report prefer that one. Capturing walks the span stack, so
+ * it is off by default.
+ */
+
+use std::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use rootcause::Report;
+use tracing_error::{SpanTrace, SpanTraceStatus};
+
+static CAPTURE_ON_CREATE: AtomicBool = AtomicBool::new(false);
+
+/// Whether [`LibReport::new`](crate::LibReport::new) captures a
+/// [`ReportSpanTrace`]; off by default.
+pub fn set_span_trace_capture(enabled: bool) {
+    CAPTURE_ON_CREATE.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether [`set_span_trace_capture`] is on.
+#[must_use]
+pub fn span_trace_capture() -> bool {
+    CAPTURE_ON_CREATE.load(Ordering::Relaxed)
+}
+
+/// The spans active where a report was created.
+///
+/// [`LibReport::new`](crate::LibReport::new) attaches one when
+/// [`set_span_trace_capture`] is on and an `ErrorLayer` recorded spans.
+/// Retrieve it with `report.get_attachment::<ReportSpanTrace>()`.
+#[derive(Debug, Clone)]
+pub struct ReportSpanTrace(pub SpanTrace);
+
+impl ReportSpanTrace {
+    /// The current span trace, or `None` outside any span or without an
+    /// `ErrorLayer` installed.
+    #[must_use]
+    pub fn capture() -> Option<Self> {
+        let trace = SpanTrace::capture();
+        (trace.status() == SpanTraceStatus::CAPTURED).then_some(Self(trace))
+    }
+
+    /// The spans as `name{fields}`, innermost first.
+    #[must_use]
+    pub fn spans(&self) -> Vec<String> {
+        let mut spans = Vec::new();
+        self.0.with_spans(|metadata, fields| {
+            spans.push(format!("{}{{{fields}}}", metadata.name()));
+            true
+        });
+        spans
+    }
+}
+
+/// tracing-error's multi-line rendering, with source locations.
+impl fmt::Display for ReportSpanTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// The current span trace, as for `ApiError::span_trace`.
+pub fn capture() -> Option<Vec<String>> {
+    ReportSpanTrace::capture()
+        .map(|trace| trace.spans())
+        .filter(|spans| !spans.is_empty())
+}
+
+/// The first [`ReportSpanTrace`] in the tree, from the top-level node down.
+pub fn trace_of<C: ?Sized, O>(report: &Report<C, O>) -> Option<&ReportSpanTrace> {
+    report.iter_reports().find_map(|node| {
+        node.attachments()
+            .iter()
+            .find_map(|attachment| attachment.downcast_inner::<ReportSpanTrace>())
+    })
+}
+
+/// `help`, followed by a span trace section when the report carries one.
+pub fn help_with_trace<'a, C: ?Sized, O>(
+    help: Option<Box<dyn fmt::Display + 'a>>,
+    report: &'a Report<C, O>,
+) -> Option<Box<dyn fmt::Display + 'a>> {
+    let Some(trace) = trace_of(report) else {
+        return help;
+    };
+    Some(Box::new(HelpWithTrace {
+        help,
+        trace,
+    }))
+}
+
+struct HelpWithTrace<'a> {
+    help: Option<Box<dyn fmt::Display + 'a>>,
+    trace: &'a ReportSpanTrace,
+}
+
+impl fmt::Display for HelpWithTrace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(help) = &self.help {
+            write!(f, "{help}\n\n")?;
+        }
+        write!(f, "span trace:\n{}", self.trace)
+    }
+}
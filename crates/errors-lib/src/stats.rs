@@ -0,0 +1,65 @@
+/*
+ * Process-wide error counts by code.
+ *
+ * Every to_api_error() call records the report's code here, so a health
+ * endpoint or shutdown hook can show which failures dominate without a
+ * metrics stack. Reports without a code are not counted.
+ */
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+/// Counts of the diagnostic codes seen by `to_api_error`.
+#[derive(Debug, Default)]
+pub struct ErrorStats {
+    counts: Mutex<HashMap<String, u64>>,
+}
+
+impl ErrorStats {
+    /// The process-wide counters `to_api_error` records into.
+    #[must_use]
+    pub fn global() -> &'static Self {
+        static STATS: OnceLock<ErrorStats> = OnceLock::new();
+        STATS.get_or_init(Self::default)
+    }
+
+    /// Adds one occurrence of `code`.
+    pub fn record(&self, code: &str) {
+        *self
+            .counts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(code.to_owned())
+            .or_default() += 1;
+    }
+
+    /// How many times `code` has been recorded.
+    #[must_use]
+    pub fn count(&self, code: &str) -> u64 {
+        self.counts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(code)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Every recorded code with its count.
+    #[must_use]
+    pub fn snapshot(&self) -> HashMap<String, u64> {
+        self.counts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Forgets every count.
+    pub fn reset(&self) {
+        self.counts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
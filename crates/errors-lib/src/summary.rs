@@ -0,0 +1,132 @@
+/*
+ * One-line summaries of a report tree.
+ *
+ * The miette render is too tall for a WARN line. A summary keeps the part
+ * a reader scans for: every context's message, outermost first, joined
+ * with ": ", then the top-level code and a count of the attachments left
+ * out. ApiError::summary carries it for trees with children, and the
+ * tracing event uses it as its title.
+ */
+
+use std::fmt::{self, Write};
+
+use miette::Diagnostic;
+use rootcause::Report;
+
+use crate::{DynLibReport, LibReport, messages, walk_report};
+
+const ELLIPSIS: char = '…';
+
+impl<E, O> LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    /// The tree on one line, e.g.
+    /// `Failed to parse config at config.json: Network timeout after 30s
+    /// (config::invalid_format) [+3 context]`: each context's message
+    /// depth-first from the top-level node, the top-level code when there
+    /// is one, and how many text attachments were left out.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let code = self.code().map(|code| code.to_string());
+        summary_of(&self.0, code.as_deref())
+    }
+
+    /// [`LibReport::summary`] cut to at most `max_len` bytes, ending in
+    /// `…` when shortened.
+    #[must_use]
+    pub fn summary_with(&self, max_len: usize) -> String {
+        truncated(self.summary(), max_len)
+    }
+}
+
+impl DynLibReport {
+    /// The tree on one line; see [`LibReport::summary`]. The code is
+    /// included when the top-level type is known.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let code = self.code().map(|code| code.to_string());
+        summary_of(&self.0, code.as_deref())
+    }
+
+    /// [`DynLibReport::summary`] cut to at most `max_len` bytes, ending in
+    /// `…` when shortened.
+    #[must_use]
+    pub fn summary_with(&self, max_len: usize) -> String {
+        truncated(self.summary(), max_len)
+    }
+}
+
+/// The one-line summary of `report`, whose top-level code is `code`.
+pub fn summary_of<C: ?Sized, O>(report: &Report<C, O>, code: Option<&str>) -> String {
+    let mut elided = 0;
+    let contexts: Vec<String> = walk_report(report, |_, node| {
+        elided += messages(node).count();
+        vec![node.format_current_context().to_string()]
+    })
+    .collect();
+
+    let mut summary = contexts.join(": ");
+    // Writing into a String cannot fail.
+    if let Some(code) = code {
+        let _ = write!(summary, " ({code})");
+    }
+    if elided > 0 {
+        let _ = write!(summary, " [+{elided} context]");
+    }
+    summary
+}
+
+/// `text` cut to at most `max_len` bytes, ending in `…` when shortened.
+///
+/// The cut never separates a character from the combining marks,
+/// variation selectors or zero-width joiner sequence that follow it, so
+/// accented letters and composed emoji are kept or dropped whole.
+fn truncated(mut text: String, max_len: usize) -> String {
+    if text.len() <= max_len {
+        return text;
+    }
+
+    let ellipsis = if max_len >= ELLIPSIS.len_utf8() {
+        ELLIPSIS.len_utf8()
+    } else {
+        0
+    };
+    let mut end = (0..=max_len - ellipsis)
+        .rev()
+        .find(|&i| text.is_char_boundary(i))
+        .unwrap_or(0);
+    while end > 0 && splits_cluster(&text, end) {
+        end = text[..end].char_indices().next_back().map_or(0, |(i, _)| i);
+    }
+
+    text.truncate(end);
+    if ellipsis > 0 {
+        text.push(ELLIPSIS);
+    }
+    text
+}
+
+/// Whether cutting `text` at the char boundary `at` would split a cluster.
+fn splits_cluster(text: &str, at: usize) -> bool {
+    let next = text[at..].chars().next();
+    let prev = text[..at].chars().next_back();
+    next.is_some_and(extends_cluster) || prev == Some('\u{200D}')
+}
+
+/// Characters that attach to the one before them.
+const fn extends_cluster(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{036F}'
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{200D}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FE20}'..='\u{FE2F}'
+            | '\u{1F3FB}'..='\u{1F3FF}'
+            | '\u{E0100}'..='\u{E01EF}'
+    )
+}
@@ -0,0 +1,155 @@
+/*
+ * Snapshot-testing helpers for ApiError.
+ *
+ * ApiError carries volatile fields — correlation_id (fresh per call), the
+ * git state (changes per commit/checkout), build_timestamp (changes per
+ * build), the toolchain (changes per machine) and the thread (changes with
+ * the test harness's threading) — that every snapshot test has to redact.
+ * These helpers do it once, and re-export insta so consumers don't need to
+ * depend on it directly.
+ *
+ * Available in this crate's own tests and, for consumers, behind the
+ * `test-helpers` feature.
+ */
+
+pub use insta;
+
+use crate::ApiError;
+
+/// Placeholder written over `ApiError::correlation_id`.
+pub const REDACTED_ID: &str = "REDACTED_ID";
+
+/// Placeholder written over `ApiError::git_hash`.
+pub const REDACTED_HASH: &str = "REDACTED_HASH";
+
+/// Placeholder written over `ApiError::git_branch`.
+pub const REDACTED_BRANCH: &str = "REDACTED_BRANCH";
+
+/// Placeholder written over `ApiError::build_timestamp`.
+pub const REDACTED_TIMESTAMP: &str = "REDACTED_TIMESTAMP";
+
+/// Placeholder written over `ApiError::rustc_version`.
+pub const REDACTED_RUSTC: &str = "REDACTED_RUSTC";
+
+/// Placeholder written over `ApiError::target`.
+pub const REDACTED_TARGET: &str = "REDACTED_TARGET";
+
+/// Placeholder written over `ApiError::thread_id`, and over
+/// `ApiError::thread_name` when there is one.
+pub const REDACTED_THREAD: &str = "REDACTED_THREAD";
+
+/// Replaces the volatile `correlation_id`, git state, `build_timestamp`,
+/// toolchain and thread fields with fixed values so the error can be
+/// snapshotted. `git_dirty` is reset to `false`.
+#[must_use]
+pub fn normalize_api_error(mut api_err: ApiError) -> ApiError {
+    api_err.correlation_id = REDACTED_ID.to_string();
+    api_err.git_hash = REDACTED_HASH.into();
+    api_err.git_branch = REDACTED_BRANCH.into();
+    api_err.git_dirty = false;
+    api_err.build_timestamp = REDACTED_TIMESTAMP.into();
+    api_err.rustc_version = REDACTED_RUSTC.into();
+    api_err.target = REDACTED_TARGET.into();
+    api_err.thread_id = REDACTED_THREAD.to_string();
+    if api_err.thread_name.is_some() {
+        api_err.thread_name = Some(REDACTED_THREAD.to_string());
+    }
+    api_err
+}
+
+/// Snapshots a normalized `ApiError` as JSON under the given name.
+///
+/// A macro rather than a function so insta resolves the snapshot directory
+/// relative to the calling test, not to this crate:
+///
+/// ```rust,ignore
+/// errors_lib::assert_api_error_json_snapshot!(&report.to_api_error(), "config_error");
+/// ```
+#[macro_export]
+macro_rules! assert_api_error_json_snapshot {
+    ($api_err:expr, $name:expr $(,)?) => {{
+        let normalized = $crate::testing::normalize_api_error(::std::clone::Clone::clone($api_err));
+        $crate::testing::insta::assert_json_snapshot!($name, normalized);
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{ErrorCode, ErrorFrame};
+
+    fn sample() -> ApiError {
+        ApiError {
+            git_hash: "abc1234".into(),
+            git_branch: "main".into(),
+            git_dirty: true,
+            build_timestamp: "2026-01-01T00:00:00Z".into(),
+            rustc_version: "rustc 1.90.0 (1159e78c4 2025-09-14)".into(),
+            target: "x86_64-linux-gnu".into(),
+            docs_url: "https://docs.rs/errors-lib/0.1.0".into(),
+            code_url: Some("https://docs.rs/errors-lib/0.1.0/#config::invalid_format".to_string()),
+            correlation_id: "V1StGXR8".to_string(),
+            title: "Failed to parse config at config.json".to_string(),
+            summary: None,
+            code: ErrorCode::new("config::invalid_format").ok(),
+            domain: Some("config".to_string()),
+            help: Some("Ensure the configuration file is valid JSON.".to_string()),
+            severity: None,
+            location: None,
+            thread_name: Some("worker-1".to_string()),
+            thread_id: "ThreadId(1)".to_string(),
+            fields: BTreeMap::new(),
+            extra: BTreeMap::new(),
+            retryable: false,
+            retry_after_ms: None,
+            max_retries: None,
+            history: vec![ErrorFrame {
+                message: "The application cannot proceed without a valid config.".to_string(),
+            }],
+            span_trace: None,
+            trace_id: None,
+            span_id: None,
+            tree: None,
+        }
+    }
+
+    #[test]
+    fn test_normalize_replaces_only_volatile_fields() {
+        let original = serde_json::to_value(sample()).unwrap();
+        let normalized = serde_json::to_value(normalize_api_error(sample())).unwrap();
+
+        assert_eq!(normalized["correlation_id"], REDACTED_ID);
+        assert_eq!(normalized["git_hash"], REDACTED_HASH);
+        assert_eq!(normalized["git_branch"], REDACTED_BRANCH);
+        assert_eq!(normalized["git_dirty"], false);
+        assert_eq!(normalized["build_timestamp"], REDACTED_TIMESTAMP);
+        assert_eq!(normalized["rustc_version"], REDACTED_RUSTC);
+        assert_eq!(normalized["target"], REDACTED_TARGET);
+        assert_eq!(normalized["thread_name"], REDACTED_THREAD);
+        assert_eq!(normalized["thread_id"], REDACTED_THREAD);
+
+        let (mut original, mut normalized) = (original, normalized);
+        for key in [
+            "correlation_id",
+            "git_hash",
+            "git_branch",
+            "git_dirty",
+            "build_timestamp",
+            "rustc_version",
+            "target",
+            "thread_name",
+            "thread_id",
+        ] {
+            original.as_object_mut().unwrap().remove(key);
+            normalized.as_object_mut().unwrap().remove(key);
+        }
+        assert_eq!(original, normalized);
+    }
+
+    #[test]
+    fn test_assert_api_error_json_snapshot() {
+        crate::assert_api_error_json_snapshot!(&sample(), "normalized_api_error");
+    }
+}
@@ -0,0 +1,194 @@
+/*
+ * Rate-limited emission.
+ *
+ * A retry loop converting the same failure floods the sinks with lines
+ * that differ only in correlation_id. With a limit set, each code (the
+ * title, for errors without one) may reach the sinks `per_code` times per
+ * fixed window; the rest are counted, and once the window has passed a
+ * single summary ApiError (code `throttle::suppressed`, at WARN) goes to
+ * the sinks saying how many were dropped. Only emission is throttled:
+ * to_api_error still returns every ApiError in full.
+ *
+ * There is no timer thread: ended windows are summarized lazily, on the
+ * next emission, on flush_emit_summaries(), or when a LoggingGuard drops.
+ */
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock, PoisonError},
+    time::{Duration, Instant},
+};
+
+use miette::Severity;
+
+use crate::{ApiError, ErrorSinkRegistry, build_api_error};
+
+/// The code of the summary emitted for a window that dropped errors. Its
+/// `fields` carry the throttled code (or title) under `suppressed_code`
+/// and the count under `suppressed`.
+pub const SUPPRESSED_CODE: &str = "throttle::suppressed";
+
+struct Limiter {
+    per_code: u32,
+    window: Duration,
+    windows: HashMap<String, Window>,
+}
+
+struct Window {
+    started: Instant,
+    emitted: u32,
+    suppressed: u64,
+}
+
+/// A window that ended with errors dropped.
+struct Suppressed {
+    key: String,
+    count: u64,
+    window: Duration,
+}
+
+impl Limiter {
+    /// Counts one error for `key`, returning whether it may be emitted and
+    /// the windows that ended meanwhile.
+    fn admit(&mut self, key: &str, now: Instant) -> (bool, Vec<Suppressed>) {
+        let ended = self.expire(now);
+        let window = self
+            .windows
+            .entry(key.to_string())
+            .or_insert_with(|| Window {
+                started: now,
+                emitted: 0,
+                suppressed: 0,
+            });
+        let admitted = window.emitted < self.per_code;
+        if admitted {
+            window.emitted += 1;
+        } else {
+            window.suppressed += 1;
+        }
+        (admitted, ended)
+    }
+
+    /// Closes the windows that have run their course, returning those
+    /// that dropped anything.
+    fn expire(&mut self, now: Instant) -> Vec<Suppressed> {
+        let mut ended = Vec::new();
+        self.windows.retain(|key, window| {
+            if now.duration_since(window.started) < self.window {
+                return true;
+            }
+            if window.suppressed > 0 {
+                ended.push(Suppressed {
+                    key: key.clone(),
+                    count: window.suppressed,
+                    window: self.window,
+                });
+            }
+            false
+        });
+        ended
+    }
+
+    /// Closes every window, ended or not, returning those that dropped
+    /// anything; for when the limit itself goes away.
+    fn expire_all(self) -> Vec<Suppressed> {
+        let window = self.window;
+        self.windows
+            .into_iter()
+            .filter(|(_, w)| w.suppressed > 0)
+            .map(|(key, w)| Suppressed {
+                key,
+                count: w.suppressed,
+                window,
+            })
+            .collect()
+    }
+}
+
+fn limiter() -> &'static Mutex<Option<Limiter>> {
+    static LIMITER: OnceLock<Mutex<Option<Limiter>>> = OnceLock::new();
+    LIMITER.get_or_init(Mutex::default)
+}
+
+/// Lets at most `per_code` errors with the same code through to the sinks
+/// per `window`, starting afresh; see the module notes.
+pub fn set_emit_rate_limit(per_code: u32, window: Duration) {
+    let previous = limiter()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .replace(Limiter {
+            per_code,
+            window,
+            windows: HashMap::new(),
+        });
+    if let Some(previous) = previous {
+        emit_summaries(previous.expire_all());
+    }
+}
+
+/// Removes the limit, emitting what it had suppressed so far.
+pub fn clear_emit_rate_limit() {
+    let previous = limiter()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+    if let Some(previous) = previous {
+        emit_summaries(previous.expire_all());
+    }
+}
+
+/// Emits the summary for every window that has ended. Nothing watches the
+/// clock, so summaries otherwise go out on the next emission after a
+/// window ends; dropping a [`LoggingGuard`](crate::LoggingGuard) calls
+/// this, and a long-running process whose errors may stop abruptly should
+/// call it periodically.
+pub fn flush_emit_summaries() {
+    let ended = limiter()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+        .map(|limiter| limiter.expire(Instant::now()))
+        .unwrap_or_default();
+    emit_summaries(ended);
+}
+
+/// Whether `error` may go to the sinks, counting it either way.
+pub fn admit(error: &ApiError) -> bool {
+    let key = error.code.as_deref().unwrap_or(&error.title);
+    let outcome = limiter()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+        .map(|limiter| limiter.admit(key, Instant::now()));
+    let Some((admitted, ended)) = outcome else {
+        return true;
+    };
+    emit_summaries(ended);
+    admitted
+}
+
+/// Hands each summary to the sinks directly, so summaries are never
+/// throttled themselves.
+fn emit_summaries(ended: Vec<Suppressed>) {
+    for Suppressed {
+        key,
+        count,
+        window,
+    } in ended
+    {
+        let mut summary = build_api_error(
+            Vec::new(),
+            format!("suppressed {count} occurrences of {key} in the last {window:?}"),
+            Some(SUPPRESSED_CODE.to_owned()),
+            None,
+            Some(Severity::Warning),
+            false,
+            None,
+        );
+        summary.fields.insert("suppressed_code".to_owned(), key);
+        summary
+            .fields
+            .insert("suppressed".to_owned(), count.to_string());
+        ErrorSinkRegistry::deliver(&summary);
+    }
+}
@@ -0,0 +1,152 @@
+/*
+ * ErrorTree: the rootcause graph as plain, serializable data.
+ *
+ * `history` flattens the report; this keeps its shape, for sinks that want
+ * to show which cause belongs under which context. Like ApiError, the type
+ * itself builds without std; only building one from a report needs it.
+ */
+
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::fmt;
+
+#[cfg(feature = "std")]
+use miette::Diagnostic;
+#[cfg(feature = "std")]
+use rootcause::{Report, markers::Cloneable};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+use crate::{DiagnosticView, LibReport, view_as};
+
+/// One node of a report and everything beneath it.
+///
+/// `code` is only known for nodes whose context type the report knows
+/// (`E` for a `LibReport<E>`). Attachments that render as nothing — typed
+/// payloads — are left out, as in `ApiError::history`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ErrorTree {
+    /// A node with no children.
+    Leaf {
+        /// The node's context message.
+        message: String,
+        /// The context's diagnostic code, when known.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        code: Option<String>,
+        /// The node's attachments, as displayed.
+        attachments: Vec<String>,
+    },
+    /// A node with at least one child.
+    Node {
+        /// The node's context message.
+        message: String,
+        /// The context's diagnostic code, when known.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        code: Option<String>,
+        /// The node's attachments, as displayed.
+        attachments: Vec<String>,
+        /// The child nodes, in order.
+        children: Vec<Self>,
+    },
+}
+
+impl ErrorTree {
+    /// The node's context message.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Leaf {
+                message, ..
+            }
+            | Self::Node {
+                message, ..
+            } => message,
+        }
+    }
+
+    /// The node's diagnostic code, when known.
+    #[must_use]
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            Self::Leaf {
+                code, ..
+            }
+            | Self::Node {
+                code, ..
+            } => code.as_deref(),
+        }
+    }
+
+    /// The node's direct children; empty for a leaf.
+    #[must_use]
+    pub fn children(&self) -> &[Self] {
+        match self {
+            Self::Leaf {
+                ..
+            } => &[],
+            Self::Node {
+                children, ..
+            } => children,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ErrorTree {
+    /// Builds a node from a report, given its own code; children's codes
+    /// come from `view`.
+    pub(crate) fn build<C: ?Sized, O>(
+        report: &Report<C, O>,
+        code: Option<String>,
+        view: DiagnosticView,
+    ) -> Self {
+        let message = report.format_current_context().to_string();
+        let attachments = report
+            .attachments()
+            .iter()
+            .map(ToString::to_string)
+            .filter(|message| !message.is_empty())
+            .collect();
+        let children: Vec<Self> = report
+            .children()
+            .iter()
+            .map(|child| {
+                let child = child.clone_arc();
+                let code = view(&child)
+                    .and_then(Diagnostic::code)
+                    .map(|c| c.to_string());
+                Self::build(&child, code, view)
+            })
+            .collect();
+
+        if children.is_empty() {
+            Self::Leaf {
+                message,
+                code,
+                attachments,
+            }
+        } else {
+            Self::Node {
+                message,
+                code,
+                attachments,
+                children,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E, O> LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    /// The report as an [`ErrorTree`], preserving parent/child structure.
+    #[must_use]
+    pub fn to_error_tree(&self) -> ErrorTree {
+        let code = self.0.current_context().code().map(|c| c.to_string());
+        ErrorTree::build(&self.0, code, view_as::<E, Cloneable>)
+    }
+}
@@ -0,0 +1,133 @@
+/*
+ * Depth-first visitors over a report tree.
+ *
+ * Applications react to what went wrong (retry on a timeout, prompt on a
+ * missing file) by implementing ErrorVisitor and handing it to
+ * LibReport::visit, instead of hand-rolling the iter_reports() loop and
+ * downcasts each time. classify_error covers the common io cases without
+ * one.
+ *
+ * A context often wraps the error worth reacting to (an io::Error inside a
+ * snafu variant), so nodes expose the context's whole source() chain, not
+ * just the context itself.
+ */
+
+use std::{error::Error, fmt};
+
+use miette::Diagnostic;
+use rootcause::{
+    ReportRef,
+    markers::{Dynamic, Uncloneable},
+};
+
+use crate::{LibReport, messages};
+
+/// One node of a report tree, as handed to an [`ErrorVisitor`].
+#[derive(Clone, Copy)]
+pub struct ErrorNode<'a> {
+    node: ReportRef<'a, Dynamic, Uncloneable>,
+    source: Option<&'a (dyn Error + 'static)>,
+}
+
+impl<'a> ErrorNode<'a> {
+    /// The node, with its context's source as seen through `E`.
+    pub(crate) fn new<E: Error + 'static>(node: ReportRef<'a, Dynamic, Uncloneable>) -> Self {
+        Self {
+            node,
+            source: source_of::<E>(node),
+        }
+    }
+
+    /// The node's context, if it is a `C`.
+    #[must_use]
+    pub fn context<C: 'static>(&self) -> Option<&'a C> {
+        self.node.downcast_current_context()
+    }
+
+    /// The first attachment of type `T` on this node.
+    #[must_use]
+    pub fn attachment<T: 'static>(&self) -> Option<&'a T> {
+        self.node
+            .attachments()
+            .iter()
+            .find_map(|attachment| attachment.downcast_inner())
+    }
+
+    /// The node's attachments that render as text, in attachment order.
+    pub fn messages(&self) -> impl Iterator<Item = String> + 'a {
+        messages(self.node)
+    }
+
+    /// The context's `Error::source()`, if the context is an error.
+    #[must_use]
+    pub fn source(&self) -> Option<&'a (dyn Error + 'static)> {
+        self.source
+    }
+
+    /// The context's source chain: [`ErrorNode::source`], its source, and
+    /// so on.
+    pub fn sources(&self) -> impl Iterator<Item = &'a (dyn Error + 'static)> {
+        std::iter::successors(self.source, |&err| err.source())
+    }
+
+    /// The context if it is a `T`, else the first `T` in its source chain,
+    /// e.g. the `io::Error` inside a snafu `Io { source }` variant.
+    #[must_use]
+    pub fn find_error<T: Error + 'static>(&self) -> Option<&'a T> {
+        self.context()
+            .or_else(|| self.sources().find_map(|err| err.downcast_ref()))
+    }
+}
+
+/// The source of a node's context: through `E` when the context is one,
+/// which works wherever the erased node's own view does not.
+pub fn source_of<E: Error + 'static>(
+    node: ReportRef<'_, Dynamic, Uncloneable>,
+) -> Option<&(dyn Error + 'static)> {
+    node.downcast_current_context::<E>()
+        .map_or_else(|| node.current_context_error_source(), Error::source)
+}
+
+/// The context's message.
+impl fmt::Display for ErrorNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.node.format_current_context(), f)
+    }
+}
+
+impl fmt::Debug for ErrorNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.node.format_current_context(), f)
+    }
+}
+
+/// Reacts to the nodes of a report; see [`LibReport::visit`].
+pub trait ErrorVisitor {
+    /// Called once per node, parents before their children.
+    fn visit_node(&mut self, node: &ErrorNode<'_>);
+
+    /// Called after [`ErrorVisitor::visit_node`] for each of the node's
+    /// attachments that renders as text, in attachment order. Typed
+    /// payloads are reached with [`ErrorNode::attachment`] instead.
+    fn visit_attachment(&mut self, node: &ErrorNode<'_>, attachment: &str) {
+        let _ = (node, attachment);
+    }
+}
+
+impl<E, O> LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    /// Walks the tree depth-first, top-level node first, handing every node
+    /// and its attachments to `visitor`.
+    pub fn visit(&self, visitor: &mut impl ErrorVisitor) {
+        for node in self.0.iter_reports() {
+            let node = ErrorNode::new::<E>(node);
+            visitor.visit_node(&node);
+            for text in node.messages() {
+                visitor.visit_attachment(&node, &text);
+            }
+        }
+    }
+}
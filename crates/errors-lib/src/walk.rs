@@ -0,0 +1,169 @@
+/*
+ * Tree walks with depth, in either order.
+ *
+ * iter_reports() yields the nodes depth-first without saying how deep each
+ * one is. nodes_with_depth recovers the depth from each node's child
+ * count, and Walk replays the result depth-first or, after
+ * breadth_first(), level by level. ApiError history is built from the same
+ * walk, so its order is defined here and nowhere else.
+ */
+
+use std::{collections::VecDeque, fmt};
+
+use miette::Diagnostic;
+use rootcause::{
+    Report, ReportRef,
+    markers::{Dynamic, Uncloneable},
+};
+
+use crate::{ErrorNode, LibReport};
+
+/// Items taken from a report's nodes, depth-first unless
+/// [`Walk::breadth_first`] is called.
+#[derive(Debug, Clone)]
+pub struct Walk<T> {
+    items: VecDeque<(usize, T)>,
+}
+
+impl<T> Walk<T> {
+    /// Reorders the remaining items level by level: all of depth 0, then
+    /// all of depth 1, and so on. Within a level nodes keep their
+    /// depth-first order, and each node's items stay together.
+    #[must_use]
+    pub fn breadth_first(mut self) -> Self {
+        self.items
+            .make_contiguous()
+            .sort_by_key(|&(depth, _)| depth);
+        self
+    }
+}
+
+impl<T> Iterator for Walk<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.items.pop_front().map(|(_, item)| item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.items.len(), Some(self.items.len()))
+    }
+}
+
+impl<T> ExactSizeIterator for Walk<T> {}
+
+/// A summary of one node, from [`LibReport::iter_nodes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeView {
+    /// 0 for the top-level node, 1 for its children, and so on.
+    pub depth: usize,
+    /// The diagnostic code, when the context is an `E` with one.
+    pub code: Option<String>,
+    /// The context's message.
+    pub message: String,
+    /// Every attachment on the node, typed payloads included.
+    pub attachment_count: usize,
+}
+
+/// A node's context, from [`LibReport::iter_contexts`]: an `E`, or another
+/// type known only through the node.
+#[derive(Debug)]
+pub enum NodeContext<'a, E> {
+    /// The context is an `E`.
+    Typed(&'a E),
+    /// The context is of another type, e.g. an `io::Error` root.
+    Other(ErrorNode<'a>),
+}
+
+/// The context's message.
+impl<E: fmt::Display> fmt::Display for NodeContext<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Typed(context) => fmt::Display::fmt(context, f),
+            Self::Other(node) => fmt::Display::fmt(node, f),
+        }
+    }
+}
+
+impl<E, O> LibReport<E, O>
+where
+    E: Diagnostic + fmt::Display + fmt::Debug + Send + Sync + 'static,
+    O: 'static,
+{
+    /// Every node's context with its depth.
+    pub fn iter_contexts(&self) -> Walk<(usize, NodeContext<'_, E>)> {
+        walk_report(&self.0, |depth, node| {
+            let context = node.downcast_current_context::<E>().map_or_else(
+                || NodeContext::Other(ErrorNode::new::<E>(node)),
+                NodeContext::Typed,
+            );
+            vec![(depth, context)]
+        })
+    }
+
+    /// Every attachment that renders as text, each node's in attachment
+    /// order.
+    pub fn iter_attachment_strings(&self) -> Walk<String> {
+        walk_report(&self.0, |_, node| messages(node).collect())
+    }
+
+    /// A [`NodeView`] of every node.
+    pub fn iter_nodes(&self) -> Walk<NodeView> {
+        walk_report(&self.0, |depth, node| {
+            vec![NodeView {
+                depth,
+                code: node
+                    .downcast_current_context::<E>()
+                    .and_then(Diagnostic::code)
+                    .map(|code| code.to_string()),
+                message: node.format_current_context().to_string(),
+                attachment_count: node.attachments().len(),
+            }]
+        })
+    }
+}
+
+/// Every node with its depth, depth-first.
+///
+/// In pre-order, a node's depth is the number of ancestors that still have
+/// children to come, so counting down each node's children recovers it.
+pub fn nodes_with_depth<C: ?Sized, O>(
+    report: &Report<C, O>,
+) -> Vec<(usize, ReportRef<'_, Dynamic, Uncloneable>)> {
+    let mut remaining: Vec<usize> = Vec::new();
+    let mut nodes = Vec::new();
+    for node in report.iter_reports() {
+        while remaining.last() == Some(&0) {
+            remaining.pop();
+        }
+        let depth = remaining.len();
+        if let Some(siblings) = remaining.last_mut() {
+            *siblings -= 1;
+        }
+        remaining.push(node.children().len());
+        nodes.push((depth, node));
+    }
+    nodes
+}
+
+/// The items `f` takes from each node, tagged with the node's depth.
+pub fn walk_report<'a, C: ?Sized, O, T>(
+    report: &'a Report<C, O>,
+    mut f: impl FnMut(usize, ReportRef<'a, Dynamic, Uncloneable>) -> Vec<T>,
+) -> Walk<T> {
+    let items = nodes_with_depth(report)
+        .into_iter()
+        .flat_map(|(depth, node)| f(depth, node).into_iter().map(move |item| (depth, item)))
+        .collect();
+    Walk {
+        items,
+    }
+}
+
+/// A node's attachments that render as text.
+pub fn messages(node: ReportRef<'_, Dynamic, Uncloneable>) -> impl Iterator<Item = String> {
+    node.attachments()
+        .iter()
+        .map(ToString::to_string)
+        .filter(|text| !text.is_empty())
+}
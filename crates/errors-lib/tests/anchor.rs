@@ -0,0 +1,98 @@
+/*
+ * Integration tests for docs anchors built from diagnostic codes.
+ */
+
+mod common;
+
+use std::fmt;
+
+use common::config_report;
+use errors_lib::{LibReport, ReportExt, code_to_anchor, rootcause::Report};
+use miette::Diagnostic;
+
+/// An error whose code holds characters a URL fragment can't.
+#[derive(Debug)]
+struct SpacedCode;
+
+impl fmt::Display for SpacedCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("config rejected")
+    }
+}
+
+impl std::error::Error for SpacedCode {}
+
+impl Diagnostic for SpacedCode {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new("config::invalid format#2"))
+    }
+}
+
+/// An error whose code renders as nothing.
+#[derive(Debug)]
+struct EmptyCode;
+
+impl fmt::Display for EmptyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("config rejected")
+    }
+}
+
+impl std::error::Error for EmptyCode {}
+
+impl Diagnostic for EmptyCode {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new(""))
+    }
+}
+
+#[test]
+fn test_namespaced_code_is_unchanged() {
+    assert_eq!(
+        code_to_anchor("config::invalid_format"),
+        "config::invalid_format"
+    );
+}
+
+#[test]
+fn test_spaces_and_slashes_are_encoded() {
+    assert_eq!(code_to_anchor("disk full/quota"), "disk%20full%2Fquota");
+    assert_eq!(code_to_anchor("  io::error  "), "io::error");
+}
+
+#[test]
+fn test_unicode_is_utf8_encoded() {
+    assert_eq!(code_to_anchor("config::café"), "config::caf%C3%A9");
+}
+
+#[test]
+fn test_url_uses_the_anchor() {
+    let url = config_report().url().unwrap().to_string();
+    assert!(
+        url.ends_with("/#config::invalid_format"),
+        "unexpected url: {url}"
+    );
+}
+
+#[test]
+fn test_url_encodes_reserved_characters_in_the_code() {
+    let report = LibReport::new(Report::new(SpacedCode));
+    let url = report.url().unwrap().to_string();
+    assert!(
+        url.ends_with("/#config::invalid%20format%232"),
+        "unexpected url: {url}"
+    );
+    assert_eq!(url.matches('#').count(), 1);
+    assert!(!url.contains(' '));
+}
+
+#[test]
+fn test_empty_code_has_no_url() {
+    let report = LibReport::new(Report::new(EmptyCode));
+    assert!(report.url().is_none());
+    assert!(report.into_dynamic().url().is_none());
+
+    let api_err = LibReport::new(Report::new(EmptyCode)).to_api_error();
+    assert_eq!(api_err.code, None);
+    assert_eq!(api_err.domain, None);
+}
@@ -0,0 +1,32 @@
+/*
+ * Integration test for configuring the anchor separator.
+ *
+ * Kept in its own test binary: the separator is process-wide, and changing
+ * it would race the default-separator assertions in anchor.rs.
+ */
+
+mod common;
+
+use common::config_report;
+use errors_lib::{
+    DEFAULT_ANCHOR_SEPARATOR, anchor_separator, code_to_anchor, set_anchor_separator,
+};
+use miette::Diagnostic;
+
+#[test]
+fn test_set_anchor_separator_remaps_double_colons() {
+    assert_eq!(anchor_separator(), DEFAULT_ANCHOR_SEPARATOR);
+
+    set_anchor_separator("-");
+    assert_eq!(
+        code_to_anchor("config::invalid_format"),
+        "config-invalid_format"
+    );
+    let url = config_report().url().unwrap().to_string();
+    assert!(
+        url.ends_with("/#config-invalid_format"),
+        "unexpected url: {url}"
+    );
+
+    set_anchor_separator(DEFAULT_ANCHOR_SEPARATOR);
+}
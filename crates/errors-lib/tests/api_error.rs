@@ -6,7 +6,7 @@
  * error type, keeping errors-lib self-contained.
  */
 
-use errors_lib::{LibReport, ReportExt, rootcause::Report};
+use errors_lib::{ApiError, ApiErrorWriter, ErrorFrame, LibReport, ReportExt, rootcause::Report};
 use miette::{Diagnostic, NamedSource, SourceSpan};
 use serde_json::Value;
 use snafu::prelude::*;
@@ -29,6 +29,9 @@ enum TestError {
         #[label("syntax error here")]
         span: SourceSpan,
     },
+
+    #[snafu(display("Network unreachable"))]
+    NetworkError,
 }
 
 fn make_report() -> LibReport<TestError> {
@@ -68,6 +71,24 @@ fn test_api_error_json_structure() {
     // git_hash is present
     assert!(json_value.get("git_hash").is_some());
 
+    // git branch and dirty state are present ("unknown"/false outside git)
+    let branch = json_value["git_branch"]
+        .as_str()
+        .expect("git_branch missing");
+    assert!(!branch.is_empty());
+    assert!(json_value["git_dirty"].is_boolean());
+
+    // build_timestamp is an RFC 3339 UTC timestamp
+    let built = json_value["build_timestamp"]
+        .as_str()
+        .expect("build_timestamp missing");
+    assert_eq!(built.len(), "2026-01-01T00:00:00Z".len());
+    assert!(built.ends_with('Z'));
+
+    // toolchain fields are present ("unknown" when rustc couldn't be run)
+    assert!(!json_value["rustc_version"].as_str().unwrap().is_empty());
+    assert!(!json_value["target"].as_str().unwrap().is_empty());
+
     // history contains the attachment
     let history = json_value["history"].as_array().expect("history missing");
     assert!(
@@ -77,6 +98,70 @@ fn test_api_error_json_structure() {
     );
 }
 
+#[test]
+fn test_build_details_are_borrowed_constants() {
+    use std::borrow::Cow;
+
+    // Borrowed from the binary, so building an ApiError doesn't allocate
+    // a String per build field.
+    let api_error = make_report().to_api_error();
+    assert!(matches!(
+        api_error.git_hash,
+        Cow::Borrowed(env!("GIT_HASH"))
+    ));
+    assert!(matches!(
+        api_error.git_branch,
+        Cow::Borrowed(env!("GIT_BRANCH"))
+    ));
+    assert!(matches!(
+        api_error.build_timestamp,
+        Cow::Borrowed(env!("BUILD_TIMESTAMP"))
+    ));
+    assert!(matches!(
+        api_error.target,
+        Cow::Borrowed(env!("TARGET_TRIPLE"))
+    ));
+    assert!(matches!(api_error.docs_url, Cow::Borrowed(_)));
+    assert_eq!(api_error.docs_url, errors_lib::docs_base_url());
+
+    // Deserialized values are owned and compare equal.
+    let json = serde_json::to_string(&api_error).unwrap();
+    let back: ApiError = serde_json::from_str(&json).unwrap();
+    assert!(matches!(back.git_hash, Cow::Owned(_)));
+    assert_eq!(back, api_error);
+}
+
+#[test]
+fn test_rustc_version_is_populated() {
+    let api_error = make_report().to_api_error();
+
+    // build.rs falls back to "unknown" when rustc can't be run
+    if api_error.rustc_version != "unknown" {
+        assert!(
+            api_error.rustc_version.starts_with("rustc 1."),
+            "unexpected rustc version: {}",
+            api_error.rustc_version
+        );
+    }
+}
+
+#[test]
+fn test_domain_is_code_prefix() {
+    let api_error = make_report().to_api_error();
+    assert_eq!(api_error.domain.as_deref(), Some("config"));
+}
+
+#[test]
+fn test_domain_is_none_without_code() {
+    let report: LibReport<TestError> = Report::new(TestError::NetworkError).into();
+    let api_error = report.to_api_error();
+    assert!(api_error.code.is_none());
+    assert!(api_error.domain.is_none());
+
+    let json_value = serde_json::to_value(&api_error).unwrap();
+    assert!(json_value.get("domain").is_none());
+}
+
 #[test]
 fn test_snapshot_api_error() {
     let api_error = make_report().to_api_error();
@@ -85,6 +170,116 @@ fn test_snapshot_api_error() {
     let mut redacted = serde_json::to_value(&api_error).unwrap();
     redacted["correlation_id"] = Value::String("REDACTED_ID".to_string());
     redacted["git_hash"] = Value::String("REDACTED_HASH".to_string());
+    redacted["git_branch"] = Value::String("REDACTED_BRANCH".to_string());
+    redacted["git_dirty"] = Value::Bool(false);
+    redacted["build_timestamp"] = Value::String("REDACTED_TIMESTAMP".to_string());
+    redacted["rustc_version"] = Value::String("REDACTED_RUSTC".to_string());
+    redacted["target"] = Value::String("REDACTED_TARGET".to_string());
+    redacted["thread_name"] = Value::String("REDACTED_THREAD".to_string());
+    redacted["thread_id"] = Value::String("REDACTED_THREAD".to_string());
 
     insta::assert_json_snapshot!(redacted);
 }
+
+#[test]
+fn test_to_log_fields_contains_all_fields() {
+    // LibReport::new records the location that a bare LibReport(..) lacks.
+    let api_error = LibReport::new(make_report().0).to_api_error();
+    let fields = api_error.to_log_fields();
+
+    let keys: Vec<&str> = fields.iter().map(|(k, _)| *k).collect();
+    assert_eq!(keys, [
+        "hash", "docs", "id", "title", "code", "location", "history"
+    ]);
+
+    let field = |key: &str| {
+        fields
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.as_str())
+            .unwrap()
+    };
+    assert_eq!(field("id"), api_error.correlation_id);
+    assert_eq!(field("code"), "config::invalid_format");
+    assert!(field("history").contains("valid config"));
+}
+
+#[test]
+fn test_api_error_writer_emits_json_lines() {
+    let mut writer = ApiErrorWriter::new(Vec::new());
+    for _ in 0..3 {
+        writer.write(&make_report().to_api_error()).unwrap();
+    }
+    writer.flush().unwrap();
+
+    let buf = String::from_utf8(writer.into_inner()).unwrap();
+    let lines: Vec<&str> = buf.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(buf.ends_with('\n'));
+
+    for line in lines {
+        let value: Value = serde_json::from_str(line).expect("line is not valid JSON");
+        assert_eq!(value["code"], "config::invalid_format");
+    }
+}
+
+fn with_history(messages: impl IntoIterator<Item = String>) -> ApiError {
+    let mut api_error = make_report().to_api_error();
+    api_error.history = messages
+        .into_iter()
+        .map(|message| ErrorFrame {
+            message,
+        })
+        .collect();
+    api_error
+}
+
+#[test]
+fn test_history_serializes_as_a_flat_string_array() {
+    let messages = ["first", "second \"quoted\"", "third"].map(String::from);
+    let api_error = with_history(messages.clone());
+
+    // Byte for byte what serializing the messages as a Vec<&str> gives.
+    let expected =
+        serde_json::to_string(&messages.iter().map(String::as_str).collect::<Vec<_>>()).unwrap();
+    let json = serde_json::to_string(&api_error).unwrap();
+    assert!(
+        json.contains(&format!("\"history\":{expected}")),
+        "unexpected JSON: {json}"
+    );
+}
+
+#[test]
+fn test_large_history_round_trips() {
+    let api_error = with_history((0..10_000).map(|i| format!("frame {i}")));
+
+    let json = serde_json::to_string(&api_error).unwrap();
+    let value: Value = serde_json::from_str(&json).unwrap();
+    let history = value["history"].as_array().unwrap();
+    assert_eq!(history.len(), 10_000);
+    assert_eq!(history[9_999], "frame 9999");
+
+    let back: ApiError = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, api_error);
+}
+
+#[test]
+fn test_json_from_before_the_added_fields_deserializes() {
+    // The original wire format: no build details, retry data or threads.
+    let json = r#"{
+        "git_hash": "abc1234",
+        "docs_url": "https://docs.rs/errors-lib/0.1.0",
+        "correlation_id": "V1StGXR8",
+        "title": "Failed to parse config at config.json",
+        "code": "config::invalid_format",
+        "history": ["The application cannot proceed without a valid config."]
+    }"#;
+
+    let api_error: ApiError = serde_json::from_str(json).unwrap();
+    assert_eq!(api_error.title, "Failed to parse config at config.json");
+    assert_eq!(api_error.git_branch, "");
+    assert!(!api_error.git_dirty);
+    assert!(!api_error.retryable);
+    assert_eq!(api_error.retry_after_ms, None);
+    assert_eq!(api_error.history.len(), 1);
+}
@@ -0,0 +1,105 @@
+/*
+ * Integration tests for building an ApiError without a report.
+ */
+
+mod common;
+
+use common::config_report;
+use errors_lib::{
+    ApiError, ApiErrorBuilder, ApiErrorBuilderError, LibReport, ReportExt, rootcause::Report,
+};
+use miette::Diagnostic;
+use snafu::Snafu;
+
+#[derive(Debug, Snafu, Diagnostic)]
+#[snafu(display("Legacy failure"))]
+#[diagnostic(code(E0001))]
+struct Legacy;
+
+fn legacy_report() -> LibReport<Legacy> {
+    Report::new(Legacy).into()
+}
+
+#[test]
+fn test_builder_matches_to_api_error() {
+    let mut expected = config_report().to_api_error();
+    // The builder has no call site to record.
+    expected.location = None;
+
+    let built = ApiErrorBuilder::new()
+        .title(expected.title.clone())
+        .code("config::invalid_format")
+        .help(expected.help.clone().unwrap())
+        .correlation_id(expected.correlation_id.clone())
+        .history(expected.history.iter().map(|frame| frame.message.clone()))
+        .build()
+        .unwrap();
+
+    assert_eq!(built, expected);
+    assert_eq!(
+        serde_json::to_string(&built).unwrap(),
+        serde_json::to_string(&expected).unwrap()
+    );
+}
+
+#[test]
+fn test_builder_defaults_and_overrides() {
+    let defaults = ApiError::builder()
+        .title("Upstream failed")
+        .build()
+        .unwrap();
+    assert_eq!(defaults.title, "Upstream failed");
+    assert!(defaults.code.is_none());
+    assert!(defaults.history.is_empty());
+    assert_eq!(defaults.correlation_id.len(), 8);
+    assert_eq!(defaults.docs_url, config_report().to_api_error().docs_url);
+
+    let overridden = ApiError::builder()
+        .title("Upstream failed")
+        .git_hash("abc1234")
+        .docs_url("https://example.com/errors")
+        .correlation_id("req-1")
+        .build()
+        .unwrap();
+    assert_eq!(overridden.git_hash, "abc1234");
+    assert_eq!(overridden.docs_url, "https://example.com/errors");
+    assert_eq!(overridden.correlation_id, "req-1");
+}
+
+#[test]
+fn test_build_without_title_fails() {
+    let missing = ApiError::builder().code("billing::declined").build();
+    assert_eq!(missing, Err(ApiErrorBuilderError::MissingTitle));
+
+    let blank = ApiError::builder().title("  ").build();
+    assert_eq!(blank, Err(ApiErrorBuilderError::MissingTitle));
+}
+
+#[test]
+fn test_build_keeps_a_malformed_code_without_a_domain() {
+    let api_err = ApiError::builder()
+        .title("Upstream failed")
+        .code("declined")
+        .build()
+        .unwrap();
+    assert_eq!(api_err.code.as_deref(), Some("declined"));
+    assert!(api_err.domain.is_none());
+
+    let blank = ApiError::builder()
+        .title("Upstream failed")
+        .code(" ")
+        .build();
+    assert!(blank.unwrap().code.is_none());
+}
+
+#[test]
+fn test_report_with_a_bare_code_can_be_rebuilt() {
+    let original = legacy_report().to_api_error();
+    let rebuilt = ApiError::builder()
+        .title(original.title.clone())
+        .code(original.code.as_deref().unwrap())
+        .build()
+        .unwrap();
+    assert_eq!(rebuilt.code, original.code);
+    assert_eq!(rebuilt.domain, original.domain);
+}
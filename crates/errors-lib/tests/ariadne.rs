@@ -0,0 +1,86 @@
+/*
+ * Integration tests for LibReport::render_ariadne (`ariadne` feature).
+ */
+
+#![cfg(feature = "ariadne")]
+
+mod common;
+
+use common::{TestError, config_report};
+use errors_lib::{LibReport, rootcause::Report};
+use std::fmt;
+
+use miette::{Diagnostic, NamedSource, SourceSpan};
+
+#[derive(Debug, Diagnostic)]
+#[diagnostic(code(config::unexpected_token))]
+struct TokenError {
+    #[source_code]
+    src: NamedSource<String>,
+    #[label("here")]
+    span: SourceSpan,
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Unexpected token")
+    }
+}
+
+impl std::error::Error for TokenError {}
+
+#[test]
+fn test_ariadne_names_the_source_file() {
+    let out = config_report().render_ariadne(false);
+    assert!(out.contains("config.json"), "missing file name:\n{out}");
+}
+
+#[test]
+fn test_ariadne_shows_message_code_and_label() {
+    let out = config_report().render_ariadne(false);
+    assert!(out.contains("Failed to parse config at config.json"));
+    assert!(out.contains("config::invalid_format"));
+    assert!(out.contains("syntax error here"));
+    assert!(out.contains("Ensure the configuration file is valid JSON."));
+}
+
+#[test]
+fn test_ariadne_plain_output_has_no_ansi_codes() {
+    let out = config_report().render_ariadne(false);
+    assert!(!out.contains('\u{1b}'));
+}
+
+#[test]
+fn test_ariadne_without_source_still_renders() {
+    let report: LibReport<TestError> = Report::new(TestError::NetworkTimeout {
+        timeout: 30,
+    })
+    .into();
+    let out = report.render_ariadne(false);
+    assert!(out.contains("Network timeout after 30s"), "got:\n{out}");
+    assert!(out.contains("network::timeout"));
+}
+
+#[test]
+fn test_ariadne_places_labels_by_byte_offset() {
+    // `é` is two bytes, so byte and char offsets of `bad` differ.
+    let text = "ééé = bad";
+    let offset = text.find("bad").unwrap();
+    let report: LibReport<TokenError> = Report::new(TokenError {
+        src: NamedSource::new("config.toml", text.to_owned()),
+        span: (offset, 3).into(),
+    })
+    .into();
+    let out = report.render_ariadne(false);
+
+    let lines: Vec<&str> = out.lines().collect();
+    let row = lines
+        .iter()
+        .position(|line| line.ends_with(text))
+        .unwrap_or_else(|| panic!("source line missing:\n{out}"));
+    let column = |line: &str, found: usize| line[..found].chars().count();
+    let label_column = column(lines[row], lines[row].find("bad").unwrap());
+    let underline = lines[row + 1];
+    let underline_column = column(underline, underline.find('─').unwrap());
+    assert_eq!(underline_column, label_column, "misplaced label:\n{out}");
+}
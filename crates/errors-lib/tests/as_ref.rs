@@ -0,0 +1,37 @@
+/*
+ * Integration tests for borrowing a LibReport's context via AsRef/AsMut.
+ */
+
+mod common;
+
+use common::{TestError, config_report, two_level_report};
+use errors_lib::ReportExt;
+
+#[test]
+fn test_as_ref_matches_on_variant() {
+    let report = config_report();
+    let err_ref: &TestError = report.as_ref();
+    assert!(matches!(err_ref, TestError::ConfigParseError { .. }));
+}
+
+#[test]
+fn test_as_ref_works_on_cloneable_reports() {
+    let report = two_level_report().into_cloneable();
+    let err_ref: &TestError = report.as_ref();
+    assert!(matches!(err_ref, TestError::ConfigParseError { .. }));
+}
+
+#[test]
+fn test_as_mut_edits_the_context() {
+    let mut report = config_report();
+    if let TestError::ConfigParseError {
+        path, ..
+    } = report.as_mut()
+    {
+        *path = "settings.json".into();
+    }
+    assert_eq!(
+        report.to_api_error().title,
+        "Failed to parse config at settings.json"
+    );
+}
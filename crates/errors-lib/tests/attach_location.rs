@@ -0,0 +1,29 @@
+/*
+ * Integration tests for attach_location! and attach_source_location.
+ */
+
+mod common;
+
+use common::config_report;
+use errors_lib::{ReportExt, attach_location};
+
+#[test]
+fn test_attach_location_records_the_call_site() {
+    let (report, line) = (attach_location!(config_report()), line!());
+
+    let history = report.to_api_error().history;
+    let message = &history.last().unwrap().message;
+    let prefix = format!("at {}:{line}:", file!());
+    assert!(message.starts_with(&prefix), "{message}");
+    assert!(message.contains("tests/attach_location.rs"));
+
+    let column: u32 = message[prefix.len()..].parse().unwrap();
+    assert!(column > 1);
+}
+
+#[test]
+fn test_attach_source_location_formats_the_message() {
+    let report = config_report().attach_source_location("src/worker.rs", 42, 7);
+    let history = report.to_api_error().history;
+    assert_eq!(history.last().unwrap().message, "at src/worker.rs:42:7");
+}
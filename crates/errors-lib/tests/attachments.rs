@@ -0,0 +1,68 @@
+/*
+ * Integration tests for typed attachments on LibReport.
+ */
+
+mod common;
+
+use common::{config_report, two_level_report};
+use errors_lib::ReportExt;
+
+/// Structured request data a service wants back when building a response.
+#[derive(Debug, PartialEq, Eq)]
+struct HttpRequestInfo {
+    method: &'static str,
+    path: &'static str,
+    status: u16,
+}
+
+const fn request_info() -> HttpRequestInfo {
+    HttpRequestInfo {
+        method: "GET",
+        path: "/config",
+        status: 502,
+    }
+}
+
+#[test]
+fn test_typed_attachment_round_trips() {
+    let report = config_report().attach_typed(request_info());
+    assert_eq!(
+        report.get_attachment::<HttpRequestInfo>(),
+        Some(&request_info())
+    );
+    assert!(report.get_attachment::<u64>().is_none());
+}
+
+#[test]
+fn test_iter_attachments_walks_children() {
+    let report = two_level_report().attach("retrying once");
+    let messages: Vec<&&str> = report.iter_attachments::<&str>().collect();
+    assert_eq!(messages, [
+        &"retrying once",
+        &"while fetching remote config"
+    ]);
+}
+
+#[test]
+fn test_typed_attachment_stays_out_of_history() {
+    let plain = config_report().to_api_error();
+    let typed = config_report().attach_typed(request_info()).to_api_error();
+    assert_eq!(typed.history.len(), plain.history.len());
+    assert!(
+        typed
+            .history
+            .iter()
+            .all(|frame| !frame.message.is_empty() && !frame.message.contains("HttpRequestInfo"))
+    );
+}
+
+#[test]
+fn test_display_attachment_appears_in_history() {
+    let api_err = config_report().attach("request id 42").to_api_error();
+    assert!(
+        api_err
+            .history
+            .iter()
+            .any(|frame| frame.message == "request id 42")
+    );
+}
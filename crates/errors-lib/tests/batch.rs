@@ -0,0 +1,54 @@
+/*
+ * Integration tests for BatchReportExt and fixed correlation IDs.
+ */
+
+mod common;
+
+use common::{config_report, io_report, two_level_report};
+use errors_lib::{ApiErrorOptions, BatchReportExt, CorrelationId, ReportExt};
+
+#[test]
+fn test_batch_shares_a_prefix_and_numbers_the_rest() {
+    let reports = [
+        config_report(),
+        two_level_report(),
+        io_report(std::io::ErrorKind::NotFound),
+    ];
+    let errors = reports.batch_to_api_errors();
+
+    assert_eq!(errors.len(), 3);
+    let ids: Vec<_> = errors.iter().map(|e| e.correlation_id.as_str()).collect();
+    let prefix = &ids[0][..6];
+    for (index, id) in ids.iter().enumerate() {
+        assert_eq!(id.len(), 8, "{id}");
+        assert_eq!(&id[..6], prefix, "{ids:?}");
+        assert_eq!(id[6..], format!("{index:02}"), "{ids:?}");
+    }
+    assert_eq!(errors[1].title, "Failed to parse config at config.json");
+    assert_eq!(errors[2].code.as_deref(), Some("io::error"));
+}
+
+#[test]
+fn test_batches_draw_a_new_prefix_per_hundred() {
+    let reports: Vec<_> = (0..101).map(|_| config_report()).collect();
+    let errors = reports.batch_to_api_errors();
+
+    assert!(errors[99].correlation_id.ends_with("99"));
+    assert!(errors[100].correlation_id.ends_with("00"));
+    assert_eq!(
+        errors[0].correlation_id[..6],
+        errors[99].correlation_id[..6]
+    );
+}
+
+#[test]
+fn test_options_can_fix_the_correlation_id() {
+    let id = CorrelationId::new("req00042").unwrap();
+    let api_err = config_report()
+        .into_dynamic()
+        .to_api_error_with_options(ApiErrorOptions::default().correlation_id(id));
+    assert_eq!(api_err.correlation_id, "req00042");
+
+    assert!(CorrelationId::new("short").is_none());
+    assert!(CorrelationId::new("abécdef").is_none());
+}
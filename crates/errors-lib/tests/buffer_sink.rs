@@ -0,0 +1,78 @@
+/*
+ * Integration tests for BufferSink, ReportExt::emit and sink isolation.
+ *
+ * The sink registry is process-wide, so everything runs in one test.
+ */
+
+mod common;
+
+use std::thread;
+
+use common::{config_report, two_level_report};
+use errors_lib::{ApiError, BufferSink, ErrorSink, ErrorSinkRegistry, ReportExt};
+
+struct Panics;
+
+impl ErrorSink for Panics {
+    fn emit(&self, _: &ApiError) {
+        panic!("sink failure");
+    }
+}
+
+#[test]
+fn test_buffer_sink_and_registry() {
+    ErrorSinkRegistry::clear();
+
+    // The buffer keeps the newest errors, oldest first.
+    let recent = BufferSink::new(2);
+    ErrorSinkRegistry::register(recent.clone());
+    let ids: Vec<_> = (0..3)
+        .map(|_| config_report().to_api_error().correlation_id)
+        .collect();
+    let kept: Vec<_> = recent
+        .errors()
+        .into_iter()
+        .map(|e| e.correlation_id)
+        .collect();
+    assert_eq!(kept, ids[1..]);
+
+    // emit() builds and dispatches without handing back the ApiError.
+    recent.clear();
+    two_level_report().emit();
+    assert_eq!(recent.len(), 1);
+    assert_eq!(
+        recent.errors()[0].code.as_deref(),
+        Some("config::invalid_format")
+    );
+
+    // A panicking sink doesn't stop the ones after it; boxed sinks work.
+    ErrorSinkRegistry::clear();
+    let after = BufferSink::new(8);
+    ErrorSinkRegistry::register(Box::new(Panics) as Box<dyn ErrorSink>);
+    ErrorSinkRegistry::register(Box::new(after.clone()) as Box<dyn ErrorSink>);
+    config_report().emit();
+    config_report().emit();
+    assert_eq!(after.len(), 2);
+
+    // Hammer the registry: emit from many threads while others register.
+    ErrorSinkRegistry::clear();
+    let shared = BufferSink::new(10_000);
+    ErrorSinkRegistry::register(shared.clone());
+    thread::scope(|scope| {
+        for i in 0..8 {
+            scope.spawn(move || {
+                if i % 2 == 0 {
+                    ErrorSinkRegistry::register(BufferSink::new(1));
+                }
+                for _ in 0..25 {
+                    config_report().emit();
+                }
+            });
+        }
+    });
+    assert_eq!(shared.len(), 8 * 25);
+
+    let empty = BufferSink::new(0);
+    empty.emit(&config_report().to_api_error());
+    assert!(empty.is_empty());
+}
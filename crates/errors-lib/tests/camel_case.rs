@@ -0,0 +1,46 @@
+/*
+ * Integration tests for serializing ApiError with camelCase keys.
+ */
+
+mod common;
+
+use common::config_report;
+use errors_lib::{ApiErrorOptions, CamelCase, ReportExt};
+
+#[test]
+fn test_keys_are_camel_case() {
+    let api_err = config_report().to_api_error();
+    let json = serde_json::to_value(CamelCase(&api_err)).unwrap();
+
+    assert_eq!(json["correlationId"], api_err.correlation_id.as_str());
+    assert_eq!(json["gitHash"], api_err.git_hash.as_ref());
+    assert!(json.get("docsUrl").is_some());
+    assert!(json.get("correlation_id").is_none());
+    assert!(json.get("git_hash").is_none());
+}
+
+#[test]
+fn test_empty_optionals_are_still_skipped() {
+    let json = serde_json::to_value(CamelCase(&config_report().to_api_error())).unwrap();
+    assert!(json.get("retryAfterMs").is_none());
+    assert!(json.get("spanTrace").is_none());
+    assert!(json.get("tree").is_none());
+}
+
+#[test]
+fn test_same_values_as_the_default_format() {
+    let api_err = config_report()
+        .attach_field("user", "42")
+        .to_api_error_with_options(ApiErrorOptions::default().include_tree(true));
+    let snake = serde_json::to_value(&api_err).unwrap();
+    let camel = serde_json::to_value(CamelCase(&api_err)).unwrap();
+
+    assert_eq!(
+        camel.as_object().unwrap().len(),
+        snake.as_object().unwrap().len()
+    );
+    assert_eq!(camel["history"], snake["history"]);
+    assert_eq!(camel["fields"], snake["fields"]);
+    assert_eq!(camel["tree"], snake["tree"]);
+    assert_eq!(camel["threadId"], snake["thread_id"]);
+}
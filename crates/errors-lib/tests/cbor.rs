@@ -0,0 +1,42 @@
+/*
+ * Integration tests for ApiError CBOR encoding (`cbor` feature).
+ */
+
+#![cfg(feature = "cbor")]
+
+mod common;
+
+use common::{config_report, two_level_report};
+use errors_lib::{ApiError, ApiErrorOptions, ReportExt};
+
+#[test]
+fn test_cbor_round_trips() {
+    let api_err = two_level_report()
+        .attach_field("user", "42")
+        .to_api_error_with_options(ApiErrorOptions::default().include_tree(true));
+    let decoded = ApiError::from_cbor(&api_err.to_cbor()).expect("valid CBOR");
+    assert_eq!(decoded, api_err);
+}
+
+#[test]
+fn test_cbor_history_matches_json() {
+    let api_err = config_report().to_api_error();
+    let from_cbor: serde_json::Value =
+        ciborium::from_reader(api_err.to_cbor().as_slice()).expect("valid CBOR");
+    let from_json = serde_json::to_value(&api_err).expect("serializable");
+    assert_eq!(from_cbor["history"], from_json["history"]);
+    assert!(from_cbor["history"][0].is_string());
+    assert_eq!(from_cbor, from_json);
+}
+
+#[test]
+fn test_cbor_is_smaller_than_json() {
+    let api_err = two_level_report().to_api_error();
+    let json = serde_json::to_vec(&api_err).expect("serializable");
+    assert!(api_err.to_cbor().len() < json.len());
+}
+
+#[test]
+fn test_garbage_is_rejected() {
+    assert!(ApiError::from_cbor(b"not cbor").is_err());
+}
@@ -0,0 +1,175 @@
+/*
+ * Integration tests for classify_error / ErrorAction / handle_error_logic.
+ */
+
+mod common;
+
+use std::{io, path::PathBuf, time::Duration};
+
+use common::{TestError, config_parse_error, config_report, io_report, three_level_report};
+use errors_lib::{
+    ActionHandler, DefaultActionHandler, ErrorAction, HandleOutcome, LibReport, classify_error,
+    handle_error_logic, rootcause::Report,
+};
+
+#[test]
+fn test_not_found_is_a_missing_file() {
+    let report = io_report(io::ErrorKind::NotFound);
+    assert_eq!(classify_error(&report), [ErrorAction::MissingFile {
+        path: None
+    }]);
+}
+
+#[test]
+fn test_missing_file_path_comes_from_a_typed_attachment() {
+    let report = io_report(io::ErrorKind::NotFound).attach_typed(PathBuf::from("config.json"));
+    assert_eq!(classify_error(&report), [ErrorAction::MissingFile {
+        path: Some(PathBuf::from("config.json"))
+    }]);
+}
+
+#[test]
+fn test_permission_denied_below_a_typed_parent() {
+    let report: LibReport<TestError> =
+        Report::new(io::Error::new(io::ErrorKind::PermissionDenied, "denied"))
+            .context(config_parse_error())
+            .into();
+    assert_eq!(classify_error(&report), [ErrorAction::PermissionDenied]);
+}
+
+#[test]
+fn test_timeout_seconds_come_from_a_typed_duration() {
+    assert_eq!(classify_error(&three_level_report()), [
+        ErrorAction::NetworkTimeout {
+            seconds: None
+        }
+    ]);
+
+    let report = io_report(io::ErrorKind::TimedOut).attach_typed(Duration::from_secs(30));
+    assert_eq!(classify_error(&report), [ErrorAction::NetworkTimeout {
+        seconds: Some(30)
+    }]);
+}
+
+#[test]
+fn test_io_variant_below_a_parent_is_a_missing_file() {
+    let report = io_report(io::ErrorKind::NotFound).change_context(config_parse_error());
+    assert_eq!(classify_error(&report), [ErrorAction::MissingFile {
+        path: None
+    }]);
+}
+
+#[test]
+fn test_io_error_in_a_child_node_is_a_missing_file() {
+    let report: LibReport<TestError> =
+        Report::new(io::Error::new(io::ErrorKind::NotFound, "no such file"))
+            .context(config_parse_error())
+            .into();
+
+    assert_eq!(classify_error(&report), [ErrorAction::MissingFile {
+        path: None
+    }]);
+    assert_eq!(
+        handle_error_logic(&report, &DefaultActionHandler),
+        (
+            ErrorAction::MissingFile {
+                path: None
+            },
+            HandleOutcome::Handled
+        )
+    );
+}
+
+#[test]
+fn test_io_error_attachment_is_recognized() {
+    let report = config_report().attach_typed(io::Error::from(io::ErrorKind::PermissionDenied));
+    assert_eq!(classify_error(&report), [ErrorAction::PermissionDenied]);
+}
+
+#[test]
+fn test_unrecognized_chain_is_unknown() {
+    assert_eq!(classify_error(&config_report()), [ErrorAction::Unknown]);
+    assert_eq!(classify_error(&io_report(io::ErrorKind::BrokenPipe)), [
+        ErrorAction::Unknown
+    ]);
+}
+
+/// A handler relying on the trait's default.
+struct Ignore;
+
+impl ActionHandler for Ignore {}
+
+#[test]
+fn test_only_actions_the_handler_deals_with_are_handled() {
+    let missing_file = ErrorAction::MissingFile {
+        path: None,
+    };
+    assert_eq!(
+        handle_error_logic(&io_report(io::ErrorKind::NotFound), &DefaultActionHandler),
+        (missing_file.clone(), HandleOutcome::Handled)
+    );
+    let timeout: LibReport<TestError> = Report::new(TestError::NetworkTimeout {
+        timeout: 30,
+    })
+    .into();
+    assert_eq!(
+        handle_error_logic(&timeout, &DefaultActionHandler),
+        (ErrorAction::Unknown, HandleOutcome::Unhandled)
+    );
+    let timed_out = io_report(io::ErrorKind::TimedOut);
+    assert_eq!(
+        handle_error_logic(&timed_out, &DefaultActionHandler),
+        (
+            ErrorAction::NetworkTimeout {
+                seconds: None
+            },
+            HandleOutcome::Unhandled
+        )
+    );
+
+    // The trait's default leaves everything unhandled.
+    assert_eq!(
+        handle_error_logic(&io_report(io::ErrorKind::NotFound), &Ignore),
+        (missing_file, HandleOutcome::Unhandled)
+    );
+}
+
+#[test]
+fn test_first_dealt_with_action_in_a_chain_wins() {
+    let retry_timeouts = |action: &ErrorAction| match action {
+        ErrorAction::NetworkTimeout {
+            seconds,
+        } => HandleOutcome::Retry(Duration::from_secs(seconds.unwrap_or(5))),
+        _ => HandleOutcome::Unhandled,
+    };
+
+    // config error -> network timeout -> io TimedOut
+    let timeout = ErrorAction::NetworkTimeout {
+        seconds: None,
+    };
+    assert_eq!(
+        handle_error_logic(&three_level_report(), &retry_timeouts),
+        (timeout.clone(), HandleOutcome::Retry(Duration::from_secs(5)))
+    );
+
+    // A missing file above a timeout: the default handler deals with the
+    // file first, the retry handler skips it and finds the timeout.
+    let report: LibReport<TestError> =
+        Report::new(io::Error::new(io::ErrorKind::TimedOut, "timed out"))
+            .context(io::Error::new(io::ErrorKind::NotFound, "missing"))
+            .context(config_parse_error())
+            .into();
+    assert_eq!(
+        handle_error_logic(&report, &DefaultActionHandler),
+        (
+            ErrorAction::MissingFile {
+                path: None
+            },
+            HandleOutcome::Handled
+        )
+    );
+    assert_eq!(
+        handle_error_logic(&report, &retry_timeouts),
+        (timeout, HandleOutcome::Retry(Duration::from_secs(5)))
+    );
+}
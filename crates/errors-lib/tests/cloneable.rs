@@ -0,0 +1,52 @@
+/*
+ * Integration tests for ClonableLibReport.
+ *
+ * Fan-out pattern: one clone is logged on a worker thread while the
+ * original is returned to the caller.
+ */
+
+mod common;
+
+use std::thread;
+
+use common::{TestError, two_level_report};
+use errors_lib::{ClonableLibReport, ReportExt};
+use miette::Diagnostic;
+
+fn shared_report() -> ClonableLibReport<TestError> {
+    two_level_report().into_cloneable()
+}
+
+#[test]
+fn test_clones_share_title_and_history() {
+    let report = shared_report();
+    let clone = report.clone();
+
+    let logged = thread::spawn(move || clone.to_api_error())
+        .join()
+        .expect("logging thread panicked");
+    let returned = report.to_api_error();
+
+    assert_eq!(logged.title, returned.title);
+    assert_eq!(
+        serde_json::to_value(&logged.history).unwrap(),
+        serde_json::to_value(&returned.history).unwrap()
+    );
+    assert_ne!(logged.correlation_id, returned.correlation_id);
+}
+
+#[test]
+fn test_clone_is_a_drop_in_diagnostic() {
+    let report = shared_report();
+    let clone = report.clone();
+
+    assert_eq!(
+        clone.code().map(|c| c.to_string()).as_deref(),
+        Some("config::invalid_format")
+    );
+    assert_eq!(
+        clone.related().map(Iterator::count),
+        report.related().map(Iterator::count)
+    );
+    assert_eq!(clone.render_pretty(false), report.render_pretty(false));
+}
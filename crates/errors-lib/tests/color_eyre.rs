@@ -0,0 +1,50 @@
+/*
+ * Integration tests for color-eyre interop (`color-eyre` feature).
+ */
+
+#![cfg(feature = "color-eyre")]
+
+mod common;
+
+use common::{config_report, two_level_report};
+use errors_lib::ApiError;
+
+#[test]
+fn test_eyre_debug_contains_original_message() {
+    let report: color_eyre::Report = config_report().into();
+    let debug = format!("{report:?}");
+    assert!(
+        debug.contains("Failed to parse config at config.json"),
+        "eyre output lost the original message:\n{debug}"
+    );
+}
+
+#[test]
+fn test_eyre_chain_reaches_child_reports() {
+    let report: color_eyre::Report = two_level_report().into();
+    let causes: Vec<String> = report.chain().skip(1).map(ToString::to_string).collect();
+    assert!(
+        causes
+            .iter()
+            .any(|cause| cause.contains("Network timeout after 30s"))
+    );
+}
+
+#[test]
+fn test_try_from_eyre_extracts_title() {
+    let report: color_eyre::Report = two_level_report().into();
+    let api_err = ApiError::try_from(report).expect("report has a message");
+    assert!(
+        api_err
+            .title
+            .contains("Failed to parse config at config.json")
+    );
+    assert!(api_err.code.is_none());
+    assert!(!api_err.history.is_empty());
+}
+
+#[test]
+fn test_try_from_eyre_rejects_empty_message() {
+    let report = color_eyre::eyre::eyre!("");
+    assert!(ApiError::try_from(report).is_err());
+}
@@ -0,0 +1,127 @@
+/*
+ * Shared fixtures for the errors-lib integration tests.
+ *
+ * A minimal error type that mirrors what a consuming crate would define,
+ * plus builders for the report shapes the tests exercise.
+ */
+
+#![allow(dead_code)]
+
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use errors_lib::{LibReport, rootcause::Report};
+use miette::{Diagnostic, NamedSource, SourceSpan};
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu, Diagnostic)]
+pub enum TestError {
+    #[snafu(display("Failed to parse config at {path}"))]
+    #[diagnostic(
+        code(config::invalid_format),
+        help("Ensure the configuration file is valid JSON.")
+    )]
+    ConfigParseError {
+        path: String,
+        #[source_code]
+        src: NamedSource<String>,
+        #[label("syntax error here")]
+        span: SourceSpan,
+    },
+
+    #[snafu(display("Network timeout after {timeout}s"))]
+    #[diagnostic(
+        code(network::timeout),
+        help("Check network connectivity and consider increasing the timeout.")
+    )]
+    NetworkTimeout { timeout: u64 },
+
+    #[snafu(context(false))]
+    #[snafu(display("IO error: {source}"))]
+    #[diagnostic(code(io::error))]
+    Io { source: std::io::Error },
+}
+
+pub fn config_parse_error() -> TestError {
+    TestError::ConfigParseError {
+        path: "config.json".into(),
+        src: NamedSource::new("config.json", "{ \"key\": !!invalid }".to_string()),
+        span: (10, 9).into(),
+    }
+}
+
+/// Single-node report: a config parse error with one attachment.
+pub fn config_report() -> LibReport<TestError> {
+    Report::new(config_parse_error())
+        .attach("The application cannot proceed without a valid config.")
+        .into()
+}
+
+/// Two-level tree: a config parse error caused by a network timeout child.
+pub fn two_level_report() -> LibReport<TestError> {
+    Report::new(TestError::NetworkTimeout {
+        timeout: 30,
+    })
+    .attach("while fetching remote config")
+    .context(config_parse_error())
+    .into()
+}
+
+/// A config parse error with two sibling causes: a network timeout, then an
+/// io error.
+pub fn two_child_report() -> LibReport<TestError> {
+    let mut report = Report::new(config_parse_error());
+    for child in [
+        Report::new(TestError::NetworkTimeout {
+            timeout: 30,
+        })
+        .into_dynamic(),
+        Report::new(io::Error::new(io::ErrorKind::TimedOut, "connection reset")).into_dynamic(),
+    ] {
+        report.children_mut().push(child.into_cloneable());
+    }
+    report.into()
+}
+
+/// Single-node report whose context wraps an `io::Error` source.
+pub fn io_report(kind: std::io::ErrorKind) -> LibReport<TestError> {
+    let err: TestError = std::io::Error::new(kind, "config.json is unavailable").into();
+    Report::new(err).into()
+}
+
+/// Three-level tree: config error -> network timeout -> io error.
+pub fn three_level_report() -> LibReport<TestError> {
+    Report::new(std::io::Error::new(
+        std::io::ErrorKind::TimedOut,
+        "connection reset",
+    ))
+    .context(TestError::NetworkTimeout {
+        timeout: 30,
+    })
+    .context(config_parse_error())
+    .into()
+}
+
+/// An in-memory log sink: clones share one buffer, so a test can hand one
+/// to `init_logging_with_writer` and read the output back from another.
+#[derive(Clone, Default)]
+pub struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl LogBuffer {
+    pub fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl io::Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
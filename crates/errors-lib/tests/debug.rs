@@ -0,0 +1,34 @@
+/*
+ * Integration tests for LibReport's miette-backed Debug output.
+ */
+
+mod common;
+
+use common::{config_report, two_level_report};
+
+#[test]
+fn test_debug_reads_like_the_rendered_report() {
+    let debug = format!("{:?}", config_report());
+
+    assert!(
+        debug.contains("Failed to parse config at config.json"),
+        "Debug output lost the title:\n{debug}"
+    );
+    assert!(!debug.contains("LibReport("), "{debug}");
+    assert!(!debug.contains("Report {"), "{debug}");
+}
+
+#[test]
+fn test_debug_includes_the_cause() {
+    let debug = format!("{:?}", two_level_report());
+
+    assert!(debug.contains("Failed to parse config at config.json"));
+    assert!(debug.contains("Network timeout after 30s"), "{debug}");
+}
+
+#[test]
+fn test_alternate_debug_keeps_the_raw_layout() {
+    let debug = format!("{:#?}", config_report());
+
+    assert!(debug.starts_with("LibReport("), "{debug}");
+}
@@ -0,0 +1,252 @@
+/*
+ * Integration tests for the miette Diagnostic impl on LibReport.
+ *
+ * Covers how the rootcause tree is exposed to miette's renderers.
+ */
+
+mod common;
+
+use std::io::ErrorKind;
+
+use common::{
+    TestError, config_parse_error, config_report, io_report, two_child_report, two_level_report,
+};
+use errors_lib::{LibReport, rootcause::Report};
+use miette::{Diagnostic, GraphicalReportHandler, GraphicalTheme, LabeledSpan, NamedSource};
+
+#[test]
+fn test_related_exposes_children_after_the_source() {
+    let report = two_child_report();
+
+    let source = report.diagnostic_source().expect("source missing");
+    assert_eq!(source.to_string(), "Network timeout after 30s");
+    // The child delegates to its own context, not the parent's
+    let code = source.code().expect("child code missing");
+    assert_eq!(code.to_string(), "network::timeout");
+
+    let related: Vec<&dyn Diagnostic> = report.related().expect("children missing").collect();
+    assert_eq!(related.len(), 1);
+    assert_eq!(related[0].to_string(), "connection reset");
+}
+
+#[test]
+fn test_related_is_none_without_children() {
+    assert!(config_report().related().is_none());
+}
+
+#[test]
+fn test_single_child_is_only_the_source() {
+    assert!(two_level_report().related().is_none());
+}
+
+#[test]
+fn test_render_includes_child_reports() {
+    let report = two_level_report();
+
+    let mut rendered = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .render_report(&mut rendered, &report)
+        .unwrap();
+
+    assert!(rendered.contains("Network timeout after 30s"));
+}
+
+#[test]
+fn test_render_shows_each_child_once() {
+    let rendered = two_child_report().render_pretty(false);
+
+    // Each child is rendered as a diagnostic once: the first as the cause,
+    // the second as related. The headline lists the whole tree as well.
+    assert_eq!(
+        rendered.matches("× Network timeout after 30s").count(),
+        1,
+        "{rendered}"
+    );
+    assert_eq!(
+        rendered.matches("× connection reset").count(),
+        1,
+        "{rendered}"
+    );
+}
+
+#[test]
+fn test_snapshot_two_child_render() {
+    let report = two_child_report();
+    let handler = GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .with_links(false)
+        .without_syntax_highlighting()
+        .with_width(80);
+
+    let mut rendered = String::new();
+    handler.render_report(&mut rendered, &report).unwrap();
+
+    insta::assert_snapshot!(rendered);
+}
+
+#[test]
+fn test_render_pretty_includes_source_and_labels() {
+    let rendered = config_report().render_pretty(false);
+
+    assert!(rendered.contains("syntax error here"));
+    assert!(rendered.contains("config.json"));
+    assert!(rendered.contains("{ \"key\": !!invalid }"));
+    assert!(
+        !rendered.contains('\x1b'),
+        "plain render must not contain ANSI escapes"
+    );
+}
+
+#[test]
+fn test_diagnostic_source_is_first_child() {
+    let report = two_level_report();
+
+    let source = report.diagnostic_source().expect("source missing");
+    assert_eq!(source.to_string(), "Network timeout after 30s");
+}
+
+#[test]
+fn test_diagnostic_source_falls_back_to_context_source() {
+    let report = io_report(ErrorKind::NotFound);
+
+    let source = report.diagnostic_source().expect("source missing");
+    assert_eq!(source.to_string(), "config.json is unavailable");
+    assert!(source.diagnostic_source().is_none());
+}
+
+#[test]
+fn test_diagnostic_source_is_none_for_leaf() {
+    assert!(config_report().diagnostic_source().is_none());
+}
+
+#[test]
+fn test_render_shows_cause_line() {
+    let rendered = io_report(ErrorKind::NotFound).render_pretty(false);
+
+    let cause = rendered
+        .lines()
+        .find(|line| line.contains("╰─▶"))
+        .expect("cause line missing");
+    assert!(cause.contains("config.json is unavailable"));
+}
+
+/// The whole text `source_code()` holds, up to 64 bytes.
+fn source_text(diagnostic: &dyn Diagnostic) -> Option<String> {
+    let source = diagnostic.source_code()?;
+    let len = (1..=64)
+        .take_while(|&len| source.read_span(&(0, len).into(), 0, 0).is_ok())
+        .last()?;
+    let contents = source.read_span(&(0, len).into(), 0, 0).ok()?;
+    Some(String::from_utf8_lossy(contents.data()).into_owned())
+}
+
+const FIXTURE_SOURCE: &str = "{ \"key\": !!invalid }";
+
+#[test]
+fn test_source_code_comes_from_a_child_when_the_top_has_none() {
+    let report: LibReport<TestError> = Report::new(config_parse_error())
+        .context(TestError::NetworkTimeout {
+            timeout: 30,
+        })
+        .into();
+
+    assert_eq!(source_text(&report).as_deref(), Some(FIXTURE_SOURCE));
+    assert_eq!(
+        source_text(&report.into_dynamic()).as_deref(),
+        Some(FIXTURE_SOURCE)
+    );
+}
+
+#[test]
+fn test_source_code_prefers_the_top_and_searches_grandchildren() {
+    let top = TestError::ConfigParseError {
+        path: "other.json".into(),
+        src: NamedSource::new("other.json", "{}".to_string()),
+        span: (0, 1).into(),
+    };
+    let report: LibReport<TestError> = Report::new(config_parse_error()).context(top).into();
+    assert_eq!(source_text(&report).as_deref(), Some("{}"));
+
+    let report: LibReport<TestError> = Report::new(config_parse_error())
+        .context(TestError::NetworkTimeout {
+            timeout: 30,
+        })
+        .context(TestError::NetworkTimeout {
+            timeout: 60,
+        })
+        .into();
+    assert_eq!(source_text(&report).as_deref(), Some(FIXTURE_SOURCE));
+
+    assert!(io_report(ErrorKind::NotFound).source_code().is_none());
+}
+
+/// A parse error pointing at several places at once, as `CliError`'s
+/// `ConfigParseError` does.
+#[derive(Debug, snafu::Snafu, Diagnostic)]
+#[snafu(display("Failed to parse {path}"))]
+struct MultiLabelError {
+    path: String,
+    #[source_code]
+    src: NamedSource<String>,
+    #[label(collection)]
+    labels: Vec<LabeledSpan>,
+}
+
+#[test]
+fn test_labels_yields_every_labelled_span() {
+    let err = MultiLabelError {
+        path: "config.json".into(),
+        src: NamedSource::new("config.json", "{ \"key\": !!invalid".to_string()),
+        labels: vec![
+            LabeledSpan::new_with_span(Some("object opened here is never closed".into()), (0, 1)),
+            LabeledSpan::new_with_span(Some("unexpected token".into()), (9, 9)),
+        ],
+    };
+    let report: LibReport<MultiLabelError> = Report::new(err).into();
+
+    assert_eq!(report.labels().unwrap().count(), 2);
+
+    let mut rendered = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .render_report(&mut rendered, &report)
+        .unwrap();
+    assert!(rendered.contains("object opened here is never closed"));
+    assert!(rendered.contains("unexpected token"));
+}
+
+#[test]
+fn test_a_directly_wrapped_report_still_shows_typed_descendants() {
+    let report = LibReport(
+        Report::new(TestError::NetworkTimeout {
+            timeout: 30,
+        })
+        .context(config_parse_error()),
+    );
+
+    let source = report.diagnostic_source().expect("source missing");
+    assert_eq!(source.to_string(), "Network timeout after 30s");
+    assert!(report.related().is_none());
+    assert!(std::error::Error::source(&report).is_some());
+    assert!(
+        report
+            .render_pretty(false)
+            .contains("Network timeout after 30s")
+    );
+}
+
+#[test]
+fn test_children_added_after_rendering_are_shown() {
+    let mut report = two_level_report();
+    assert!(report.related().is_none());
+
+    report.0.children_mut().push(
+        Report::new(TestError::NetworkTimeout {
+            timeout: 60,
+        })
+        .into_dynamic()
+        .into_cloneable(),
+    );
+
+    let related: Vec<&dyn Diagnostic> = report.related().expect("new child missing").collect();
+    assert_eq!(related.len(), 1);
+    assert_eq!(related[0].to_string(), "Network timeout after 60s");
+}
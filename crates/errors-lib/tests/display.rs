@@ -0,0 +1,37 @@
+/*
+ * Integration tests for the alternate (`{:#}`) Display of reports.
+ */
+
+mod common;
+
+use common::{config_report, two_level_report};
+
+#[test]
+fn test_alternate_display_lists_attachments_in_order() {
+    let report = config_report().attach("first note").attach("second note");
+    let rendered = format!("{report:#}");
+    let mut lines = rendered.lines();
+
+    assert_eq!(lines.next(), Some("Failed to parse config at config.json"));
+    let rest: Vec<_> = lines.collect();
+    assert!(rest.iter().all(|line| line.starts_with("  ")));
+    let first = rest.iter().position(|line| line.trim() == "first note");
+    let second = rest.iter().position(|line| line.trim() == "second note");
+    assert!(first.is_some() && second.is_some());
+    assert!(first < second);
+}
+
+#[test]
+fn test_alternate_display_includes_causes() {
+    let rendered = format!("{:#}", two_level_report().into_dynamic());
+    let lines: Vec<_> = rendered.lines().map(str::trim).collect();
+
+    assert!(lines.contains(&"while fetching remote config"));
+    assert!(lines.len() > 2);
+}
+
+#[test]
+fn test_plain_display_is_unchanged() {
+    let report = config_report();
+    assert_eq!(format!("{report}"), report.0.to_string());
+}
@@ -0,0 +1,32 @@
+/*
+ * Integration test for the runtime docs URL override.
+ *
+ * Kept in its own test binary, as a single test: the override is
+ * process-wide, and would race the default-link assertions elsewhere.
+ */
+
+mod common;
+
+use common::config_report;
+use errors_lib::{ReportExt, clear_docs_url_override, docs_base_url, set_docs_url_override};
+use miette::Diagnostic;
+
+#[test]
+fn test_override_replaces_the_compiled_in_docs_url() {
+    let default = docs_base_url();
+    assert_eq!(default, env!("ERROR_DOCS_URL"));
+
+    set_docs_url_override("https://docs.example.com/errors");
+
+    let api_err = config_report().to_api_error();
+    assert_eq!(api_err.docs_url, "https://docs.example.com/errors");
+    let url = config_report().url().map(|u| u.to_string());
+    assert_eq!(
+        url.as_deref(),
+        Some("https://docs.example.com/errors/#config::invalid_format")
+    );
+
+    clear_docs_url_override();
+    assert_eq!(docs_base_url(), default);
+    assert_eq!(config_report().to_api_error().docs_url, default);
+}
@@ -0,0 +1,121 @@
+/*
+ * Integration tests for DynLibReport, the untyped LibReport.
+ *
+ * Chains here mix an io::Error root with a typed TestError parent, the
+ * case LibReport<E> cannot express without picking a top-level type.
+ */
+
+mod common;
+
+use std::{error::Error, io};
+
+use common::{TestError, config_parse_error};
+use errors_lib::{DynLibReport, DynLibResult, LibReport, ReportExt, rootcause::Report};
+use miette::{Diagnostic, MietteDiagnostic};
+use snafu::Snafu;
+
+#[derive(Debug, Snafu, Diagnostic)]
+#[snafu(display("Disk full on {volume}"))]
+#[diagnostic(code(storage::full), help("Free some space."))]
+struct DiskFull {
+    volume: String,
+}
+
+fn io_error() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "config.json not found")
+}
+
+/// Typed parent over an untyped `io::Error` root, erased after the fact.
+fn typed_over_io() -> DynLibReport {
+    let report: LibReport<TestError> = Report::new(io_error())
+        .attach("while opening config")
+        .context(config_parse_error())
+        .into();
+    report.into_dynamic()
+}
+
+fn load() -> DynLibResult<()> {
+    Err(Report::new(io_error()).into_dynamic().into())
+}
+
+#[test]
+fn test_into_dynamic_keeps_typed_diagnostics() {
+    let report = typed_over_io();
+    assert_eq!(
+        report.code().map(|c| c.to_string()).as_deref(),
+        Some("config::invalid_format")
+    );
+    assert!(report.help().is_some());
+    assert!(report.labels().is_some());
+}
+
+#[test]
+fn test_untyped_report_degrades_to_display() {
+    let report = load().unwrap_err();
+    assert!(report.code().is_none());
+    assert!(report.help().is_none());
+    assert!(report.url().is_none());
+    assert!(report.to_string().contains("config.json not found"));
+}
+
+#[test]
+fn test_io_child_is_the_source_not_related() {
+    let report = typed_over_io();
+    assert!(report.related().is_none());
+    let diagnostic_source = report
+        .diagnostic_source()
+        .expect("io child should be the source");
+    assert_eq!(diagnostic_source.to_string(), "config.json not found");
+
+    let source = report.source().expect("io child should be the source");
+    assert_eq!(source.to_string(), "config.json not found");
+}
+
+#[test]
+fn test_to_api_error_from_typed_parent() {
+    let api_err = typed_over_io().to_api_error();
+    assert_eq!(api_err.title, "Failed to parse config at config.json");
+    assert_eq!(api_err.code.as_deref(), Some("config::invalid_format"));
+    assert!(api_err.help.is_some());
+    assert!(
+        api_err
+            .history
+            .iter()
+            .any(|frame| frame.message == "while opening config")
+    );
+}
+
+#[test]
+fn test_to_api_error_from_untyped_root() {
+    let api_err = load().unwrap_err().to_api_error();
+    assert_eq!(api_err.title, "config.json not found");
+    assert!(api_err.code.is_none());
+    assert!(api_err.help.is_none());
+}
+
+#[test]
+fn test_from_error_queues_mixed_types() {
+    let queue: Vec<DynLibReport> = vec![
+        DynLibReport::from_error(config_parse_error()),
+        DynLibReport::from_error(DiskFull {
+            volume: "/var".into(),
+        }),
+        DynLibReport::from_error(MietteDiagnostic::new("Rate limited").with_code("api::throttled")),
+    ];
+
+    let api_errors: Vec<_> = queue.iter().map(ReportExt::to_api_error).collect();
+    let titles: Vec<_> = api_errors.iter().map(|e| e.title.as_str()).collect();
+    let codes: Vec<_> = api_errors.iter().map(|e| e.code.as_deref()).collect();
+    assert_eq!(titles, [
+        "Failed to parse config at config.json",
+        "Disk full on /var",
+        "Rate limited"
+    ]);
+    assert_eq!(codes, [
+        Some("config::invalid_format"),
+        Some("storage::full"),
+        Some("api::throttled")
+    ]);
+    assert_eq!(api_errors[1].help.as_deref(), Some("Free some space."));
+    assert!(queue[0].labels().is_some());
+}
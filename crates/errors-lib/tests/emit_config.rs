@@ -0,0 +1,74 @@
+/*
+ * Integration test for the configurable tracing emission. The config is
+ * process-wide, so everything runs in one test.
+ */
+
+mod common;
+
+use common::{LogBuffer, config_report};
+use errors_lib::{
+    EmitConfig, FieldNames, ReportExt, clear_emit_config, emit_config, init_logging_with_writer,
+    set_emit_config,
+};
+use serde_json::Value;
+
+fn emitted(filter: &str) -> (errors_lib::ApiError, Option<Value>) {
+    let buffer = LogBuffer::default();
+    let api_err = {
+        let _guard = init_logging_with_writer(buffer.clone(), filter);
+        config_report().to_api_error()
+    };
+    let event = buffer
+        .contents()
+        .lines()
+        .next()
+        .map(|line| serde_json::from_str(line).unwrap());
+    (api_err, event)
+}
+
+#[test]
+fn test_emit_config_renames_fields_and_sets_target() {
+    assert_eq!(emit_config(), EmitConfig::default());
+    let (_, event) = emitted("error");
+    let event = event.expect("default event");
+    assert_eq!(event["target"], "errors_lib::report");
+    assert!(event["fields"]["hash"].is_string());
+    assert!(event["fields"]["history"].is_string());
+
+    set_emit_config(EmitConfig {
+        target: "api_errors",
+        field_names: FieldNames {
+            hash: "build_hash",
+            docs: "docs_link",
+            id: "correlation_id",
+            ..FieldNames::DEFAULT
+        },
+        include_history: false,
+    });
+
+    let (api_err, event) = emitted("api_errors=error");
+    let event = event.expect("custom target passes its own filter");
+    assert_eq!(event["target"], "api_errors");
+    let fields = &event["fields"];
+    assert_eq!(fields["correlation_id"], api_err.correlation_id.as_str());
+    assert_eq!(fields["build_hash"], api_err.git_hash.as_ref());
+    assert_eq!(fields["docs_link"], api_err.docs_url.as_ref());
+    assert_eq!(fields["code"], "config::invalid_format");
+    for renamed in ["hash", "docs", "id", "history"] {
+        assert!(fields.get(renamed).is_none(), "{renamed} still emitted");
+    }
+    // Left out of the log line, but still in the returned error.
+    assert!(!api_err.history.is_empty());
+
+    let log_fields = api_err.to_log_fields();
+    let names: Vec<_> = log_fields.iter().map(|(name, _)| *name).collect();
+    assert!(names.contains(&"correlation_id"));
+    assert!(!names.contains(&"history"));
+
+    // The old target no longer matches.
+    let (_, event) = emitted("errors_lib=error,api_errors=off");
+    assert!(event.is_none());
+
+    clear_emit_config();
+    assert_eq!(emit_config(), EmitConfig::DEFAULT);
+}
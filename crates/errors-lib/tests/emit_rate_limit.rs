@@ -0,0 +1,97 @@
+/*
+ * Integration tests for rate-limited emission.
+ *
+ * The limit is process-wide, so everything runs in one test.
+ */
+
+mod common;
+
+use std::{thread, time::Duration};
+
+use common::{LogBuffer, config_report, io_report};
+use errors_lib::{
+    ReportExt, SUPPRESSED_CODE, clear_emit_rate_limit, flush_emit_summaries,
+    init_logging_with_writer, set_emit_rate_limit,
+};
+use serde_json::Value;
+
+fn messages(buffer: &LogBuffer) -> Vec<String> {
+    field(buffer, "message")
+}
+
+fn field(buffer: &LogBuffer, name: &str) -> Vec<String> {
+    buffer
+        .contents()
+        .lines()
+        .map(|line| {
+            let event: Value = serde_json::from_str(line).unwrap();
+            event["fields"][name].as_str().unwrap().to_owned()
+        })
+        .collect()
+}
+
+#[test]
+fn test_repeated_errors_are_throttled_and_summarized() {
+    const WINDOW: Duration = Duration::from_millis(200);
+    set_emit_rate_limit(5, WINDOW);
+
+    let buffer = LogBuffer::default();
+    let _guard = init_logging_with_writer(buffer.clone(), "warn");
+
+    // The caller still gets every ApiError in full.
+    let ids: Vec<_> = (0..100)
+        .map(|_| config_report().to_api_error().correlation_id)
+        .collect();
+    assert_eq!(ids.len(), 100);
+    assert!(ids.iter().all(|id| id.len() == 8));
+
+    // Another code has its own budget.
+    io_report(std::io::ErrorKind::NotFound).emit();
+    assert_eq!(messages(&buffer).len(), 6);
+
+    // The summary goes to the sinks, as an ApiError of its own, once the
+    // window has passed.
+    thread::sleep(WINDOW + Duration::from_millis(50));
+    flush_emit_summaries();
+    let titles = field(&buffer, "title");
+    let codes = field(&buffer, "code");
+    let summaries: Vec<_> = titles
+        .iter()
+        .zip(&codes)
+        .filter(|(_, code)| *code == SUPPRESSED_CODE)
+        .map(|(title, _)| title)
+        .collect();
+    assert_eq!(summaries, [
+        "suppressed 95 occurrences of config::invalid_format in the last 200ms"
+    ]);
+    assert_eq!(titles.len(), 7);
+
+    // A fresh window lets errors through again; without a limit, all do.
+    config_report().emit();
+    assert_eq!(messages(&buffer).len(), 8);
+
+    clear_emit_rate_limit();
+    for _ in 0..10 {
+        config_report().emit();
+    }
+    assert_eq!(messages(&buffer).len(), 18);
+
+    // A window too long to ever end is still summarized when the limit is
+    // replaced or cleared.
+    set_emit_rate_limit(1, Duration::MAX);
+    config_report().emit();
+    config_report().emit();
+    set_emit_rate_limit(1, Duration::MAX);
+    config_report().emit();
+    config_report().emit();
+    clear_emit_rate_limit();
+    let codes = field(&buffer, "code");
+    assert_eq!(
+        codes[18..]
+            .iter()
+            .filter(|code| *code == SUPPRESSED_CODE)
+            .count(),
+        2
+    );
+    assert_eq!(codes.len(), 22);
+}
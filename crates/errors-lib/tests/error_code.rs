@@ -0,0 +1,117 @@
+/*
+ * Integration tests for ErrorCode validation and its use in ApiError.
+ */
+
+mod common;
+
+use common::{config_report, two_level_report};
+use errors_lib::{ApiError, ErrorCode, ErrorCodeError, LibReport, ReportExt, rootcause::Report};
+use miette::Diagnostic;
+use snafu::Snafu;
+
+/// A context whose code predates the `namespace::variant` convention.
+#[derive(Debug, Snafu, Diagnostic)]
+#[snafu(display("Legacy failure"))]
+#[diagnostic(code(E0001))]
+struct Legacy;
+
+#[test]
+fn test_namespace_variant_parses() {
+    let code = ErrorCode::new("config::invalid_format").unwrap();
+    assert_eq!(code.namespace(), Some("config"));
+    assert_eq!(code.variant(), Some("invalid_format"));
+    assert_eq!(code.to_string(), "config::invalid_format");
+}
+
+#[test]
+fn test_code_without_separator_fails() {
+    assert!(matches!(
+        ErrorCode::new("nocolon"),
+        Err(ErrorCodeError::MissingSeparator { .. })
+    ));
+}
+
+#[test]
+fn test_empty_or_blank_segments_fail() {
+    for code in [
+        "config::",
+        "::invalid_format",
+        "config::::x",
+        "config::bad format",
+    ] {
+        assert!(
+            matches!(
+                ErrorCode::new(code),
+                Err(ErrorCodeError::InvalidSegment { .. })
+            ),
+            "{code} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn test_deeper_codes_are_multi_level() {
+    let code = ErrorCode::new("too::many::parts").unwrap();
+    assert_eq!(code.namespace(), Some("too"));
+    assert_eq!(code.variant(), Some("many::parts"));
+}
+
+#[test]
+fn test_api_error_code_is_validated_and_serializes_as_a_string() {
+    let api_err = config_report().to_api_error();
+    let code = api_err.code.as_ref().unwrap();
+    assert_eq!(code.namespace(), Some("config"));
+    assert_eq!(api_err.domain.as_deref(), Some("config"));
+
+    let json = serde_json::to_value(&api_err).unwrap();
+    assert_eq!(json["code"], "config::invalid_format");
+
+    let mut legacy = json;
+    legacy["code"] = "E0001".into();
+    let decoded = serde_json::from_value::<ApiError>(legacy).unwrap();
+    let code = decoded.code.unwrap();
+    assert_eq!(code, "E0001");
+    assert!(!code.is_well_formed());
+}
+
+#[test]
+fn test_codes_without_a_namespace_are_kept() {
+    let code = ErrorCode::lenient("E0001").unwrap();
+    assert!(!code.is_well_formed());
+    assert_eq!(code.namespace(), None);
+    assert_eq!(code.variant(), None);
+    assert_eq!(code.as_str(), "E0001");
+    assert!(ErrorCode::lenient("  ").is_none());
+}
+
+#[test]
+fn test_report_with_a_bare_code_keeps_it() {
+    let report: LibReport<Legacy> = Report::new(Legacy).into();
+    let api_err = report.to_api_error();
+
+    assert_eq!(api_err.code.as_deref(), Some("E0001"));
+    assert!(api_err.domain.is_none());
+    assert_eq!(report.error_code().unwrap(), "E0001");
+}
+
+#[test]
+fn test_invalid_segment_message_mentions_whitespace() {
+    let err = ErrorCode::new("config::bad format").unwrap_err();
+    assert!(err.to_string().contains("containing whitespace"), "{err}");
+}
+
+#[test]
+fn test_report_error_code() {
+    assert_eq!(
+        two_level_report().error_code().unwrap(),
+        "config::invalid_format"
+    );
+    assert_eq!(
+        config_report()
+            .into_dynamic()
+            .error_code()
+            .unwrap()
+            .variant(),
+        Some("invalid_format")
+    );
+}
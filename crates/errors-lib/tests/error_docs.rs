@@ -0,0 +1,73 @@
+/*
+ * Integration test for per-namespace docs links.
+ *
+ * Kept in its own test binary, as a single test: the registry is
+ * process-wide, and registrations would race the default-link assertions
+ * elsewhere.
+ */
+
+mod common;
+
+use common::{TestError, config_report};
+use errors_lib::{
+    ErrorDocs, LibReport, ReportExt, clear_error_docs, docs_base_url, docs_url_for,
+    register_error_docs, rootcause::Report,
+};
+use miette::Diagnostic;
+
+struct Wiki;
+
+impl ErrorDocs for Wiki {
+    fn docs_url(&self, code: &str) -> Option<String> {
+        Some(format!(
+            "https://wiki.internal/errors/{}",
+            code.replace("::", "/")
+        ))
+    }
+}
+
+#[test]
+fn test_registered_prefixes_route_to_their_hosts() {
+    register_error_docs("config::*", "https://docs.example.com/config");
+    register_error_docs("network::*", Wiki);
+
+    let config_url = config_report().url().unwrap().to_string();
+    assert_eq!(
+        config_url,
+        "https://docs.example.com/config/#config::invalid_format"
+    );
+    let api_err = config_report().to_api_error();
+    assert_eq!(api_err.code_url.as_deref(), Some(config_url.as_str()));
+    // docs_url is always the base, registrations or not.
+    assert_eq!(api_err.docs_url, docs_base_url());
+
+    let network: LibReport<TestError> = Report::new(TestError::NetworkTimeout {
+        timeout: 30,
+    })
+    .into();
+    let network_url = network.url().unwrap().to_string();
+    assert_eq!(network_url, "https://wiki.internal/errors/network/timeout");
+    assert_eq!(
+        network.to_api_error().code_url.as_deref(),
+        Some(network_url.as_str())
+    );
+
+    // Unregistered namespaces, codes sharing only part of a segment, and
+    // patterns without `::*` fall back to the crate docs.
+    register_error_docs("io", "https://io.example.com");
+    assert!(docs_url_for("io::error").starts_with("https://docs.rs/errors-lib/"));
+    assert!(docs_url_for("configuration::missing").starts_with("https://docs.rs/errors-lib/"));
+
+    // The most specific pattern wins.
+    register_error_docs("config::io::*", "https://io.example.com");
+    assert!(docs_url_for("config::io::denied").starts_with("https://io.example.com/#"));
+
+    clear_error_docs();
+    assert!(
+        config_report()
+            .url()
+            .unwrap()
+            .to_string()
+            .starts_with("https://docs.rs/errors-lib/")
+    );
+}
@@ -0,0 +1,72 @@
+/*
+ * Integration tests for ErrorSinkRegistry.
+ *
+ * The registry is process-wide, so everything runs in one test.
+ */
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use common::{config_report, two_level_report};
+use errors_lib::{
+    ApiError, ApiErrorOptions, ErrorSink, ErrorSinkRegistry, ErrorStats, ReportExt, TracingSink,
+};
+
+#[derive(Clone, Default)]
+struct Collect(Arc<Mutex<Vec<ApiError>>>);
+
+impl ErrorSink for Collect {
+    fn emit(&self, error: &ApiError) {
+        self.0.lock().unwrap().push(error.clone());
+    }
+}
+
+impl Collect {
+    fn ids(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| e.correlation_id.clone())
+            .collect()
+    }
+}
+
+#[test]
+fn test_registered_sinks_receive_every_error() {
+    let first = Collect::default();
+    let second = Collect::default();
+    ErrorSinkRegistry::register(first.clone());
+    ErrorSinkRegistry::register(second.clone());
+
+    let a = config_report().to_api_error();
+    let b = two_level_report().into_dynamic().to_api_error();
+
+    assert_eq!(first.ids(), [a.correlation_id.clone(), b.correlation_id]);
+    assert_eq!(second.ids(), first.ids());
+    assert_eq!(first.0.lock().unwrap()[0], a);
+
+    // Cleared, nothing is emitted; the default sink can be put back.
+    ErrorSinkRegistry::clear();
+    let _ = config_report().to_api_error();
+    assert_eq!(first.ids().len(), 2);
+
+    ErrorSinkRegistry::register(TracingSink);
+    ErrorSinkRegistry::register(first.clone());
+    let _ = config_report().to_api_error();
+    assert_eq!(first.ids().len(), 3);
+
+    // Building without emitting, as the format conversions do, reaches no
+    // sink and leaves the stats alone.
+    let seen = ErrorStats::global().count("config::invalid_format");
+    let built = config_report().build_api_error_with_options(ApiErrorOptions::default());
+    assert_eq!(built.title, a.title);
+    assert_ne!(built.correlation_id, a.correlation_id);
+    #[cfg(feature = "jsonrpc")]
+    let _ = config_report().to_jsonrpc_error();
+    #[cfg(feature = "slack")]
+    let _ = config_report().to_slack_message();
+    assert_eq!(first.ids().len(), 3);
+    assert_eq!(ErrorStats::global().count("config::invalid_format"), seen);
+}
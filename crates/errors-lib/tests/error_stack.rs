@@ -0,0 +1,42 @@
+/*
+ * Integration tests for error-stack interop (`error-stack` feature).
+ */
+
+#![cfg(feature = "error-stack")]
+
+mod common;
+
+use common::{TestError, config_report, two_level_report};
+use errors_lib::{LibReport, ReportExt};
+
+#[test]
+fn test_into_error_stack_keeps_the_context() {
+    let stack: error_stack::Report<TestError> = config_report().into();
+
+    assert!(matches!(
+        stack.current_context(),
+        TestError::ConfigParseError { .. }
+    ));
+}
+
+#[test]
+fn test_round_trip_preserves_frames() {
+    let stack: error_stack::Report<TestError> = two_level_report().into();
+    let debug = format!("{stack:?}");
+    assert!(debug.contains("Failed to parse config at config.json"));
+    assert!(debug.contains("Network timeout after 30s"));
+
+    let back = LibReport::from_error_stack(stack);
+    let rendered = back.render_pretty(false);
+    assert!(rendered.contains("Network timeout after 30s"), "{rendered}");
+}
+
+#[test]
+fn test_round_trip_keeps_api_error_title() {
+    let original = config_report().to_api_error();
+
+    let stack: error_stack::Report<TestError> = config_report().into();
+    let converted = LibReport::from_error_stack(stack).to_api_error();
+
+    assert_eq!(converted.title, original.title);
+}
@@ -0,0 +1,36 @@
+/*
+ * Integration test for ErrorStats.
+ *
+ * Kept in its own test binary, as a single test: the counters are
+ * process-wide, and every to_api_error() elsewhere would bump them.
+ */
+
+mod common;
+
+use common::{config_report, io_report, two_level_report};
+use errors_lib::{ErrorStats, ReportExt};
+
+#[test]
+fn test_to_api_error_counts_codes() {
+    let stats = ErrorStats::global();
+    stats.reset();
+    assert_eq!(stats.count("config::invalid_format"), 0);
+
+    for _ in 0..3 {
+        let _ = config_report().to_api_error();
+    }
+    let _ = two_level_report().to_api_error();
+    let _ = io_report(std::io::ErrorKind::NotFound).to_api_error();
+    let _ = config_report().into_dynamic().to_api_error();
+
+    assert_eq!(stats.count("config::invalid_format"), 5);
+    assert_eq!(stats.count("io::error"), 1);
+    assert_eq!(stats.count("network::timeout"), 0);
+
+    let snapshot = stats.snapshot();
+    assert_eq!(snapshot.len(), 2);
+    assert_eq!(snapshot["config::invalid_format"], 5);
+
+    stats.reset();
+    assert!(stats.snapshot().is_empty());
+}
@@ -0,0 +1,64 @@
+/*
+ * Integration tests for LibReport::to_error_tree.
+ */
+
+mod common;
+
+use common::{config_report, three_level_report, two_level_report};
+use errors_lib::{ApiErrorOptions, ErrorTree, ReportExt};
+
+#[test]
+fn test_single_report_is_a_leaf() {
+    let tree = config_report().to_error_tree();
+    let ErrorTree::Leaf {
+        message,
+        code,
+        attachments,
+    } = tree
+    else {
+        panic!("expected a leaf, got {tree:?}");
+    };
+    assert_eq!(message, "Failed to parse config at config.json");
+    assert_eq!(code.as_deref(), Some("config::invalid_format"));
+    assert!(
+        attachments.contains(&"The application cannot proceed without a valid config.".to_string())
+    );
+}
+
+#[test]
+fn test_report_with_child_is_a_node_with_one_leaf() {
+    let tree = two_level_report().to_error_tree();
+    assert!(matches!(tree, ErrorTree::Node { .. }));
+    assert_eq!(tree.code(), Some("config::invalid_format"));
+
+    let [child] = tree.children() else {
+        panic!("expected one child, got {:?}", tree.children());
+    };
+    assert!(matches!(child, ErrorTree::Leaf { .. }));
+    assert_eq!(child.message(), "Network timeout after 30s");
+    assert_eq!(child.code(), Some("network::timeout"));
+}
+
+#[test]
+fn test_untyped_nodes_have_no_code() {
+    let tree = three_level_report().to_error_tree();
+    let io = &tree.children()[0].children()[0];
+    assert_eq!(io.message(), "connection reset");
+    assert!(io.code().is_none());
+}
+
+#[test]
+fn test_tree_serializes_into_api_error_on_request() {
+    let plain = serde_json::to_value(two_level_report().to_api_error()).unwrap();
+    assert!(plain.get("tree").is_none());
+
+    let api_err =
+        two_level_report().to_api_error_with_options(ApiErrorOptions::default().include_tree(true));
+    let json = serde_json::to_value(&api_err).unwrap();
+    assert_eq!(json["tree"]["kind"], "node");
+    assert_eq!(json["tree"]["children"][0]["kind"], "leaf");
+    assert_eq!(
+        json["tree"]["children"][0]["message"],
+        "Network timeout after 30s"
+    );
+}
@@ -0,0 +1,94 @@
+/*
+ * Integration tests for exit codes derived from diagnostic codes.
+ */
+
+mod common;
+
+use std::process::ExitCode;
+
+use common::{config_report, io_report, two_level_report};
+use errors_lib::{ExitCodeMap, LibResult, ReportExt, run_with_exit_codes};
+
+fn sysexits() -> ExitCodeMap {
+    ExitCodeMap::new()
+        .rule("config::*", 78)
+        .rule("io::*", 74)
+        .fallback(70)
+}
+
+#[test]
+fn test_prefix_rules_map_reports() {
+    let map = sysexits();
+
+    assert_eq!(config_report().exit_status(&map), 78);
+    assert_eq!(
+        io_report(std::io::ErrorKind::NotFound).exit_status(&map),
+        74
+    );
+}
+
+#[test]
+fn test_unmatched_and_missing_codes_fall_back() {
+    let map = sysexits();
+
+    assert_eq!(map.status_for(Some("network::timeout")), 70);
+    assert_eq!(map.status_for(None), 70);
+    assert_eq!(
+        ExitCodeMap::new().status_for(Some("config::invalid_format")),
+        1
+    );
+}
+
+#[test]
+fn test_exact_rule_beats_prefix_and_longer_prefix_wins() {
+    let map = sysexits()
+        .rule("config::invalid_format", 65)
+        .rule("config::io::*", 74);
+
+    assert_eq!(map.status_for(Some("config::invalid_format")), 65);
+    assert_eq!(map.status_for(Some("config::io::denied")), 74);
+    assert_eq!(map.status_for(Some("config::missing")), 78);
+    // Prefixes only match whole segments.
+    assert_eq!(map.status_for(Some("configuration::missing")), 70);
+}
+
+#[test]
+fn test_exit_code_uses_the_top_level_code() {
+    // The config error wraps a network timeout; the outer code decides.
+    assert_eq!(two_level_report().exit_status(&sysexits()), 78);
+}
+
+#[test]
+fn test_run_with_exit_codes() {
+    let map = sysexits();
+
+    let ok = run_with_exit_codes(|| -> LibResult<(), common::TestError> { Ok(()) }, &map);
+    assert_eq!(ok, ExitCode::SUCCESS);
+
+    let failed = run_with_exit_codes(|| Err(config_report()), &map);
+    assert_eq!(failed, ExitCode::from(78));
+}
+
+#[test]
+fn test_sysexits_preset() {
+    let map = ExitCodeMap::sysexits();
+
+    assert_eq!(config_report().exit_status(&map), 78);
+    assert_eq!(
+        io_report(std::io::ErrorKind::NotFound).exit_status(&map),
+        74
+    );
+    assert_eq!(map.status_for(Some("network::timeout")), 1);
+    assert_eq!(map.status_for(None), 1);
+}
+
+#[test]
+fn test_process_exit_code_defaults_to_sysexits() {
+    assert_eq!(config_report().process_exit_code(), 78);
+    assert_eq!(
+        io_report(std::io::ErrorKind::NotFound)
+            .into_dynamic()
+            .process_exit_code(),
+        74
+    );
+}
@@ -0,0 +1,29 @@
+/*
+ * Integration test for replacing the process-wide exit code map.
+ *
+ * Kept in its own binary: the map is global, and the other exit code tests
+ * rely on the sysexits default.
+ */
+
+mod common;
+
+use common::{config_report, io_report, two_level_report};
+use errors_lib::{ExitCodeMap, ReportExt, exit_code_map, set_exit_code_map};
+
+#[test]
+fn test_set_exit_code_map_overrides_the_default() {
+    assert_eq!(config_report().process_exit_code(), 78);
+
+    set_exit_code_map(ExitCodeMap::sysexits().rule("config::*", 65).fallback(70));
+
+    assert_eq!(config_report().process_exit_code(), 65);
+    assert_eq!(
+        io_report(std::io::ErrorKind::NotFound).process_exit_code(),
+        74
+    );
+    assert_eq!(exit_code_map().status_for(Some("network::timeout")), 70);
+    assert_eq!(two_level_report().process_exit_code(), 65);
+
+    set_exit_code_map(ExitCodeMap::sysexits());
+    assert_eq!(config_report().process_exit_code(), 78);
+}
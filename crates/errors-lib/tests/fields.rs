@@ -0,0 +1,126 @@
+/*
+ * Integration tests for key=value fields attached with attach_field.
+ */
+
+mod common;
+
+use common::{LogBuffer, config_report};
+use errors_lib::{ReportExt, init_logging_with_writer, register_field_key};
+use serde_json::Value;
+use tracing::Level;
+
+#[test]
+fn test_fields_land_in_api_error() {
+    let api_err = config_report()
+        .attach_field("path", "/etc/app.json")
+        .attach_field("attempt", 3)
+        .to_api_error();
+
+    assert_eq!(api_err.fields["path"], "/etc/app.json");
+    assert_eq!(api_err.fields["attempt"], "3");
+    // Fields are structured data, not history lines.
+    assert!(
+        api_err
+            .history
+            .iter()
+            .all(|f| !f.message.contains("/etc/app.json"))
+    );
+}
+
+#[test]
+fn test_duplicate_key_keeps_last_value() {
+    let api_err = config_report()
+        .attach_field("attempt", 1)
+        .attach_field("attempt", 2)
+        .to_api_error();
+
+    assert_eq!(api_err.fields.len(), 1);
+    assert_eq!(api_err.fields["attempt"], "2");
+}
+
+#[test]
+fn test_fields_serialize_only_when_present() {
+    let json = serde_json::to_value(config_report().to_api_error()).unwrap();
+    assert!(json.get("fields").is_none());
+
+    let json =
+        serde_json::to_value(config_report().attach_field("user_id", 42).to_api_error()).unwrap();
+    assert_eq!(json["fields"]["user_id"], "42");
+}
+
+#[test]
+fn test_fields_reach_the_tracing_event() {
+    let buffer = LogBuffer::default();
+    {
+        let _guard = init_logging_with_writer(buffer.clone(), "error");
+        let _ = config_report()
+            .attach_field("path", "/etc/app.json")
+            .to_api_error();
+    }
+
+    let logs = buffer.contents();
+    let event: Value = serde_json::from_str(logs.lines().next().unwrap()).unwrap();
+    assert_eq!(event["fields"]["path"], "/etc/app.json", "{logs}");
+    assert!(event["fields"].get("fields").is_none(), "{logs}");
+}
+
+#[test]
+fn test_clashing_keys_stay_in_the_fields_field() {
+    let buffer = LogBuffer::default();
+    {
+        let _guard = init_logging_with_writer(buffer.clone(), "error");
+        let _ = config_report()
+            .attach_field("code", "shadowed")
+            .attach_field("tenant", "acme")
+            .to_api_error();
+    }
+
+    let logs = buffer.contents();
+    let event: Value = serde_json::from_str(logs.lines().next().unwrap()).unwrap();
+    let fields = &event["fields"];
+    assert_eq!(fields["code"], "config::invalid_format", "{logs}");
+    assert_eq!(fields["tenant"], "acme", "{logs}");
+    assert!(
+        fields["fields"].as_str().unwrap().contains("shadowed"),
+        "{logs}"
+    );
+}
+
+#[test]
+fn test_unregistered_keys_stay_in_the_fields_field() {
+    let mut api_err = config_report().to_api_error();
+    api_err
+        .fields
+        .insert("relayed_upstream_key".into(), "from upstream".into());
+    api_err
+        .fields
+        .insert("declared_key".into(), "declared".into());
+    register_field_key("declared_key");
+
+    let buffer = LogBuffer::default();
+    {
+        let _guard = init_logging_with_writer(buffer.clone(), "error");
+        api_err.log_to_tracing(Level::ERROR);
+    }
+
+    let logs = buffer.contents();
+    let event: Value = serde_json::from_str(logs.lines().next().unwrap()).unwrap();
+    let fields = &event["fields"];
+    assert_eq!(fields["declared_key"], "declared", "{logs}");
+    assert!(fields.get("relayed_upstream_key").is_none(), "{logs}");
+    assert!(
+        fields["fields"].as_str().unwrap().contains("from upstream"),
+        "{logs}"
+    );
+}
+
+#[test]
+fn test_log_fields_list_each_pair() {
+    let api_err = config_report()
+        .attach_field("path", "/etc/app.json")
+        .to_api_error();
+    let log_fields = api_err.to_log_fields();
+
+    assert!(log_fields.contains(&("path", "/etc/app.json".to_string())));
+    assert!(log_fields.iter().all(|(key, _)| *key != "fields"));
+}
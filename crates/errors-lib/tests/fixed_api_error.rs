@@ -0,0 +1,81 @@
+/*
+ * Integration tests for FixedApiError (`heapless` feature).
+ */
+
+#![cfg(feature = "heapless")]
+
+mod common;
+
+use common::two_level_report;
+use errors_lib::{FixedApiError, ReportExt};
+
+#[test]
+fn test_json_fits_in_a_stack_buffer() {
+    let mut err: FixedApiError = FixedApiError::new("Sensor read failed")
+        .with_code("sensor::read")
+        .with_retryable(true);
+    assert!(err.push_history("i2c bus timeout"));
+
+    let mut buf = [0u8; 512];
+    let len = err.to_json(&mut buf).expect("fits");
+    let json: serde_json::Value = serde_json::from_slice(&buf[..len]).expect("valid JSON");
+
+    assert_eq!(json["title"], "Sensor read failed");
+    assert_eq!(json["code"], "sensor::read");
+    assert_eq!(json["retryable"], true);
+    assert_eq!(json["history"], serde_json::json!(["i2c bus timeout"]));
+    assert!(json.get("help").is_none());
+}
+
+#[test]
+fn test_too_small_buffer_is_an_error() {
+    let err: FixedApiError = FixedApiError::new("Sensor read failed");
+    assert!(err.to_json(&mut [0u8; 16]).is_err());
+}
+
+#[test]
+fn test_long_text_is_cut_at_a_char_boundary() {
+    let err: FixedApiError<8, 1> = FixedApiError::new("héllo wörld");
+    assert_eq!(err.title.as_str(), "héllo w");
+}
+
+#[test]
+fn test_history_past_capacity_is_dropped() {
+    let mut err: FixedApiError<16, 2> = FixedApiError::new("x");
+    assert!(err.push_history("one"));
+    assert!(err.push_history("two"));
+    assert!(!err.push_history("three"));
+    assert_eq!(err.history.len(), 2);
+}
+
+#[test]
+fn test_correlation_ids_come_from_a_counter() {
+    let a: FixedApiError = FixedApiError::new("a");
+    let b: FixedApiError = FixedApiError::new("b");
+    assert_ne!(a.correlation_id, b.correlation_id);
+    assert_eq!(a.correlation_id.len(), 8);
+}
+
+#[test]
+fn test_from_api_error_keeps_the_leading_fields() {
+    let api_err = two_level_report().to_api_error();
+    let fixed: FixedApiError<64, 2> = FixedApiError::from(&api_err);
+
+    assert_eq!(fixed.title.as_str(), api_err.title);
+    assert_eq!(fixed.code.as_deref(), api_err.code.as_deref());
+    assert_eq!(fixed.retryable, api_err.retryable);
+    assert_eq!(fixed.history.len(), api_err.history.len().min(2));
+    assert_eq!(fixed.history[0].as_str(), api_err.history[0].message);
+}
+
+#[test]
+fn test_camel_case_json() {
+    let err: FixedApiError = FixedApiError::new("Sensor read failed").with_code("sensor::read");
+    let mut buf = [0u8; 512];
+    let len = err.to_json_camel_case(&mut buf).expect("fits");
+    let json: serde_json::Value = serde_json::from_slice(&buf[..len]).expect("valid JSON");
+
+    assert_eq!(json["correlationId"], err.correlation_id.as_str());
+    assert_eq!(json["gitHash"], err.git_hash);
+    assert!(json.get("correlation_id").is_none());
+}
@@ -0,0 +1,34 @@
+/*
+ * Integration test for process-wide ApiError fields.
+ *
+ * Kept in its own binary: the fields are global, and other tests' JSON
+ * assumes none are set.
+ */
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::config_report;
+use errors_lib::{ReportExt, clear_global_fields, global_fields, set_global_fields};
+
+#[test]
+fn test_global_fields_are_stamped_into_every_api_error() {
+    let json = serde_json::to_value(config_report().to_api_error()).unwrap();
+    assert!(json.get("extra").is_none());
+
+    set_global_fields(BTreeMap::from([
+        ("service".to_string(), "payments".to_string()),
+        ("environment".to_string(), "staging".to_string()),
+    ]));
+    assert_eq!(global_fields().len(), 2);
+
+    let api_err = config_report().into_dynamic().to_api_error();
+    assert_eq!(api_err.extra["service"], "payments");
+    let json = serde_json::to_value(config_report().to_api_error()).unwrap();
+    assert_eq!(json["extra"]["service"], "payments");
+    assert_eq!(json["extra"]["environment"], "staging");
+
+    clear_global_fields();
+    assert!(config_report().to_api_error().extra.is_empty());
+}
@@ -0,0 +1,42 @@
+/*
+ * Integration tests for GraphQL error extensions (`graphql` feature).
+ */
+
+#![cfg(feature = "graphql")]
+
+mod common;
+
+use common::{config_report, two_level_report};
+use errors_lib::ReportExt;
+use serde_json::json;
+
+#[test]
+fn test_message_is_the_title_and_extensions_carry_code_and_id() {
+    let report = config_report();
+    let api_err = report.to_api_error();
+    let (message, extensions) = api_err.to_graphql_extensions();
+
+    assert_eq!(message, api_err.title);
+    assert_eq!(extensions["code"], "config::invalid_format");
+    assert_eq!(
+        extensions["correlation_id"],
+        api_err.correlation_id.as_str()
+    );
+    let history: Vec<_> = api_err.history.iter().map(|f| f.message.as_str()).collect();
+    assert_eq!(extensions["history"], json!(history));
+}
+
+#[test]
+fn test_report_ext_builds_the_pair_directly() {
+    let (message, extensions) = two_level_report().to_graphql_extensions();
+
+    assert_eq!(message, "Failed to parse config at config.json");
+    assert_eq!(extensions["code"], "config::invalid_format");
+    assert_eq!(extensions["correlation_id"].as_str().map(str::len), Some(8));
+    assert!(
+        extensions["history"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("Network timeout after 30s"))
+    );
+}
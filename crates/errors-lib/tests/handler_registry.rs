@@ -0,0 +1,149 @@
+/*
+ * Integration tests for ErrorHandlerRegistry.
+ */
+
+mod common;
+
+use std::{
+    sync::{
+        Arc, Mutex, OnceLock,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+};
+
+use common::{config_report, three_level_report, two_level_report};
+use errors_lib::ErrorHandlerRegistry;
+
+/// A registry whose handlers append their label to a shared log.
+fn recording(patterns: &[&'static str]) -> (ErrorHandlerRegistry, Arc<Mutex<Vec<String>>>) {
+    let registry = ErrorHandlerRegistry::new();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    for &pattern in patterns {
+        let log = Arc::clone(&log);
+        registry.on(pattern, move |node| {
+            log.lock()
+                .unwrap()
+                .push(format!("{pattern} <- {}", node.code()));
+        });
+    }
+    (registry, log)
+}
+
+#[test]
+fn test_exact_fires_before_prefix() {
+    let (registry, log) = recording(&["config::*", "config::invalid_format"]);
+    let outcome = registry.dispatch(&config_report());
+
+    assert_eq!(*log.lock().unwrap(), [
+        "config::invalid_format <- config::invalid_format",
+        "config::* <- config::invalid_format",
+    ]);
+    assert_eq!(outcome.fired.len(), 2);
+    assert_eq!(outcome.fired[0].pattern, "config::invalid_format");
+}
+
+#[test]
+fn test_matches_fire_in_registration_order() {
+    let (registry, log) = recording(&["network::*", "io::*", "network::*"]);
+    let outcome = registry.dispatch(&three_level_report());
+
+    assert_eq!(*log.lock().unwrap(), [
+        "network::* <- network::timeout",
+        "network::* <- network::timeout"
+    ]);
+    let ids: Vec<_> = outcome.fired.iter().map(|f| f.id).collect();
+    assert!(ids[0] < ids[1]);
+}
+
+#[test]
+fn test_trailing_colons_and_stars_are_prefixes() {
+    let (registry, log) = recording(&["config::", "config*", "conf::*", "configuration*"]);
+    let outcome = registry.dispatch(&config_report());
+
+    assert!(outcome.is_handled());
+    assert_eq!(*log.lock().unwrap(), [
+        "config:: <- config::invalid_format",
+        "config* <- config::invalid_format",
+    ]);
+}
+
+#[test]
+fn test_every_coded_node_is_dispatched() {
+    let registry = ErrorHandlerRegistry::new();
+    let config = registry.on("config::*", |_| {});
+    let network = registry.on("network::timeout", |_| {});
+    let io = registry.on("io::*", |_| {});
+
+    let outcome = registry.dispatch(&two_level_report());
+    assert!(outcome.fired(config));
+    assert!(outcome.fired(network));
+    assert!(!outcome.fired(io));
+    let codes: Vec<_> = outcome.fired.iter().map(|f| f.code.as_str()).collect();
+    assert_eq!(codes, ["config::invalid_format", "network::timeout"]);
+}
+
+#[test]
+fn test_handler_sees_the_matching_node() {
+    let registry = ErrorHandlerRegistry::new();
+    let seen = Arc::new(Mutex::new(None));
+    let sink = Arc::clone(&seen);
+    registry.on("network::timeout", move |node| {
+        let messages: Vec<_> = node.node().messages().collect();
+        *sink.lock().unwrap() = Some((node.to_string(), messages));
+    });
+
+    registry.dispatch(&two_level_report());
+    let (message, attachments) = seen.lock().unwrap().take().unwrap();
+    assert_eq!(message, "Network timeout after 30s");
+    assert!(attachments.contains(&"while fetching remote config".to_string()));
+}
+
+#[test]
+fn test_no_match_is_unhandled() {
+    let (registry, log) = recording(&["network::*"]);
+    let outcome = registry.dispatch(&config_report());
+
+    assert!(!outcome.is_handled());
+    assert!(log.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_shared_registry_across_threads() {
+    static REGISTRY: OnceLock<ErrorHandlerRegistry> = OnceLock::new();
+    static FIRED: AtomicUsize = AtomicUsize::new(0);
+
+    let registry = REGISTRY.get_or_init(ErrorHandlerRegistry::new);
+    registry.on("network::*", |_| {
+        FIRED.fetch_add(1, Ordering::SeqCst);
+    });
+
+    thread::scope(|scope| {
+        for i in 0..8 {
+            scope.spawn(move || {
+                let registry = REGISTRY.get().unwrap();
+                if i % 2 == 0 {
+                    // Handlers registered mid-flight must not deadlock dispatch.
+                    registry.on("config::*", |_| {});
+                }
+                for _ in 0..25 {
+                    assert!(registry.dispatch(&two_level_report()).is_handled());
+                }
+            });
+        }
+    });
+
+    assert_eq!(FIRED.load(Ordering::SeqCst), 8 * 25);
+}
+
+#[test]
+fn test_handler_may_register_another() {
+    let registry = Arc::new(ErrorHandlerRegistry::new());
+    let inner = Arc::clone(&registry);
+    registry.on("config::*", move |_| {
+        inner.on("network::*", |_| {});
+    });
+
+    registry.dispatch(&config_report());
+    assert_eq!(format!("{registry:?}"), r#"["config::*", "network::*"]"#);
+}
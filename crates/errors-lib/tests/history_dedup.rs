@@ -0,0 +1,77 @@
+/*
+ * Integration tests for ApiError history deduplication (HistoryDedup).
+ */
+
+mod common;
+
+use common::{TestError, config_parse_error};
+use errors_lib::{
+    ApiError, ApiErrorOptions, HistoryDedup, LibReport, ReportExt, rootcause::Report,
+};
+
+/// "retrying" twice in a row on the child, and once more on the parent,
+/// separated from the others by the child's context message.
+fn report() -> LibReport<TestError> {
+    Report::new(TestError::NetworkTimeout {
+        timeout: 30,
+    })
+    .attach("retrying")
+    .attach("retrying")
+    .context(config_parse_error())
+    .attach("retrying")
+    .into()
+}
+
+fn history(dedup: HistoryDedup) -> Vec<String> {
+    let api_err: ApiError =
+        report().to_api_error_with_options(ApiErrorOptions::default().history_dedup(dedup));
+    api_err
+        .history
+        .into_iter()
+        .map(|frame| frame.message)
+        .collect()
+}
+
+fn count(history: &[String], message: &str) -> usize {
+    history.iter().filter(|m| *m == message).count()
+}
+
+#[test]
+fn test_duplicates_are_kept_by_default() {
+    let default: Vec<String> = report()
+        .to_api_error()
+        .history
+        .into_iter()
+        .map(|frame| frame.message)
+        .collect();
+    assert_eq!(default, history(HistoryDedup::Keep));
+    assert_eq!(count(&default, "retrying"), 3);
+}
+
+#[test]
+fn test_adjacent_duplicates_collapse() {
+    let history = history(HistoryDedup::Adjacent);
+    assert_eq!(count(&history, "retrying"), 2, "{history:?}");
+    assert!(
+        history.windows(2).all(|pair| pair[0] != pair[1]),
+        "{history:?}"
+    );
+}
+
+#[test]
+fn test_non_adjacent_duplicates_survive_adjacent_mode() {
+    let history = history(HistoryDedup::Adjacent);
+    let timeout = history
+        .iter()
+        .position(|m| m == "Network timeout after 30s")
+        .expect("child context");
+    assert!(history[..timeout].contains(&"retrying".to_string()));
+    assert!(history[timeout..].contains(&"retrying".to_string()));
+}
+
+#[test]
+fn test_all_mode_keeps_only_the_first() {
+    let history = history(HistoryDedup::All);
+    assert_eq!(count(&history, "retrying"), 1, "{history:?}");
+    assert!(history.contains(&"Network timeout after 30s".to_string()));
+}
@@ -0,0 +1,64 @@
+/*
+ * Integration tests for ApiError history ordering (HistoryOrder).
+ */
+
+mod common;
+
+use common::{TestError, two_level_report};
+use errors_lib::{ApiError, ApiErrorOptions, HistoryOrder, LibReport, ReportExt};
+
+/// Two attachments on the top-level node, over a network timeout child.
+fn report() -> LibReport<TestError> {
+    two_level_report()
+        .attach("first attachment")
+        .attach("second attachment")
+}
+
+fn messages(api_err: &ApiError) -> Vec<&str> {
+    api_err
+        .history
+        .iter()
+        .map(|frame| frame.message.as_str())
+        .collect()
+}
+
+/// Positions of the known messages, ignoring anything else in history.
+fn order_of(history: &[&str]) -> Vec<usize> {
+    [
+        "first attachment",
+        "second attachment",
+        "Network timeout after 30s",
+        "while fetching remote config",
+    ]
+    .iter()
+    .map(|msg| history.iter().position(|m| m == msg).expect(msg))
+    .collect()
+}
+
+#[test]
+fn test_context_first_is_the_default() {
+    let default = report().to_api_error();
+    let explicit = report().to_api_error_with_options(
+        ApiErrorOptions::default().history_order(HistoryOrder::ContextFirst),
+    );
+    assert_eq!(messages(&default), messages(&explicit));
+
+    let order = order_of(&messages(&default));
+    assert!(order.is_sorted(), "expected outermost first: {order:?}");
+}
+
+#[test]
+fn test_root_first_reverses_history() {
+    let context_first = report().to_api_error();
+    let root_first = report().to_api_error_root_first();
+
+    let mut reversed = messages(&context_first);
+    reversed.reverse();
+    assert_eq!(messages(&root_first), reversed);
+
+    let order = order_of(&messages(&root_first));
+    assert!(
+        order.iter().rev().is_sorted(),
+        "expected root cause first: {order:?}"
+    );
+}
@@ -0,0 +1,61 @@
+/*
+ * Integration tests for LibReport::inspect / inspect_report.
+ */
+
+mod common;
+
+use std::cell::Cell;
+
+use common::{TestError, config_report, two_level_report};
+use errors_lib::{LibResult, ReportExt};
+
+#[test]
+fn test_inspect_sees_the_api_error_once() {
+    let calls = Cell::new(0);
+    let report = config_report().inspect(|api_err| {
+        calls.set(calls.get() + 1);
+        assert_eq!(api_err.code.as_deref(), Some("config::invalid_format"));
+    });
+
+    assert_eq!(calls.get(), 1);
+    assert_eq!(
+        report.to_api_error().title,
+        "Failed to parse config at config.json"
+    );
+}
+
+#[test]
+fn test_inspect_report_keeps_the_chain() {
+    let calls = Cell::new(0);
+    let report = two_level_report().inspect_report(|report| {
+        calls.set(calls.get() + 1);
+        assert_eq!(report.codes().len(), 2);
+    });
+
+    assert_eq!(calls.get(), 1);
+    assert_eq!(report.codes(), [
+        "config::invalid_format",
+        "network::timeout"
+    ]);
+    let history = report.to_api_error().history;
+    assert!(
+        history
+            .iter()
+            .any(|frame| frame.message == "while fetching remote config")
+    );
+}
+
+#[test]
+fn test_inspect_in_a_map_err_chain() {
+    let calls = Cell::new(0);
+    let result: LibResult<(), TestError> = Err(config_report());
+    let result = result.map_err(|report| report.inspect(|_| calls.set(calls.get() + 1)));
+
+    assert_eq!(calls.get(), 1);
+    assert!(result.is_err());
+
+    let ok: LibResult<(), TestError> = Ok(());
+    let ok = ok.map_err(|report| report.inspect(|_| calls.set(calls.get() + 1)));
+    assert!(ok.is_ok());
+    assert_eq!(calls.get(), 1);
+}
@@ -0,0 +1,46 @@
+/*
+ * Integration tests for LibReport::instrument_future (`async` feature).
+ */
+
+#![cfg(feature = "async")]
+
+mod common;
+
+use std::time::Duration;
+
+use common::TestError;
+use errors_lib::{LibReport, ReportExt};
+
+async fn fetch(timeout: u64) -> Result<String, TestError> {
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    Err(TestError::NetworkTimeout {
+        timeout,
+    })
+}
+
+#[tokio::test]
+async fn test_error_carries_elapsed_time_in_history() {
+    let report = LibReport::instrument_future(fetch(30)).await.unwrap_err();
+
+    let api_err = report.to_api_error();
+    assert_eq!(api_err.title, "Network timeout after 30s");
+    let elapsed = api_err
+        .history
+        .iter()
+        .find_map(|frame| frame.message.strip_prefix("failed after "))
+        .expect("elapsed attachment missing");
+    assert!(elapsed.ends_with("ms"), "{elapsed}");
+}
+
+#[tokio::test]
+async fn test_location_is_the_call_site() {
+    let fut = LibReport::instrument_future(fetch(30));
+    let location = fut.await.unwrap_err().to_api_error().location.unwrap();
+    assert!(location.contains("instrument_future.rs:37:"), "{location}");
+}
+
+#[tokio::test]
+async fn test_success_passes_through() {
+    let value = LibReport::<TestError>::instrument_future(async { Ok(7) }).await;
+    assert_eq!(value.unwrap(), 7);
+}
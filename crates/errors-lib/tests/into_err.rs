@@ -0,0 +1,38 @@
+/*
+ * Integration tests for LibReport::into_err and LibReport::ok.
+ */
+
+mod common;
+
+use common::{TestError, config_report};
+use errors_lib::{LibReport, LibResult, miette::Diagnostic};
+
+fn parse(valid: bool) -> LibResult<u16, TestError> {
+    if !valid {
+        // T is inferred from the return type.
+        return config_report().into_err();
+    }
+    LibReport::ok(8080)
+}
+
+#[test]
+fn test_ok_and_err_carry_their_values() {
+    assert_eq!(parse(true).unwrap(), 8080);
+
+    let report = parse(false).unwrap_err();
+    assert_eq!(
+        report.code().map(|code| code.to_string()).as_deref(),
+        Some("config::invalid_format")
+    );
+}
+
+#[test]
+fn test_t_is_inferred_from_later_use() {
+    let mut result = config_report().into_err();
+    assert!(result.is_err());
+    result = Ok(String::from("recovered"));
+    assert_eq!(result.unwrap(), "recovered");
+
+    let result = LibReport::<TestError>::ok(vec![1, 2, 3]);
+    assert_eq!(result.map(|v| v.len()).unwrap(), 3);
+}
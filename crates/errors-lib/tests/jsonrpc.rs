@@ -0,0 +1,58 @@
+/*
+ * Integration tests for JSON-RPC error objects (`jsonrpc` feature).
+ *
+ * The code registry is process-wide, so everything runs in one test.
+ */
+
+#![cfg(feature = "jsonrpc")]
+
+mod common;
+
+use common::{config_report, io_report};
+use errors_lib::{
+    JSONRPC_SERVER_ERROR, ReportExt, clear_jsonrpc_codes, jsonrpc_code_for, register_jsonrpc_code,
+};
+
+#[test]
+fn test_jsonrpc_error_codes() {
+    // Nothing registered: the generic server error.
+    let error = config_report().to_jsonrpc_error();
+    assert_eq!(error.code, JSONRPC_SERVER_ERROR);
+    assert_eq!(error.code, -32000);
+    assert_eq!(error.message, "Failed to parse config at config.json");
+    assert_eq!(error.data["code"], "config::invalid_format");
+    assert_eq!(error.data["title"], error.message.as_str());
+
+    register_jsonrpc_code("config::*", -32602);
+    register_jsonrpc_code("config::invalid_format", -32001);
+
+    // The exact registration beats the prefix.
+    let report = config_report();
+    let api_err = report.to_api_error();
+    let error = api_err.to_jsonrpc_error();
+    assert_eq!(error.code, -32001);
+    assert_eq!(error.data, serde_json::to_value(&api_err).unwrap());
+
+    assert_eq!(jsonrpc_code_for(Some("config::missing")), -32602);
+    assert_eq!(jsonrpc_code_for(Some("configuration::missing")), -32000);
+    assert_eq!(jsonrpc_code_for(None), JSONRPC_SERVER_ERROR);
+    assert_eq!(
+        io_report(std::io::ErrorKind::NotFound)
+            .to_jsonrpc_error()
+            .code,
+        JSONRPC_SERVER_ERROR
+    );
+
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(json["code"], -32001);
+    assert_eq!(
+        json["data"]["correlation_id"],
+        api_err.correlation_id.as_str()
+    );
+
+    clear_jsonrpc_codes();
+    assert_eq!(
+        config_report().to_jsonrpc_error().code,
+        JSONRPC_SERVER_ERROR
+    );
+}
@@ -0,0 +1,59 @@
+/*
+ * Integration tests for the tracing level errors are emitted at.
+ *
+ * The level mapper is process-wide, so everything runs in one test.
+ */
+
+mod common;
+
+use common::{LogBuffer, config_report};
+use errors_lib::{
+    ApiError, ReportExt, clear_level_mapper, init_logging_with_writer, miette::Severity,
+    set_level_mapper,
+};
+use serde_json::Value;
+use tracing::Level;
+
+/// The level of each event logged while `emit` runs.
+fn logged_levels(emit: impl FnOnce()) -> Vec<String> {
+    let buffer = LogBuffer::default();
+    {
+        let _guard = init_logging_with_writer(buffer.clone(), "trace");
+        emit();
+    }
+    buffer
+        .contents()
+        .lines()
+        .map(|line| {
+            let event: Value = serde_json::from_str(line).unwrap();
+            event["level"].as_str().unwrap().to_owned()
+        })
+        .collect()
+}
+
+fn quiet_config_errors(error: &ApiError) -> Level {
+    if error.code.as_deref() == Some("config::invalid_format") {
+        Level::DEBUG
+    } else {
+        error.severity_level()
+    }
+}
+
+#[test]
+fn test_event_level_follows_severity_and_mapper() {
+    let levels = logged_levels(|| {
+        config_report().emit();
+        config_report().with_severity(Severity::Warning).emit();
+        config_report().with_severity(Severity::Advice).emit();
+    });
+    assert_eq!(levels, ["ERROR", "WARN", "INFO"]);
+
+    set_level_mapper(quiet_config_errors);
+    let api_err = config_report().to_api_error();
+    assert_eq!(api_err.level(), Level::DEBUG);
+    assert_eq!(api_err.severity_level(), Level::ERROR);
+    assert_eq!(logged_levels(|| config_report().emit()), ["DEBUG"]);
+
+    clear_level_mapper();
+    assert_eq!(logged_levels(|| config_report().emit()), ["ERROR"]);
+}
@@ -0,0 +1,51 @@
+/*
+ * Integration tests for the lib_bail! and lib_ensure! macros.
+ */
+
+mod common;
+
+use common::{TestError, config_parse_error};
+use errors_lib::{LibResult, ReportExt, lib_bail, lib_ensure};
+
+fn fetch(timeout: u64) -> LibResult<String, TestError> {
+    lib_bail!(TestError::NetworkTimeout {
+        timeout
+    });
+}
+
+fn parse(input: &str) -> LibResult<usize, TestError> {
+    lib_ensure!(input.starts_with('{'), config_parse_error());
+    Ok(input.len())
+}
+
+#[test]
+fn test_bail_returns_the_context() {
+    let report = fetch(30).unwrap_err();
+    assert!(matches!(report.as_ref(), TestError::NetworkTimeout {
+        timeout: 30
+    }));
+    assert_eq!(
+        report.to_api_error().code.as_deref(),
+        Some("network::timeout")
+    );
+}
+
+#[test]
+fn test_bail_records_the_call_site() {
+    let location = fetch(30).unwrap_err().to_api_error().location.unwrap();
+    assert!(location.contains("lib_bail.rs:11:"), "{location}");
+}
+
+#[test]
+fn test_ensure_passes_when_the_condition_holds() {
+    assert_eq!(parse("{}").unwrap(), 2);
+}
+
+#[test]
+fn test_ensure_bails_when_the_condition_fails() {
+    let report = parse("key = 1").unwrap_err();
+    assert!(matches!(
+        report.as_ref(),
+        TestError::ConfigParseError { .. }
+    ));
+}
@@ -0,0 +1,76 @@
+/*
+ * Integration tests for the lib_error! macro.
+ */
+
+use std::io;
+
+use errors_lib::{LibReport, ReportExt, lib_error, rootcause::Report};
+use miette::Diagnostic;
+
+lib_error! {
+    #[snafu(visibility(pub(crate)))]
+    enum AppError {
+        /// Config file could not be parsed.
+        ConfigParseError { path: String } => {
+            display: "Failed to parse config at {path}",
+            code: config::invalid_format,
+            help: "Ensure the configuration file is valid JSON.",
+        },
+        NetworkTimeout { timeout: u64 } => {
+            display: "Network timeout after {timeout}s",
+            code: network::timeout,
+        },
+        Cancelled => { display: "Cancelled by user" },
+    }
+}
+
+fn open_missing() -> Result<(), AppError> {
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "config.json not found",
+    ))?;
+    Ok(())
+}
+
+fn code_of(err: &AppError) -> Option<String> {
+    err.code().map(|c| c.to_string())
+}
+
+#[test]
+fn test_question_mark_converts_io_errors() {
+    let err = open_missing().unwrap_err();
+    assert!(matches!(err, AppError::Io { .. }));
+    assert_eq!(err.to_string(), "IO error: config.json not found");
+    assert_eq!(code_of(&err).as_deref(), Some("io::error"));
+}
+
+#[test]
+fn test_variant_code_and_help() {
+    let err = ConfigParseSnafu {
+        path: "config.json",
+    }
+    .build();
+    assert_eq!(err.to_string(), "Failed to parse config at config.json");
+    assert_eq!(code_of(&err).as_deref(), Some("config::invalid_format"));
+    assert!(err.help().is_some());
+
+    let timeout = AppError::NetworkTimeout {
+        timeout: 30,
+    };
+    assert_eq!(code_of(&timeout).as_deref(), Some("network::timeout"));
+    assert!(timeout.help().is_none());
+}
+
+#[test]
+fn test_variant_without_code() {
+    assert!(code_of(&AppError::Cancelled).is_none());
+}
+
+#[test]
+fn test_generated_enum_wraps_in_lib_report() {
+    let report: LibReport<AppError> = Report::new(AppError::NetworkTimeout {
+        timeout: 30,
+    })
+    .into();
+    assert!(report.is_retryable());
+}
@@ -0,0 +1,48 @@
+/*
+ * Integration tests for the creation location recorded by LibReport::new.
+ */
+
+mod common;
+
+use common::{TestError, config_parse_error};
+use errors_lib::{LibReport, ReportExt, ReportLocation, rootcause::Report};
+
+fn wrapped_here() -> LibReport<TestError> {
+    Report::new(config_parse_error()).into()
+}
+
+#[test]
+fn test_location_names_the_calling_file() {
+    let report = wrapped_here();
+    let location = report
+        .get_attachment::<ReportLocation>()
+        .expect("location missing");
+    assert!(location.0.file().ends_with("location.rs"));
+    assert_eq!(location.0.line(), 11);
+}
+
+#[test]
+fn test_api_error_exposes_location() {
+    let api_err = LibReport::new(Report::new(config_parse_error())).to_api_error();
+    let location = api_err.location.expect("location missing");
+    assert!(
+        location.contains("location.rs:"),
+        "unexpected location: {location}"
+    );
+    assert!(
+        !api_err
+            .history
+            .iter()
+            .any(|frame| frame.message == location)
+    );
+}
+
+#[test]
+fn test_change_context_records_the_new_call_site() {
+    let report = wrapped_here().change_context(TestError::NetworkTimeout {
+        timeout: 30,
+    });
+    let outermost = report.get_attachment::<ReportLocation>().unwrap();
+    assert!(outermost.0.file().ends_with("location.rs"));
+    assert_ne!(outermost.0.line(), 11);
+}
@@ -0,0 +1,61 @@
+/*
+ * Integration tests for the scoped test subscriber.
+ */
+
+mod common;
+
+use common::{LogBuffer, config_report, two_level_report};
+use errors_lib::{ReportExt, init_logging_with_writer};
+use serde_json::Value;
+
+#[test]
+fn test_to_api_error_emits_json_fields() {
+    let buffer = LogBuffer::default();
+    let api_err = {
+        let _guard = init_logging_with_writer(buffer.clone(), "error");
+        config_report().to_api_error()
+    };
+
+    let logs = buffer.contents();
+    assert!(logs.contains("\"title\""), "unexpected logs: {logs}");
+
+    let event: Value = serde_json::from_str(logs.lines().next().unwrap()).unwrap();
+    let fields = &event["fields"];
+    assert_eq!(fields["title"], api_err.title.as_str());
+    assert_eq!(fields["id"], api_err.correlation_id.as_str());
+    assert_eq!(fields["code"], "config::invalid_format");
+}
+
+#[test]
+fn test_nested_report_logs_its_summary_as_title() {
+    let buffer = LogBuffer::default();
+    let api_err = {
+        let _guard = init_logging_with_writer(buffer.clone(), "error");
+        two_level_report().to_api_error()
+    };
+
+    let logs = buffer.contents();
+    let event: Value = serde_json::from_str(logs.lines().next().unwrap()).unwrap();
+    let summary = api_err.summary.as_deref().expect("tree has children");
+    assert!(summary.contains("Network timeout after 30s"));
+    assert_eq!(event["fields"]["title"], summary);
+}
+
+#[test]
+fn test_filter_drops_events_below_level() {
+    let buffer = LogBuffer::default();
+    {
+        let _guard = init_logging_with_writer(buffer.clone(), "off");
+        config_report().to_api_error();
+    }
+    assert!(buffer.contents().is_empty());
+}
+
+#[test]
+fn test_guard_drop_uninstalls_subscriber() {
+    let buffer = LogBuffer::default();
+    drop(init_logging_with_writer(buffer.clone(), "trace"));
+
+    config_report().to_api_error();
+    assert!(buffer.contents().is_empty());
+}
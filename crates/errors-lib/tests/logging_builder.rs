@@ -0,0 +1,37 @@
+/*
+ * Integration test for LoggingBuilder.
+ *
+ * It installs the process-wide subscriber, which can happen only once, so
+ * everything runs in one test.
+ */
+
+mod common;
+
+use std::fs;
+
+use common::config_report;
+use errors_lib::{LogRotation, LoggingBuilder, ReportExt};
+use serde_json::Value;
+
+#[test]
+fn test_file_output_survives_until_the_guard_drops() {
+    let dir = tempfile::tempdir().unwrap();
+    let guard = LoggingBuilder::new()
+        .without_stderr()
+        .file(dir.path().join("logs"), "api-errors.log")
+        .rotation(LogRotation::Never)
+        .build()
+        .unwrap();
+
+    let api_err = config_report().to_api_error();
+    drop(guard);
+
+    let logs = fs::read_to_string(dir.path().join("logs/api-errors.log")).unwrap();
+    let event: Value = serde_json::from_str(logs.lines().next().unwrap()).unwrap();
+    assert_eq!(event["level"], "ERROR");
+    assert_eq!(event["fields"]["id"], api_err.correlation_id.as_str());
+    assert_eq!(event["fields"]["title"], api_err.title.as_str());
+
+    // The global subscriber is already set.
+    assert!(LoggingBuilder::new().build().is_err());
+}
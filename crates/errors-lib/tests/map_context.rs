@@ -0,0 +1,72 @@
+/*
+ * Integration tests for changing a LibReport's context type at a module
+ * boundary (map_context / change_context).
+ */
+
+mod common;
+
+use common::{TestError, config_report};
+use errors_lib::{LibReport, ReportExt};
+use miette::Diagnostic;
+use snafu::prelude::*;
+
+/// The "outer module" error type a storage-level `TestError` is lifted into.
+#[derive(Debug, Snafu, Diagnostic)]
+enum AppError {
+    #[snafu(display("Could not load settings"))]
+    #[diagnostic(code(app::settings))]
+    Settings,
+
+    #[snafu(display("Wrapped: {message}"))]
+    Wrapped { message: String },
+}
+
+fn attachment_count(report: &LibReport<impl Diagnostic + Send + Sync + 'static>) -> usize {
+    report.0.attachments().len()
+}
+
+#[test]
+fn test_map_context_keeps_attachments_and_children() {
+    let report = config_report();
+    let attachments = attachment_count(&report);
+
+    let mapped: LibReport<AppError> = report.map_context(|err| AppError::Wrapped {
+        message: err.to_string(),
+    });
+
+    assert_eq!(
+        mapped.0.current_context().to_string(),
+        "Wrapped: Failed to parse config at config.json"
+    );
+    assert_eq!(attachment_count(&mapped), attachments);
+    assert!(mapped.0.children().is_empty());
+}
+
+#[test]
+fn test_change_context_pushes_old_context_down() {
+    let changed = config_report().change_context(AppError::Settings);
+
+    assert_eq!(changed.0.children().len(), 1);
+    let child = changed.0.children().iter().next().unwrap();
+    assert!(child.downcast_current_context::<TestError>().is_some());
+    assert_eq!(
+        changed.code().map(|c| c.to_string()).as_deref(),
+        Some("app::settings")
+    );
+}
+
+#[test]
+fn test_change_context_keeps_old_message_in_history() {
+    let api_err = config_report()
+        .change_context(AppError::Settings)
+        .to_api_error();
+
+    assert_eq!(api_err.title, "Could not load settings");
+    let history: Vec<&str> = api_err
+        .history
+        .iter()
+        .map(|frame| frame.message.as_str())
+        .collect();
+    assert!(history.contains(&"Failed to parse config at config.json"));
+    assert!(history.contains(&"The application cannot proceed without a valid config."));
+}
@@ -0,0 +1,47 @@
+/*
+ * Integration tests for LibReport::to_markdown.
+ */
+
+mod common;
+
+use std::io::ErrorKind;
+
+use common::{config_report, io_report};
+
+#[test]
+fn test_markdown_has_heading_and_fields() {
+    let md = config_report().to_markdown();
+    assert!(md.starts_with("## Error: Failed to parse config at config.json\n"));
+    assert!(md.contains("**Code:** `config::invalid_format`"));
+    assert!(md.contains("**Help:** Ensure the configuration file is valid JSON."));
+}
+
+#[test]
+fn test_markdown_fences_labelled_source() {
+    let md = config_report().to_markdown();
+    assert_eq!(
+        md.matches("```").count(),
+        2,
+        "expected one code block:\n{md}"
+    );
+    assert!(md.contains("{ \"key\": !!invalid }"));
+    assert!(md.contains("^^^^^^^^^ syntax error here"));
+}
+
+#[test]
+fn test_markdown_omits_block_without_source() {
+    let md = io_report(ErrorKind::NotFound).to_markdown();
+    assert!(!md.contains("```"));
+}
+
+#[test]
+fn test_markdown_lists_history() {
+    let md = config_report().to_markdown();
+    assert!(md.contains("### History\n\n"));
+    assert!(md.contains("- The application cannot proceed without a valid config.\n"));
+}
+
+#[test]
+fn test_markdown_has_no_ansi_codes() {
+    assert!(!config_report().to_markdown().contains('\u{1b}'));
+}
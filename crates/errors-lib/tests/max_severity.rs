@@ -0,0 +1,58 @@
+/*
+ * Integration tests for severity aggregated over the report tree.
+ */
+
+mod common;
+
+use std::io;
+
+use common::{config_parse_error, config_report, two_level_report};
+use errors_lib::{
+    DynLibReport, ReportExt,
+    miette::{Diagnostic, Severity},
+    rootcause::Report,
+};
+use tracing::Level;
+
+#[test]
+fn test_warning_only_tree_stays_a_warning() {
+    let report = config_report()
+        .with_severity(Severity::Warning)
+        .change_context(config_parse_error())
+        .with_severity(Severity::Warning);
+
+    assert_eq!(report.max_severity(), Severity::Warning);
+    let api_err = report.to_api_error();
+    assert_eq!(api_err.severity.as_deref(), Some("warning"));
+    assert_eq!(api_err.level(), Level::WARN);
+}
+
+#[test]
+fn test_error_child_outranks_a_downgraded_top() {
+    // The top-level node is downgraded; the network timeout under it is not.
+    let report = two_level_report().with_severity(Severity::Warning);
+
+    assert_eq!(report.severity(), Some(Severity::Warning));
+    assert_eq!(report.max_severity(), Severity::Error);
+    let api_err = report.to_api_error();
+    assert_eq!(api_err.severity.as_deref(), Some("error"));
+    assert_eq!(api_err.level(), Level::ERROR);
+}
+
+#[test]
+fn test_report_without_diagnostics_defaults_to_error() {
+    let report = DynLibReport::from(Report::new(io::Error::other("disk on fire")).into_dynamic());
+
+    assert_eq!(report.max_severity(), Severity::Error);
+    let api_err = report.to_api_error();
+    assert_eq!(api_err.severity, None);
+    assert_eq!(api_err.level(), Level::ERROR);
+}
+
+#[test]
+fn test_message_only_report_is_advice() {
+    let report = DynLibReport::from(Report::new("cache warmed late".to_string()).into_dynamic());
+
+    assert_eq!(report.max_severity(), Severity::Advice);
+    assert_eq!(report.to_api_error().severity.as_deref(), Some("advice"));
+}
@@ -0,0 +1,78 @@
+/*
+ * Integration tests for per-code error metrics (`metrics` feature).
+ */
+
+#![cfg(feature = "metrics")]
+
+mod common;
+
+use common::{config_report, io_report};
+use errors_lib::{
+    ReportExt,
+    metrics::{ERROR_HISTORY_LENGTH, ERRORS_TOTAL, describe},
+};
+use metrics::{SharedString, Unit};
+use metrics_util::{
+    CompositeKey, MetricKind,
+    debugging::{DebugValue, DebuggingRecorder},
+};
+
+/// The labels of a metric key, sorted.
+fn labels(key: &metrics::Key) -> Vec<(String, String)> {
+    let mut labels: Vec<_> = key
+        .labels()
+        .map(|label| (label.key().to_owned(), label.value().to_owned()))
+        .collect();
+    labels.sort();
+    labels
+}
+
+type Snapshot = Vec<(CompositeKey, Option<Unit>, Option<SharedString>, DebugValue)>;
+
+fn counter(snapshot: &Snapshot, code: &str) -> Option<u64> {
+    snapshot.iter().find_map(|(key, _, _, value)| {
+        let matches = key.kind() == MetricKind::Counter
+            && key.key().name() == ERRORS_TOTAL
+            && labels(key.key())
+                == [
+                    ("code".to_owned(), code.to_owned()),
+                    ("severity".to_owned(), "error".to_owned()),
+                ];
+        match value {
+            DebugValue::Counter(count) if matches => Some(*count),
+            _ => None,
+        }
+    })
+}
+
+#[test]
+fn test_conversions_increment_the_labelled_counter() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+
+    metrics::with_local_recorder(&recorder, || {
+        describe();
+        let _ = config_report().to_api_error();
+        let _ = config_report().to_api_error();
+        let _ = io_report(std::io::ErrorKind::NotFound).to_api_error();
+    });
+
+    // One snapshot: taking it drains the histograms.
+    let snapshot = snapshotter.snapshot().into_vec();
+    assert_eq!(counter(&snapshot, "config::invalid_format"), Some(2));
+    assert_eq!(counter(&snapshot, "io::error"), Some(1));
+
+    let (_, unit, description, value) = snapshot
+        .iter()
+        .find(|(key, ..)| {
+            key.key().name() == ERROR_HISTORY_LENGTH
+                && labels(key.key()) == [("code".to_owned(), "config::invalid_format".to_owned())]
+        })
+        .expect("history histogram missing");
+    assert_eq!(*unit, Some(Unit::Count));
+    assert!(description.is_some());
+    match value {
+        DebugValue::Histogram(samples) => assert_eq!(samples.len(), 2),
+        other => panic!("unexpected value: {other:?}"),
+    }
+}
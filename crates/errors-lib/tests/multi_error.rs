@@ -0,0 +1,85 @@
+/*
+ * Integration tests for joining independent failures with MultiError.
+ */
+
+mod common;
+
+use common::{TestError, config_report};
+use errors_lib::{LibReport, MultiError, ReportExt, rootcause::Report};
+use miette::Diagnostic;
+
+fn timeout(seconds: u64) -> LibReport<TestError> {
+    Report::new(TestError::NetworkTimeout {
+        timeout: seconds,
+    })
+    .into()
+}
+
+fn joined() -> LibReport<MultiError<TestError>> {
+    LibReport::join(vec![timeout(10), timeout(20), timeout(30)]).unwrap()
+}
+
+#[test]
+fn test_join_puts_every_report_under_one_root() {
+    let report = joined();
+
+    assert_eq!(report.0.iter_reports().count(), 4);
+    assert_eq!(report.0.children().len(), 3);
+    assert_eq!(report.as_ref().to_string(), "3 errors occurred");
+}
+
+#[test]
+fn test_history_lists_every_child() {
+    let api_err = joined().to_api_error();
+    let history: Vec<_> = api_err.history.iter().map(|f| f.message.as_str()).collect();
+
+    for seconds in [10, 20, 30] {
+        let title = format!("Network timeout after {seconds}s");
+        assert!(history.contains(&title.as_str()), "{history:?}");
+    }
+    assert_eq!(api_err.title, "3 errors occurred");
+    assert!(api_err.retryable);
+}
+
+#[test]
+fn test_related_keeps_child_diagnostics() {
+    let report = joined();
+    let codes: Vec<_> = report
+        .related()
+        .unwrap()
+        .map(|d| d.code().unwrap().to_string())
+        .collect();
+
+    assert_eq!(codes, ["network::timeout"; 3]);
+    assert_eq!(report.as_ref().len(), 3);
+}
+
+#[test]
+fn test_render_shows_each_child() {
+    let rendered = LibReport::join(vec![config_report(), timeout(5)])
+        .unwrap()
+        .render_pretty(false);
+
+    assert!(rendered.contains("2 errors occurred"), "{rendered}");
+    assert!(
+        rendered.contains("Failed to parse config at config.json"),
+        "{rendered}"
+    );
+    assert!(rendered.contains("Network timeout after 5s"), "{rendered}");
+    assert_eq!(
+        rendered.matches("× ● Network timeout after 5s").count(),
+        1,
+        "{rendered}"
+    );
+}
+
+#[test]
+fn test_joined_errors_are_related_not_a_source() {
+    assert!(joined().diagnostic_source().is_none());
+    assert!(std::error::Error::source(&joined()).is_none());
+}
+
+#[test]
+fn test_join_of_nothing_is_none() {
+    assert!(LibReport::<MultiError<TestError>>::join(Vec::new()).is_none());
+}
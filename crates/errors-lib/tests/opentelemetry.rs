@@ -0,0 +1,86 @@
+/*
+ * Integration tests for OpenTelemetry log records (`opentelemetry`
+ * feature).
+ */
+
+#![cfg(feature = "opentelemetry")]
+
+mod common;
+
+use std::collections::HashMap;
+
+use common::{config_report, two_level_report};
+use errors_lib::{ReportExt, miette::Severity as DiagnosticSeverity};
+use opentelemetry::logs::{AnyValue, Logger, LoggerProvider, Severity};
+use opentelemetry_sdk::logs::{InMemoryLogExporter, SdkLogRecord, SdkLoggerProvider};
+
+/// A provider exporting into memory, and the exporter to read back from.
+fn in_memory() -> (SdkLoggerProvider, InMemoryLogExporter) {
+    let exporter = InMemoryLogExporter::default();
+    let provider = SdkLoggerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    (provider, exporter)
+}
+
+fn emitted(exporter: &InMemoryLogExporter) -> Vec<SdkLogRecord> {
+    exporter
+        .get_emitted_logs()
+        .unwrap()
+        .into_iter()
+        .map(|log| log.record)
+        .collect()
+}
+
+fn attributes(record: &SdkLogRecord) -> HashMap<String, AnyValue> {
+    record
+        .attributes_iter()
+        .map(|(key, value)| (key.to_string(), value.clone()))
+        .collect()
+}
+
+#[test]
+fn test_record_carries_title_severity_and_attributes() {
+    let (provider, exporter) = in_memory();
+    let logger = provider.logger("errors-lib");
+    logger.emit(config_report().to_opentelemetry_event(&logger));
+
+    let records = emitted(&exporter);
+    let record = &records[0];
+
+    assert_eq!(record.severity_text(), Some("ERROR"));
+    assert_eq!(record.severity_number(), Some(Severity::Error));
+    assert_eq!(
+        record.body(),
+        Some(&AnyValue::from("Failed to parse config at config.json"))
+    );
+
+    let attributes = attributes(record);
+    assert_eq!(attributes["code"], AnyValue::from("config::invalid_format"));
+    assert!(attributes.contains_key("docs_url"));
+    assert!(attributes.contains_key("git_hash"));
+    assert!(matches!(
+        &attributes["correlation_id"],
+        AnyValue::String(id) if id.as_str().len() == 8
+    ));
+    assert!(matches!(
+        &attributes["history"],
+        AnyValue::ListAny(history) if !history.is_empty()
+    ));
+}
+
+#[test]
+fn test_warning_maps_to_warn_severity() {
+    let (provider, exporter) = in_memory();
+    let logger = provider.logger("errors-lib");
+    let warning = config_report().with_severity(DiagnosticSeverity::Warning);
+    logger.emit(warning.to_opentelemetry_event(&logger));
+    // A downgraded top over an error child is still an error.
+    let mixed = two_level_report().with_severity(DiagnosticSeverity::Warning);
+    logger.emit(mixed.to_opentelemetry_event(&logger));
+
+    let records = emitted(&exporter);
+    assert_eq!(records[0].severity_text(), Some("WARN"));
+    assert_eq!(records[0].severity_number(), Some(Severity::Warn));
+    assert_eq!(records[1].severity_text(), Some("ERROR"));
+}
@@ -0,0 +1,56 @@
+/*
+ * Integration tests for OpenTelemetry trace and span IDs (`otel` feature).
+ */
+
+#![cfg(feature = "otel")]
+
+mod common;
+
+use common::{LogBuffer, config_report};
+use errors_lib::ReportExt;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use serde_json::Value;
+use tracing_subscriber::{fmt, layer::SubscriberExt};
+
+fn is_hex(id: &str, len: usize) -> bool {
+    id.len() == len && id.bytes().all(|b| b.is_ascii_hexdigit()) && id.bytes().any(|b| b != b'0')
+}
+
+#[test]
+fn test_ids_come_from_the_active_span() {
+    let provider = SdkTracerProvider::builder().build();
+    let buffer = LogBuffer::default();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("errors-lib-test")))
+        .with(fmt::layer().json().with_writer(buffer.clone()));
+
+    let api_err = tracing::subscriber::with_default(subscriber, || {
+        let span = tracing::info_span!("handle_request");
+        let _entered = span.enter();
+        config_report().to_api_error()
+    });
+
+    let trace_id = api_err.trace_id.as_deref().expect("trace_id missing");
+    let span_id = api_err.span_id.as_deref().expect("span_id missing");
+    assert!(is_hex(trace_id, 32), "bad trace_id: {trace_id}");
+    assert!(is_hex(span_id, 16), "bad span_id: {span_id}");
+
+    let event: Value = serde_json::from_str(buffer.contents().lines().next().unwrap()).unwrap();
+    assert_eq!(event["fields"]["trace_id"], trace_id);
+    assert_eq!(event["fields"]["span_id"], span_id);
+
+    let json = serde_json::to_value(&api_err).unwrap();
+    assert_eq!(json["trace_id"], trace_id);
+}
+
+#[test]
+fn test_ids_are_absent_outside_a_span() {
+    let api_err = config_report().to_api_error();
+    assert_eq!(api_err.trace_id, None);
+    assert_eq!(api_err.span_id, None);
+
+    let json = serde_json::to_value(&api_err).unwrap();
+    assert!(json.get("trace_id").is_none());
+    assert!(json.get("span_id").is_none());
+}
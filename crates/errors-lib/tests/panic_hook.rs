@@ -0,0 +1,52 @@
+/*
+ * Integration test for the panic-to-ApiError hook.
+ *
+ * Kept in its own test binary, as a single test: the panic hook is
+ * process-wide.
+ */
+
+mod common;
+
+use std::panic;
+
+use common::LogBuffer;
+use errors_lib::{
+    BufferSink, ErrorSinkRegistry, PANIC_CODE, init_logging_with_writer, install_panic_to_api_error,
+};
+use serde_json::Value;
+
+#[test]
+fn test_panic_is_logged_as_api_error() {
+    let default_hook = panic::take_hook();
+    install_panic_to_api_error();
+    let sink = BufferSink::new(4);
+    ErrorSinkRegistry::register(sink.clone());
+
+    let buffer = LogBuffer::default();
+    let result = {
+        let _guard = init_logging_with_writer(buffer.clone(), "error");
+        panic::catch_unwind(|| panic!("disk quota exceeded for {}", "/var/data"))
+    };
+    drop(panic::take_hook());
+    panic::set_hook(default_hook);
+    assert!(result.is_err());
+
+    let logs = buffer.contents();
+    let event: Value = serde_json::from_str(logs.lines().next().unwrap()).unwrap();
+    let fields = &event["fields"];
+    assert_eq!(event["level"], "ERROR");
+    assert_eq!(fields["title"], "disk quota exceeded for /var/data");
+    assert_eq!(fields["code"], PANIC_CODE);
+    assert!(
+        fields["location"]
+            .as_str()
+            .unwrap()
+            .starts_with("crates/errors-lib/tests/panic_hook.rs:"),
+        "unexpected logs: {logs}"
+    );
+
+    // The panic went through the sink registry, not just tracing.
+    let emitted = sink.errors();
+    assert_eq!(emitted.len(), 1);
+    assert_eq!(emitted[0].title, "disk quota exceeded for /var/data");
+}
@@ -0,0 +1,57 @@
+/*
+ * Integration test for the panic hook alongside a panicking sink.
+ *
+ * Kept in its own test binary, as a single test: the panic hook and the
+ * sink registry are process-wide.
+ */
+
+mod common;
+
+use std::panic;
+
+use common::config_report;
+use errors_lib::{
+    ApiError, BufferSink, ErrorSink, ErrorSinkRegistry, PANIC_CODE, ReportExt,
+    install_panic_to_api_error,
+};
+
+/// Panics on every error but the hook's own.
+struct PanicsOnReports;
+
+impl ErrorSink for PanicsOnReports {
+    fn emit(&self, error: &ApiError) {
+        assert!(
+            error.code.as_deref() == Some(PANIC_CODE),
+            "sink failed on {}",
+            error.title
+        );
+    }
+}
+
+#[test]
+fn test_a_panicking_sink_is_skipped_with_the_hook_installed() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    install_panic_to_api_error();
+    let buffer = BufferSink::new(4);
+    ErrorSinkRegistry::register(PanicsOnReports);
+    ErrorSinkRegistry::register(buffer.clone());
+
+    // The sink's panic reaches the hook, which leaves the sinks alone; the
+    // registry catches it and the next sink still runs.
+    let api_err = config_report().to_api_error();
+    let emitted = buffer.errors();
+    assert_eq!(emitted.len(), 1);
+    assert_eq!(emitted[0].correlation_id, api_err.correlation_id);
+
+    // A panic outside the sinks still goes to every sink.
+    let result = panic::catch_unwind(|| panic!("worker crashed"));
+    drop(panic::take_hook());
+    panic::set_hook(default_hook);
+    assert!(result.is_err());
+
+    let emitted = buffer.errors();
+    assert_eq!(emitted.len(), 2);
+    assert_eq!(emitted[1].code.as_deref(), Some(PANIC_CODE));
+    assert_eq!(emitted[1].title, "worker crashed");
+}
@@ -0,0 +1,186 @@
+/*
+ * Integration tests for the find_context / contains_code / code_matches
+ * query helpers.
+ */
+
+mod common;
+
+use std::io;
+
+use common::{
+    TestError, config_parse_error, config_report, io_report, three_level_report, two_level_report,
+};
+use errors_lib::{DynLibReport, LibReport, MultiError, rootcause::Report};
+
+#[test]
+fn test_find_context_reaches_nested_nodes() {
+    let report = three_level_report();
+
+    let io_err = report.find_context::<io::Error>().expect("io root");
+    assert_eq!(io_err.kind(), io::ErrorKind::TimedOut);
+    assert!(matches!(
+        report.find_context::<TestError>(),
+        Some(TestError::ConfigParseError { .. })
+    ));
+}
+
+#[test]
+fn test_contexts_yield_parent_then_child() {
+    let report = two_level_report();
+
+    let displays: Vec<_> = report.contexts().map(ToString::to_string).collect();
+    assert_eq!(displays, [
+        "Failed to parse config at config.json",
+        "Network timeout after 30s"
+    ]);
+    let codes: Vec<_> = report
+        .contexts()
+        .filter_map(|ctx| ctx.code().map(|code| code.to_string()))
+        .collect();
+    assert_eq!(codes, ["config::invalid_format", "network::timeout"]);
+}
+
+#[test]
+fn test_contexts_skip_untyped_nodes() {
+    assert_eq!(three_level_report().contexts().count(), 2);
+}
+
+#[test]
+fn test_find_error_looks_inside_source_chains() {
+    let report = io_report(io::ErrorKind::NotFound);
+    assert!(report.find_context::<io::Error>().is_none());
+    let io_err = report.find_error::<io::Error>().expect("io source");
+    assert_eq!(io_err.kind(), io::ErrorKind::NotFound);
+
+    let wrapped = report.change_context(config_parse_error());
+    let io_err = wrapped
+        .find_error::<io::Error>()
+        .expect("io source below parent");
+    assert_eq!(io_err.kind(), io::ErrorKind::NotFound);
+
+    let dynamic = io_report(io::ErrorKind::NotFound).into_dynamic();
+    assert!(dynamic.find_error::<io::Error>().is_some());
+    assert!(config_report().find_error::<io::Error>().is_none());
+}
+
+#[test]
+fn test_any_context_applies_the_predicate() {
+    let report = three_level_report();
+
+    assert!(report.any_context(|err: &TestError| matches!(err, TestError::NetworkTimeout { .. })));
+    assert!(!report.any_context(|err: &io::Error| err.kind() == io::ErrorKind::NotFound));
+}
+
+#[test]
+fn test_codes_lists_every_typed_node() {
+    let report = three_level_report();
+
+    assert_eq!(report.codes(), [
+        "config::invalid_format",
+        "network::timeout"
+    ]);
+    assert!(report.contains_code("network::timeout"));
+}
+
+#[test]
+fn test_codes_include_multi_error_members() {
+    let timeout = |seconds| -> LibReport<TestError> {
+        Report::new(TestError::NetworkTimeout {
+            timeout: seconds,
+        })
+        .into()
+    };
+    let joined =
+        LibReport::<MultiError<TestError>>::join(vec![timeout(10), config_report(), timeout(20)])
+            .unwrap();
+
+    assert_eq!(joined.codes(), [
+        "network::timeout",
+        "config::invalid_format"
+    ]);
+}
+
+#[test]
+fn test_no_match() {
+    let report = config_report();
+
+    assert!(report.find_context::<io::Error>().is_none());
+    assert!(!report.any_context(|_: &io::Error| true));
+    assert!(!report.contains_code("network::timeout"));
+}
+
+#[test]
+fn test_dynamic_report_finds_io_errors() {
+    let report = DynLibReport::new(
+        Report::new(io::Error::new(io::ErrorKind::NotFound, "gone")).into_dynamic(),
+    );
+
+    assert_eq!(
+        report.find_context::<io::Error>().map(io::Error::kind),
+        Some(io::ErrorKind::NotFound)
+    );
+    assert!(report.find_context::<TestError>().is_none());
+    assert!(report.codes().is_empty());
+}
+
+#[test]
+fn test_dynamic_report_keeps_typed_codes() {
+    let report = three_level_report().into_dynamic();
+
+    assert!(report.any_context(|err: &io::Error| err.kind() == io::ErrorKind::TimedOut));
+    assert_eq!(report.codes(), [
+        "config::invalid_format",
+        "network::timeout"
+    ]);
+    assert!(!report.contains_code("io::error"));
+}
+
+#[test]
+fn test_code_matches_exact_and_wildcard() {
+    let report = two_level_report();
+
+    assert!(report.code_matches("config::invalid_format"));
+    assert!(report.code_matches("config::*"));
+    assert!(!report.code_matches("config::invalid"));
+    assert!(!report.code_matches("network::*"));
+    // Only the top-level code is checked.
+    assert!(!report.code_matches("network::timeout"));
+}
+
+#[test]
+fn test_code_matches_rejects_longer_patterns() {
+    let report = config_report();
+
+    assert!(!report.code_matches("config::invalid_format::strict"));
+    assert!(!report.code_matches("config::invalid_format::*"));
+    assert!(!report.code_matches(""));
+}
+
+#[test]
+fn test_code_matches_trailing_colons_and_stars() {
+    let report = config_report();
+
+    assert!(report.code_matches("config::"));
+    assert!(report.code_matches("config*"));
+    assert!(report.code_matches("config::invalid*"));
+    assert!(report.code_matches("*"));
+    // Only `::*` and `::` stop at a namespace boundary.
+    assert!(!report.code_matches("conf::*"));
+    assert!(!report.code_matches("conf::"));
+    assert!(!report.code_matches("configuration*"));
+}
+
+#[test]
+fn test_code_matches_nothing_without_a_code() {
+    let report = DynLibReport::new(
+        Report::new(io::Error::new(io::ErrorKind::NotFound, "gone")).into_dynamic(),
+    );
+
+    assert!(!report.code_matches("*"));
+    assert!(!report.code_matches("io::*"));
+    assert!(
+        three_level_report()
+            .into_dynamic()
+            .code_matches("config::*")
+    );
+}
@@ -0,0 +1,93 @@
+/*
+ * Integration tests for PII redaction of ApiError.
+ */
+
+use errors_lib::{
+    ApiErrorOptions, LibReport, REDACTED, RedactionConfig, ReportExt, rootcause::Report,
+};
+use miette::Diagnostic;
+use regex::Regex;
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu, Diagnostic)]
+enum AccountError {
+    #[snafu(display("User {email} not found"))]
+    #[diagnostic(code(account::not_found), help("Check whether {email} signed up."))]
+    UserNotFound { email: String },
+}
+
+fn report() -> LibReport<AccountError> {
+    Report::new(AccountError::UserNotFound {
+        email: "john@example.com".into(),
+    })
+    .attach("session 3f2b8c1e-9a4d-4e6f-8b2a-1c5d7e9f0a3b expired")
+    .into()
+}
+
+#[test]
+fn test_email_pattern_redacts_title() {
+    let config = RedactionConfig::new(vec![
+        Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap(),
+    ]);
+    let api_err = report().to_api_error().redact(&config);
+
+    assert_eq!(api_err.title, "User [REDACTED] not found");
+    assert_eq!(
+        api_err.help.as_deref(),
+        Some("Check whether [REDACTED] signed up.")
+    );
+}
+
+#[test]
+fn test_default_pii_redacts_history() {
+    let api_err = report()
+        .to_api_error()
+        .redact(&RedactionConfig::default_pii());
+
+    let expected = format!("session {REDACTED} expired");
+    assert!(api_err.history.iter().any(|f| f.message == expected));
+    assert!(!api_err.title.contains("john@example.com"));
+}
+
+#[test]
+fn test_redact_leaves_other_fields_untouched() {
+    let original = report().to_api_error();
+    let redacted = original.redact(&RedactionConfig::default_pii());
+
+    assert_eq!(redacted.correlation_id, original.correlation_id);
+    assert_eq!(redacted.code, original.code);
+    assert_eq!(original.title, "User john@example.com not found");
+}
+
+#[test]
+fn test_redact_covers_the_whole_serialized_error() {
+    let report: LibReport<AccountError> = Report::new(AccountError::UserNotFound {
+        email: "jane@example.com".into(),
+    })
+    .attach("owner john@example.com")
+    .context(AccountError::UserNotFound {
+        email: "john@example.com".into(),
+    })
+    .attach("session 3f2b8c1e-9a4d-4e6f-8b2a-1c5d7e9f0a3b expired")
+    .into();
+    let report = report.attach_field("requested_by", "ops@example.com");
+
+    let api_err = report
+        .to_api_error_with_options(ApiErrorOptions::default().include_tree(true))
+        .redact(&RedactionConfig::default_pii());
+    let json = serde_json::to_string(&api_err).unwrap();
+
+    assert!(api_err.tree.is_some());
+    assert_eq!(api_err.fields["requested_by"], REDACTED);
+    assert!(!json.contains("@example.com"), "{json}");
+    assert!(!json.contains("3f2b8c1e"), "{json}");
+}
+
+#[test]
+fn test_empty_config_is_a_no_op() {
+    let original = report().to_api_error();
+    let redacted = original.redact(&RedactionConfig::default());
+
+    assert_eq!(redacted.title, original.title);
+    assert_eq!(redacted.help, original.help);
+}
@@ -0,0 +1,59 @@
+/*
+ * Integration test for process-wide redaction on the way to the sinks.
+ *
+ * Kept in its own test binary, as a single test: the redaction config and
+ * the sink registry are process-wide.
+ */
+
+mod common;
+
+use common::LogBuffer;
+use errors_lib::{
+    BufferSink, ErrorSinkRegistry, LibReport, REDACTED, RedactionConfig, ReportExt,
+    clear_sink_redaction, init_logging_with_writer, rootcause::Report, set_sink_redaction,
+};
+use miette::Diagnostic;
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu, Diagnostic)]
+enum AccountError {
+    #[snafu(display("User {email} not found"))]
+    #[diagnostic(code(account::not_found))]
+    UserNotFound { email: String },
+}
+
+fn report() -> LibReport<AccountError> {
+    Report::new(AccountError::UserNotFound {
+        email: "john@example.com".into(),
+    })
+    .attach("lookup by john@example.com")
+    .into()
+}
+
+#[test]
+fn test_sinks_and_logs_see_redacted_errors() {
+    set_sink_redaction(RedactionConfig::default_pii());
+    let sink = BufferSink::new(4);
+    ErrorSinkRegistry::register(sink.clone());
+
+    let buffer = LogBuffer::default();
+    let api_err = {
+        let _guard = init_logging_with_writer(buffer.clone(), "error");
+        report().to_api_error()
+    };
+
+    // The caller still gets the error as built.
+    assert_eq!(api_err.title, "User john@example.com not found");
+
+    let logs = buffer.contents();
+    assert!(!logs.contains("john@example.com"), "unredacted logs: {logs}");
+    assert!(logs.contains(REDACTED), "unexpected logs: {logs}");
+
+    let emitted = sink.errors();
+    assert_eq!(emitted[0].title, format!("User {REDACTED} not found"));
+    assert_eq!(emitted[0].correlation_id, api_err.correlation_id);
+
+    clear_sink_redaction();
+    report().emit();
+    assert_eq!(sink.errors()[1].title, "User john@example.com not found");
+}
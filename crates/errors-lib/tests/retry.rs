@@ -0,0 +1,46 @@
+/*
+ * Integration tests for retry classification (ReportExt::is_retryable).
+ */
+
+mod common;
+
+use std::io::ErrorKind;
+
+use common::{TestError, config_report, io_report, three_level_report, two_level_report};
+use errors_lib::{LibReport, ReportExt, rootcause::Report};
+
+#[test]
+fn test_network_timeout_is_retryable() {
+    let report: LibReport<TestError> = Report::new(TestError::NetworkTimeout {
+        timeout: 30,
+    })
+    .into();
+    assert!(report.is_retryable());
+    assert!(report.to_api_error().retryable);
+}
+
+#[test]
+fn test_config_parse_error_is_not_retryable() {
+    let report = config_report();
+    assert!(!report.is_retryable());
+    assert!(!report.to_api_error().retryable);
+}
+
+#[test]
+fn test_io_timed_out_source_is_retryable() {
+    assert!(io_report(ErrorKind::TimedOut).is_retryable());
+    assert!(io_report(ErrorKind::WouldBlock).is_retryable());
+    assert!(!io_report(ErrorKind::NotFound).is_retryable());
+}
+
+#[test]
+fn test_transient_child_makes_chain_retryable() {
+    assert!(two_level_report().is_retryable());
+    assert!(three_level_report().into_dynamic().is_retryable());
+}
+
+#[test]
+fn test_untyped_io_root_is_retryable() {
+    let report = Report::new(std::io::Error::from(ErrorKind::TimedOut)).into_dynamic();
+    assert!(errors_lib::DynLibReport::new(report).is_retryable());
+}
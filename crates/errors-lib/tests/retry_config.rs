@@ -0,0 +1,26 @@
+/*
+ * Integration test for configuring the transient code set.
+ *
+ * Kept in its own test binary: the set is process-wide, and changing it
+ * would race the default-set assertions in retry.rs.
+ */
+
+mod common;
+
+use common::{config_report, two_level_report};
+use errors_lib::{DEFAULT_TRANSIENT_CODES, ReportExt, set_transient_codes, transient_codes};
+
+#[test]
+fn test_set_transient_codes_replaces_defaults() {
+    assert_eq!(transient_codes(), DEFAULT_TRANSIENT_CODES);
+    assert!(!config_report().is_retryable());
+
+    set_transient_codes(["config::invalid_format"]);
+    assert!(config_report().is_retryable());
+
+    // network::timeout is no longer in the set
+    set_transient_codes(Vec::<String>::new());
+    assert!(!two_level_report().is_retryable());
+
+    set_transient_codes(DEFAULT_TRANSIENT_CODES.iter().copied());
+}
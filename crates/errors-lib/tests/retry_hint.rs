@@ -0,0 +1,64 @@
+/*
+ * Integration tests for RetryHint attachments.
+ */
+
+mod common;
+
+use std::time::Duration;
+
+use common::{config_parse_error, config_report};
+use errors_lib::{ReportExt, RetryHint};
+
+const HINT: RetryHint = RetryHint {
+    delay: Duration::from_millis(1500),
+    max_attempts: 3,
+};
+
+#[test]
+fn test_hint_fills_api_error_fields() {
+    let api_err = config_report().with_retry_hint(HINT).to_api_error();
+    assert_eq!(api_err.retry_after_ms, Some(1500));
+    assert_eq!(api_err.max_retries, Some(3));
+    assert!(api_err.retryable);
+
+    let json = serde_json::to_value(&api_err).unwrap();
+    assert_eq!(json["retry_after_ms"], 1500);
+    assert_eq!(json["max_retries"], 3);
+}
+
+#[test]
+fn test_fields_are_absent_without_a_hint() {
+    let api_err = config_report().to_api_error();
+    assert_eq!(api_err.retry_after_ms, None);
+    assert_eq!(api_err.max_retries, None);
+    assert!(!api_err.retryable);
+
+    let json = serde_json::to_value(&api_err).unwrap();
+    assert!(json.get("retry_after_ms").is_none());
+    assert!(json.get("max_retries").is_none());
+}
+
+#[test]
+fn test_hint_below_a_new_context_is_found() {
+    let report = config_report()
+        .with_retry_hint(HINT)
+        .change_context(config_parse_error());
+
+    assert!(report.is_retryable());
+    let api_err = report.to_api_error();
+    assert_eq!(api_err.retry_after_ms, Some(1500));
+    assert_eq!(api_err.max_retries, Some(3));
+}
+
+#[test]
+fn test_latest_hint_wins() {
+    let first = RetryHint {
+        delay: Duration::from_secs(45),
+        max_attempts: 1,
+    };
+    let api_err = config_report()
+        .with_retry_hint(first)
+        .with_retry_hint(HINT)
+        .to_api_error();
+    assert_eq!(api_err.max_retries, Some(3));
+}
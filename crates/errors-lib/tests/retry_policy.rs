@@ -0,0 +1,86 @@
+/*
+ * Integration tests for per-variant retry policies.
+ */
+
+mod common;
+
+use std::time::Duration;
+
+use common::{TestError, config_parse_error, config_report, two_level_report};
+use errors_lib::{LibReport, RetryPolicy, Retryable, rootcause::Report};
+
+impl Retryable for TestError {
+    fn retry_policy(&self) -> RetryPolicy {
+        match self {
+            Self::ConfigParseError {
+                ..
+            } => RetryPolicy::Never,
+            Self::NetworkTimeout {
+                timeout,
+            } => RetryPolicy::After(Duration::from_secs(*timeout)),
+            Self::Io {
+                ..
+            } => RetryPolicy::Immediate,
+        }
+    }
+}
+
+fn timeout_over_config() -> LibReport<TestError> {
+    Report::new(config_parse_error())
+        .context(TestError::NetworkTimeout {
+            timeout: 5,
+        })
+        .into()
+}
+
+#[test]
+fn test_single_node_uses_its_own_policy() {
+    assert_eq!(config_report().retry_policy(), RetryPolicy::Never);
+}
+
+#[test]
+fn test_retryable_root_with_non_retryable_child_is_retryable() {
+    assert_eq!(
+        timeout_over_config().retry_policy(),
+        RetryPolicy::After(Duration::from_secs(5))
+    );
+}
+
+#[test]
+fn test_retryable_child_makes_the_chain_retryable() {
+    assert_eq!(
+        two_level_report().retry_policy(),
+        RetryPolicy::After(Duration::from_secs(30))
+    );
+}
+
+#[test]
+fn test_merge_prefers_the_longest_delay() {
+    let short = RetryPolicy::After(Duration::from_secs(1));
+    let long = RetryPolicy::After(Duration::from_secs(9));
+
+    assert_eq!(short.merge(long), long);
+    assert_eq!(long.merge(short), long);
+    assert_eq!(short.merge(RetryPolicy::Immediate), short);
+    assert_eq!(
+        RetryPolicy::Immediate.merge(RetryPolicy::Never),
+        RetryPolicy::Immediate
+    );
+    assert_eq!(RetryPolicy::Immediate.merge(short), short);
+    assert_eq!(
+        RetryPolicy::Never.merge(RetryPolicy::Immediate),
+        RetryPolicy::Immediate
+    );
+}
+
+#[test]
+fn test_api_error_carries_retry_after_ms() {
+    let api_err = timeout_over_config().to_api_error_with_retry_policy();
+    assert_eq!(api_err.retry_after_ms, Some(5000));
+    assert!(api_err.retryable);
+
+    let api_err = config_report().to_api_error_with_retry_policy();
+    assert_eq!(api_err.retry_after_ms, None);
+    let json = serde_json::to_value(&api_err).unwrap();
+    assert!(json.get("retry_after_ms").is_none());
+}
@@ -0,0 +1,145 @@
+/*
+ * Integration tests for Sentry event capture (`sentry` feature).
+ */
+
+#![cfg(feature = "sentry")]
+
+mod common;
+
+use common::{config_report, two_level_report};
+use errors_lib::{ReportExt, capture_lib_report, capture_to_sentry};
+use sentry::{Hub, Level, test::with_captured_events};
+
+#[test]
+fn test_capture_sends_one_event_with_tags() {
+    let api_err = config_report().to_api_error();
+
+    let mut event_id = None;
+    let events = with_captured_events(|| {
+        event_id = Some(api_err.capture_to_sentry());
+    });
+
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(Some(event.event_id), event_id);
+    assert_eq!(event.message.as_deref(), Some(api_err.title.as_str()));
+    assert_eq!(
+        event.tags.get("code").map(String::as_str),
+        Some("config::invalid_format")
+    );
+    assert_eq!(
+        event.tags.get("correlation_id"),
+        Some(&api_err.correlation_id)
+    );
+    assert_eq!(event.level, Level::Error);
+}
+
+#[test]
+fn test_history_becomes_breadcrumbs() {
+    let api_err = two_level_report().to_api_error();
+    let event = api_err.to_sentry_event();
+
+    let crumbs: Vec<_> = event
+        .breadcrumbs
+        .iter()
+        .filter_map(|b| b.message.as_deref())
+        .collect();
+    let history: Vec<_> = api_err.history.iter().map(|f| f.message.as_str()).collect();
+    assert_eq!(crumbs, history);
+}
+
+#[test]
+fn test_warning_severity_maps_to_warning_level() {
+    let api_err = config_report()
+        .with_severity(miette::Severity::Warning)
+        .to_api_error();
+
+    assert_eq!(api_err.to_sentry_event().level, Level::Warning);
+}
+
+#[test]
+fn test_capture_report_on_client_sets_title_and_fingerprint() {
+    let report = config_report();
+
+    let mut event_id = None;
+    let events = with_captured_events(|| {
+        let client = Hub::current().client().unwrap();
+        event_id = Some(capture_to_sentry(&report, &client));
+    });
+
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(Some(event.event_id), event_id);
+    assert_eq!(
+        event.message.as_deref(),
+        Some("Failed to parse config at config.json")
+    );
+    assert_eq!(event.fingerprint.as_ref(), ["config::invalid_format"]);
+    assert_eq!(event.release.as_deref(), Some(env!("GIT_HASH")));
+}
+
+#[test]
+fn test_report_event_has_one_exception_per_node() {
+    let event = two_level_report().to_sentry_event();
+
+    let chain: Vec<_> = event
+        .exception
+        .iter()
+        .map(|e| (e.ty.as_str(), e.value.as_deref().unwrap()))
+        .collect();
+    // Oldest first: the cause, then the top-level context.
+    assert_eq!(chain, [
+        ("sentry::common::TestError", "Network timeout after 30s"),
+        (
+            "sentry::common::TestError",
+            "Failed to parse config at config.json"
+        ),
+    ]);
+    assert_eq!(event.fingerprint.as_ref(), ["config::invalid_format"]);
+    assert_eq!(
+        event.tags.get("code").map(String::as_str),
+        Some("config::invalid_format")
+    );
+    assert!(event.tags.contains_key("correlation_id"));
+}
+
+#[test]
+fn test_report_event_breadcrumbs_are_the_attachments() {
+    let event = config_report().attach("retrying once").to_sentry_event();
+
+    let crumbs: Vec<_> = event
+        .breadcrumbs
+        .iter()
+        .filter_map(|b| b.message.as_deref())
+        .collect();
+    // rootcause's own location attachment comes first.
+    assert!(
+        crumbs.ends_with(&[
+            "The application cannot proceed without a valid config.",
+            "retrying once"
+        ]),
+        "{crumbs:?}"
+    );
+    assert!(!crumbs.contains(&"Failed to parse config at config.json"));
+}
+
+#[test]
+fn test_untyped_report_names_io_errors() {
+    let event = common::three_level_report()
+        .into_dynamic()
+        .to_sentry_event();
+
+    let types: Vec<_> = event.exception.iter().map(|e| e.ty.as_str()).collect();
+    assert_eq!(types.first(), Some(&"std::io::error::Error"));
+    assert!(types[1..].iter().all(|ty| *ty == "Error"), "{types:?}");
+}
+
+#[test]
+fn test_capture_lib_report_uses_the_current_hub() {
+    let events = with_captured_events(|| {
+        let _ = capture_lib_report(&two_level_report());
+    });
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].exception.len(), 2);
+}
@@ -0,0 +1,84 @@
+/*
+ * Integration tests for LibReport::with_severity.
+ */
+
+mod common;
+
+use common::{LogBuffer, TestError, config_report};
+use errors_lib::{LibReport, ReportExt, init_logging_with_writer};
+use miette::{Diagnostic, Severity};
+use tracing::Level;
+
+/// Captures tracing output emitted while `f` runs.
+fn capture_logs(f: impl FnOnce()) -> String {
+    let buffer = LogBuffer::default();
+    let guard = init_logging_with_writer(buffer.clone(), "trace");
+    f();
+    drop(guard);
+    buffer.contents()
+}
+
+fn warning_report() -> LibReport<TestError> {
+    config_report().with_severity(Severity::Warning)
+}
+
+#[test]
+fn test_override_takes_precedence_over_context() {
+    assert_eq!(config_report().severity(), None);
+    assert_eq!(warning_report().severity(), Some(Severity::Warning));
+}
+
+#[test]
+fn test_override_survives_attach_and_clone() {
+    let report = warning_report().attach("optional config, continuing");
+    assert_eq!(report.severity(), Some(Severity::Warning));
+    assert_eq!(report.into_cloneable().severity(), Some(Severity::Warning));
+}
+
+#[test]
+fn test_latest_override_wins() {
+    let report = warning_report().with_severity(Severity::Advice);
+    assert_eq!(report.severity(), Some(Severity::Advice));
+}
+
+#[test]
+fn test_warning_renders_with_warning_styling() {
+    let rendered = warning_report().render_pretty(false);
+    let header = rendered
+        .lines()
+        .find(|l| l.contains("Failed to parse config"))
+        .unwrap();
+    assert!(header.contains('⚠'), "expected warning marker: {rendered}");
+    assert!(!header.contains('×'));
+}
+
+#[test]
+fn test_api_error_carries_severity_and_level() {
+    let api_err = warning_report().to_api_error();
+    assert_eq!(api_err.severity.as_deref(), Some("warning"));
+    assert_eq!(api_err.level(), Level::WARN);
+
+    let api_err = config_report().to_api_error();
+    assert!(api_err.severity.is_none());
+    assert_eq!(api_err.level(), Level::ERROR);
+}
+
+#[test]
+fn test_warning_logs_at_warn() {
+    let logs = capture_logs(|| {
+        warning_report().to_api_error();
+    });
+    assert!(
+        logs.contains(r#""level":"WARN""#),
+        "unexpected logs: {logs}"
+    );
+    assert!(!logs.contains("ERROR"));
+
+    let logs = capture_logs(|| {
+        config_report().to_api_error();
+    });
+    assert!(
+        logs.contains(r#""level":"ERROR""#),
+        "unexpected logs: {logs}"
+    );
+}
@@ -0,0 +1,84 @@
+/*
+ * Integration tests for ApiError::size_budget.
+ */
+
+mod common;
+
+use common::{config_report, three_level_report};
+use errors_lib::{ApiError, HISTORY_TRUNCATED, ReportExt};
+use serde_json::Value;
+
+fn json_len(err: &ApiError) -> usize {
+    serde_json::to_string(err).unwrap().len()
+}
+
+#[test]
+fn test_fitting_error_is_returned_unchanged() {
+    let api_err = config_report().to_api_error();
+    let size = json_len(&api_err);
+
+    let kept = api_err.clone().size_budget(size).unwrap();
+    assert_eq!(kept.history.len(), api_err.history.len());
+    assert_eq!(kept.help, api_err.help);
+}
+
+#[test]
+fn test_over_budget_drops_oldest_history_first() {
+    let api_err = three_level_report().to_api_error();
+    let last = api_err.history.last().unwrap().message.clone();
+    // The summary goes before any history does.
+    let mut unsummarized = api_err.clone();
+    unsummarized.summary = None;
+    let budget = json_len(&unsummarized) - 1;
+
+    let trimmed = api_err.clone().size_budget(budget).unwrap_err();
+    assert!(json_len(&trimmed) <= budget);
+    assert!(trimmed.history.len() < api_err.history.len());
+    assert_eq!(trimmed.history.last().unwrap().message, last);
+    assert!(
+        trimmed
+            .help
+            .unwrap()
+            .ends_with(&format!("({HISTORY_TRUNCATED})"))
+    );
+    assert_eq!(trimmed.summary, None);
+    assert_eq!(trimmed.title, api_err.title);
+}
+
+#[test]
+fn test_title_and_help_shrink_when_history_is_not_enough() {
+    let api_err = three_level_report().to_api_error();
+    let mut bare = api_err.clone();
+    bare.title.clear();
+    bare.summary = None;
+    bare.help = None;
+    bare.history.clear();
+    let budget = json_len(&bare) + 20;
+
+    let trimmed = api_err.clone().size_budget(budget).unwrap_err();
+    let json = serde_json::to_string(&trimmed).unwrap();
+    assert!(json.len() <= budget, "{} bytes: {json}", json.len());
+    assert!(trimmed.history.is_empty());
+    assert!(trimmed.title.ends_with('…'), "{json}");
+    assert!(
+        api_err
+            .title
+            .starts_with(trimmed.title.trim_end_matches('…'))
+    );
+}
+
+#[test]
+fn test_tiny_budget_still_yields_valid_json() {
+    // 200 bytes is below what the build metadata alone takes, so this
+    // trims as far as it can and still hands back a valid error.
+    let trimmed = three_level_report()
+        .to_api_error()
+        .size_budget(200)
+        .unwrap_err();
+
+    let json = serde_json::to_string(&trimmed).unwrap();
+    let value: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["history"], Value::Array(Vec::new()));
+    assert_eq!(value["title"], "");
+    assert!(value.get("help").is_none());
+}
@@ -0,0 +1,98 @@
+/*
+ * Integration tests for Slack Block Kit messages (`slack` feature).
+ */
+
+#![cfg(feature = "slack")]
+
+mod common;
+
+use common::{config_report, two_level_report};
+use errors_lib::{ApiError, ReportExt};
+
+fn block_types(message: &serde_json::Value) -> Vec<&str> {
+    message["blocks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|block| block["type"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_message_has_header_details_history_and_context() {
+    let api_err = config_report().to_api_error();
+    let message = api_err.to_slack_message();
+
+    assert_eq!(block_types(&message), [
+        "header", "section", "section", "context"
+    ]);
+    assert_eq!(message["text"], api_err.title.as_str());
+
+    let blocks = &message["blocks"];
+    assert_eq!(blocks[0]["text"]["type"], "plain_text");
+    assert_eq!(blocks[0]["text"]["text"], api_err.title.as_str());
+
+    let details = blocks[1]["text"]["text"].as_str().unwrap();
+    assert!(details.contains("`config::invalid_format`"));
+    assert!(details.contains(api_err.help.as_deref().unwrap()));
+
+    let history = blocks[2]["text"]["text"].as_str().unwrap();
+    for frame in &api_err.history {
+        assert!(history.contains(&format!("• {}", frame.message)));
+    }
+
+    let context = blocks[3]["elements"].to_string();
+    assert!(context.contains(api_err.git_hash.as_ref()));
+    assert!(context.contains(&api_err.correlation_id));
+}
+
+#[test]
+fn test_absent_parts_are_left_out_and_long_titles_are_cut() {
+    let mut api_err = two_level_report().to_api_error();
+    api_err.code = None;
+    api_err.help = None;
+    api_err.history.clear();
+    api_err.title = "x".repeat(200);
+
+    let message = api_err.to_slack_message();
+    assert_eq!(block_types(&message), ["header", "context"]);
+
+    let header = message["blocks"][0]["text"]["text"].as_str().unwrap();
+    assert_eq!(header.chars().count(), 150);
+    assert!(header.ends_with('…'));
+}
+
+#[test]
+fn test_report_ext_builds_the_message_directly() {
+    let message = config_report().to_slack_message();
+    let api_err: ApiError = config_report().to_api_error();
+    assert_eq!(message["blocks"][0]["text"]["text"], api_err.title.as_str());
+}
+
+#[test]
+fn test_mrkdwn_control_characters_are_escaped() {
+    let mut api_err = config_report().to_api_error();
+    api_err.title = "<!channel> deploy failed".into();
+    api_err.history[0].message = "<!channel> & <@U123>".into();
+
+    let message = api_err.to_slack_message();
+    assert_eq!(message["text"], "&lt;!channel&gt; deploy failed");
+    // The header is plain text, which Slack never reads as a mention.
+    assert_eq!(message["blocks"][0]["text"]["text"], "<!channel> deploy failed");
+
+    let history = message["blocks"][2]["text"]["text"].as_str().unwrap();
+    assert!(history.contains("• &lt;!channel&gt; &amp; &lt;@U123&gt;"));
+    assert!(!message["blocks"].to_string().contains("<!"));
+}
+
+#[test]
+fn test_long_escaped_text_is_not_cut_inside_an_entity() {
+    let mut api_err = config_report().to_api_error();
+    api_err.history.truncate(1);
+    api_err.history[0].message = "<".repeat(1000);
+
+    let message = api_err.to_slack_message();
+    let history = message["blocks"][2]["text"]["text"].as_str().unwrap();
+    assert!(history.chars().count() <= 3000);
+    assert!(history.ends_with("&lt;…"), "cut mid-entity: {history}");
+}
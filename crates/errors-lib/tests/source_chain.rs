@@ -0,0 +1,59 @@
+/*
+ * Integration tests for std::error::Error::source on LibReport.
+ *
+ * Anything walking the standard source chain (anyhow, tracing-error, ...)
+ * should see the rootcause tree rather than a leaf.
+ */
+
+mod common;
+
+use std::{error::Error, io::ErrorKind};
+
+use common::{config_report, io_report, three_level_report, two_level_report};
+
+fn chain_messages(err: &dyn Error) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut current = err.source();
+    while let Some(src) = current {
+        messages.push(src.to_string());
+        current = src.source();
+    }
+    messages
+}
+
+fn chain_len(err: &dyn Error) -> usize {
+    chain_messages(err).len()
+}
+
+#[test]
+fn test_single_node_report_has_no_source() {
+    assert!(config_report().source().is_none());
+    assert_eq!(chain_len(&config_report()), 0);
+}
+
+#[test]
+fn test_source_is_first_child() {
+    let report = two_level_report();
+
+    let source = report.source().expect("source missing");
+    assert_eq!(source.to_string(), "Network timeout after 30s");
+    assert_eq!(chain_len(&report), 1);
+}
+
+#[test]
+fn test_source_walks_every_level() {
+    let report = three_level_report();
+
+    let messages = chain_messages(&report);
+    assert_eq!(messages, ["Network timeout after 30s", "connection reset"]);
+    assert_eq!(chain_len(&report), 2);
+}
+
+#[test]
+fn test_source_surfaces_context_source() {
+    let report = io_report(ErrorKind::NotFound);
+
+    let source = report.source().expect("source missing");
+    assert_eq!(source.to_string(), "config.json is unavailable");
+    assert_eq!(chain_len(&report), 1);
+}
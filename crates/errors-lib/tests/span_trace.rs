@@ -0,0 +1,57 @@
+/*
+ * Integration tests for SpanTrace capture (`span-trace` feature).
+ */
+
+#![cfg(feature = "span-trace")]
+
+mod common;
+
+use common::{LogBuffer, config_report};
+use errors_lib::{ApiError, ReportExt, init_logging_with_writer};
+use tracing_error::ErrorLayer;
+use tracing_subscriber::prelude::*;
+
+#[test]
+fn test_span_trace_lists_entered_spans_innermost_first() {
+    let subscriber = tracing_subscriber::registry().with(ErrorLayer::default());
+
+    let api_err = tracing::subscriber::with_default(subscriber, || {
+        let _request = tracing::info_span!("handle_request", id = 7).entered();
+        let _load = tracing::info_span!("load_config").entered();
+        config_report().to_api_error()
+    });
+
+    let spans = api_err.span_trace.expect("spans were active");
+    assert_eq!(spans.len(), 2, "{spans:?}");
+    assert!(spans[0].starts_with("load_config{"), "{spans:?}");
+    assert!(spans[1].starts_with("handle_request{"), "{spans:?}");
+    assert!(spans[1].contains("id=7"), "{spans:?}");
+}
+
+#[test]
+fn test_span_trace_is_omitted_outside_spans() {
+    let subscriber = tracing_subscriber::registry().with(ErrorLayer::default());
+
+    let api_err = tracing::subscriber::with_default(subscriber, || config_report().to_api_error());
+
+    assert!(api_err.span_trace.is_none());
+    let json = serde_json::to_value(&api_err).unwrap();
+    assert!(json.get("span_trace").is_none());
+}
+
+#[tracing::instrument]
+fn load_config(path: &str) -> ApiError {
+    config_report().to_api_error()
+}
+
+#[test]
+fn test_instrumented_fn_appears_under_init_logging() {
+    let _guard = init_logging_with_writer(LogBuffer::default(), "off");
+
+    let api_err = load_config("config.json");
+
+    let spans = api_err.span_trace.expect("load_config was instrumented");
+    assert_eq!(spans.len(), 1, "{spans:?}");
+    assert!(spans[0].starts_with("load_config{"), "{spans:?}");
+    assert!(spans[0].contains("config.json"), "{spans:?}");
+}
@@ -0,0 +1,60 @@
+/*
+ * Integration test for capturing a SpanTrace when a LibReport is created
+ * (`span-trace` feature). The toggle is process-wide, so everything runs
+ * in one test.
+ */
+
+#![cfg(feature = "span-trace")]
+
+mod common;
+
+use common::{TestError, config_report};
+use errors_lib::{LibReport, ReportExt, ReportSpanTrace, set_span_trace_capture};
+use tracing_error::ErrorLayer;
+use tracing_subscriber::prelude::*;
+
+#[tracing::instrument]
+fn load_config(path: &str) -> LibReport<TestError> {
+    parse_config(path.len())
+}
+
+#[tracing::instrument]
+fn parse_config(len: usize) -> LibReport<TestError> {
+    config_report()
+}
+
+#[test]
+fn test_span_trace_is_captured_at_creation() {
+    let subscriber = tracing_subscriber::registry().with(ErrorLayer::default());
+    let _default = tracing::subscriber::set_default(subscriber);
+
+    // Off by default: the spans are gone by the time the ApiError is built.
+    let report = load_config("config.json");
+    assert!(report.get_attachment::<ReportSpanTrace>().is_none());
+    assert!(report.to_api_error().span_trace.is_none());
+
+    set_span_trace_capture(true);
+    let report = load_config("config.json");
+
+    let spans = report
+        .to_api_error()
+        .span_trace
+        .expect("spans were captured");
+    assert_eq!(spans.len(), 2, "{spans:?}");
+    assert!(spans[0].starts_with("parse_config{"), "{spans:?}");
+    assert!(spans[1].starts_with("load_config{"), "{spans:?}");
+    assert!(spans[1].contains("config.json"), "{spans:?}");
+
+    let rendered = report.render_pretty(false);
+    assert!(rendered.contains("span trace:"), "{rendered}");
+    assert!(rendered.contains("parse_config"), "{rendered}");
+    assert!(rendered.contains("load_config"), "{rendered}");
+
+    // Outside any span there is nothing to attach.
+    let report = config_report();
+    assert!(report.get_attachment::<ReportSpanTrace>().is_none());
+    assert!(report.to_api_error().span_trace.is_none());
+    assert!(!report.render_pretty(false).contains("span trace:"));
+
+    set_span_trace_capture(false);
+}
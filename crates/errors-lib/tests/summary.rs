@@ -0,0 +1,58 @@
+/*
+ * Integration tests for one-line report summaries.
+ */
+
+mod common;
+
+use common::{config_report, three_level_report, two_level_report};
+use errors_lib::{DynLibReport, ReportExt, rootcause::Report};
+
+#[test]
+fn test_two_level_summary_format() {
+    assert_eq!(
+        two_level_report().summary(),
+        "Failed to parse config at config.json: Network timeout after 30s \
+         (config::invalid_format) [+3 context]"
+    );
+}
+
+#[test]
+fn test_summary_of_dynamic_and_deeper_trees() {
+    let report = three_level_report();
+    assert_eq!(
+        report.into_dynamic().summary(),
+        three_level_report().summary()
+    );
+    assert_eq!(
+        config_report().summary(),
+        "Failed to parse config at config.json (config::invalid_format) [+2 context]"
+    );
+}
+
+#[test]
+fn test_summary_with_truncates() {
+    let summary = two_level_report().summary();
+
+    assert_eq!(two_level_report().summary_with(summary.len()), summary);
+    let short = two_level_report().summary_with(20);
+    assert_eq!(short, "Failed to parse c…");
+    assert!(short.len() <= 20);
+}
+
+#[test]
+fn test_summary_with_keeps_combining_marks_whole() {
+    // "é" as "e" plus a combining acute accent.
+    let report = DynLibReport::new(Report::new("Cafe\u{301} closed".to_string()).into_dynamic());
+
+    assert!(report.summary().starts_with("Cafe\u{301} closed"));
+    assert_eq!(report.summary_with(8), "Caf…");
+    assert_eq!(report.summary_with(9), "Cafe\u{301}…");
+    assert_eq!(report.summary_with(2), "Ca");
+}
+
+#[test]
+fn test_api_error_carries_summary_only_with_children() {
+    let report = two_level_report();
+    assert_eq!(report.to_api_error().summary, Some(report.summary()));
+    assert_eq!(config_report().to_api_error().summary, None);
+}
@@ -0,0 +1,36 @@
+/*
+ * Integration tests for the thread recorded on ApiError.
+ */
+
+mod common;
+
+use std::thread;
+
+use common::config_report;
+use errors_lib::ReportExt;
+
+#[test]
+fn test_named_thread_is_recorded() {
+    let api_err = thread::Builder::new()
+        .name("ingest-worker".to_string())
+        .spawn(|| config_report().to_api_error())
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert_eq!(api_err.thread_name.as_deref(), Some("ingest-worker"));
+    assert_ne!(api_err.thread_id, format!("{:?}", thread::current().id()));
+}
+
+#[test]
+fn test_unnamed_thread_has_no_name() {
+    let api_err = thread::spawn(|| config_report().to_api_error())
+        .join()
+        .unwrap();
+
+    assert!(api_err.thread_name.is_none());
+    assert!(api_err.thread_id.starts_with("ThreadId("));
+    let json = serde_json::to_value(&api_err).unwrap();
+    assert!(json.get("thread_name").is_none());
+    assert_eq!(json["thread_id"], api_err.thread_id.as_str());
+}
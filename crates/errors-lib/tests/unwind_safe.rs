@@ -0,0 +1,67 @@
+/*
+ * Integration tests for LibReport across catch_unwind.
+ */
+
+mod common;
+
+use std::panic::{AssertUnwindSafe, RefUnwindSafe, UnwindSafe, catch_unwind};
+
+use common::{TestError, config_report};
+use errors_lib::{LibReport, LibResult, ReportExt, miette, rootcause::Report};
+use miette::Diagnostic;
+use snafu::Snafu;
+
+/// A context with no `io::Error` inside, so it is unwind safe.
+#[derive(Debug, Snafu, Diagnostic)]
+#[snafu(display("Cache miss for {key}"))]
+#[diagnostic(code(cache::miss))]
+struct CacheMiss {
+    key: String,
+}
+
+const fn assert_unwind_safe<T: UnwindSafe + RefUnwindSafe>() {}
+
+fn cache_report() -> LibReport<CacheMiss> {
+    Report::new(CacheMiss {
+        key: "user:7".into(),
+    })
+    .into()
+}
+
+#[test]
+fn test_report_is_unwind_safe_when_its_context_is() {
+    assert_unwind_safe::<LibReport<CacheMiss>>();
+    assert_unwind_safe::<LibResult<(), CacheMiss>>();
+}
+
+#[test]
+fn test_result_returned_from_catch_unwind() {
+    let caught = catch_unwind(|| -> LibResult<(), TestError> { Err(config_report()) });
+
+    let report = caught.expect("no panic").unwrap_err();
+    assert_eq!(
+        report.to_api_error().code.as_deref(),
+        Some("config::invalid_format")
+    );
+}
+
+#[test]
+fn test_report_moved_and_borrowed_into_catch_unwind() {
+    let report = cache_report();
+    let title = catch_unwind(|| report.to_api_error().title).expect("no panic");
+    assert_eq!(title, "Cache miss for user:7");
+
+    let result = catch_unwind(move || {
+        let _report = report;
+        panic!("handler failed");
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_io_contexts_need_assert_unwind_safe() {
+    // `io::Error` isn't unwind safe, so neither is a report around one.
+    let report = config_report();
+    let title = catch_unwind(AssertUnwindSafe(|| report.to_api_error().title)).expect("no panic");
+    assert_eq!(title, "Failed to parse config at config.json");
+}
@@ -0,0 +1,106 @@
+/*
+ * Integration tests for ErrorVisitor / LibReport::visit.
+ */
+
+mod common;
+
+use std::io::{self, ErrorKind};
+
+use common::{TestError, config_parse_error, io_report, two_level_report};
+use errors_lib::{ErrorNode, ErrorVisitor, LibReport, rootcause::Report};
+
+/// Collects every `io::ErrorKind` in the tree, whether an `io::Error` is a
+/// node's context or the source of a typed context.
+#[derive(Default)]
+struct IoKinds(Vec<ErrorKind>);
+
+impl ErrorVisitor for IoKinds {
+    fn visit_node(&mut self, node: &ErrorNode<'_>) {
+        if let Some(io_err) = node.find_error::<io::Error>() {
+            self.0.push(io_err.kind());
+        }
+    }
+}
+
+/// Records the callbacks in the order they arrive.
+#[derive(Default)]
+struct Trace(Vec<String>);
+
+impl ErrorVisitor for Trace {
+    fn visit_node(&mut self, node: &ErrorNode<'_>) {
+        self.0.push(format!("node: {node}"));
+    }
+
+    fn visit_attachment(&mut self, _node: &ErrorNode<'_>, attachment: &str) {
+        self.0.push(format!("attachment: {attachment}"));
+    }
+}
+
+fn mixed_report() -> LibReport<TestError> {
+    let denied: TestError = io::Error::new(ErrorKind::PermissionDenied, "denied").into();
+    Report::new(io::Error::new(ErrorKind::TimedOut, "connection reset"))
+        .context(TestError::NetworkTimeout {
+            timeout: 30,
+        })
+        .context(denied)
+        .context(config_parse_error())
+        .into()
+}
+
+#[test]
+fn test_visitor_collects_io_kinds_from_contexts_and_sources() {
+    let mut kinds = IoKinds::default();
+    mixed_report().visit(&mut kinds);
+    assert_eq!(kinds.0, [ErrorKind::PermissionDenied, ErrorKind::TimedOut]);
+}
+
+#[test]
+fn test_visit_is_depth_first_with_attachments_after_their_node() {
+    let mut trace = Trace::default();
+    two_level_report().visit(&mut trace);
+    assert_eq!(trace.0[0], "node: Failed to parse config at config.json");
+    let child = trace
+        .0
+        .iter()
+        .position(|entry| entry == "node: Network timeout after 30s")
+        .expect("child node visited");
+    let attachment = trace
+        .0
+        .iter()
+        .position(|entry| entry == "attachment: while fetching remote config")
+        .expect("child attachment visited");
+    assert!(child < attachment, "{:#?}", trace.0);
+}
+
+#[test]
+fn test_typed_context_downcasts() {
+    struct Codes(Vec<bool>);
+
+    impl ErrorVisitor for Codes {
+        fn visit_node(&mut self, node: &ErrorNode<'_>) {
+            self.0.push(node.context::<TestError>().is_some());
+        }
+    }
+
+    let mut codes = Codes(Vec::new());
+    mixed_report().visit(&mut codes);
+    assert_eq!(codes.0, [true, true, true, false]);
+}
+
+#[test]
+fn test_node_sources_reach_a_wrapped_io_error() {
+    #[derive(Default)]
+    struct Sources(Vec<String>);
+
+    impl ErrorVisitor for Sources {
+        fn visit_node(&mut self, node: &ErrorNode<'_>) {
+            self.0.extend(node.sources().map(ToString::to_string));
+        }
+    }
+
+    let report = io_report(ErrorKind::NotFound).change_context(config_parse_error());
+
+    let mut sources = Sources::default();
+    report.visit(&mut sources);
+    assert_eq!(sources.0, ["config.json is unavailable"]);
+}
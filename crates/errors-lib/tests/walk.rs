@@ -0,0 +1,126 @@
+/*
+ * Integration tests for iter_nodes / iter_contexts /
+ * iter_attachment_strings.
+ */
+
+mod common;
+
+use common::{TestError, config_report, three_level_report, two_level_report};
+use errors_lib::{LibReport, MultiError, NodeContext, ReportExt};
+
+/// multi -> [config -> network, config]: the orders differ here.
+fn branching_report() -> LibReport<MultiError<TestError>> {
+    LibReport::join(vec![two_level_report(), config_report()]).expect("two reports")
+}
+
+fn depths_and_messages<E>(report: &LibReport<E>, breadth_first: bool) -> Vec<(usize, String)>
+where
+    E: miette::Diagnostic + std::fmt::Display + std::fmt::Debug + Send + Sync + 'static,
+{
+    let nodes = report.iter_nodes();
+    let nodes = if breadth_first {
+        nodes.breadth_first()
+    } else {
+        nodes
+    };
+    nodes.map(|node| (node.depth, node.message)).collect()
+}
+
+#[test]
+fn test_three_level_tree_is_walked_root_to_leaf() {
+    let nodes: Vec<_> = three_level_report().iter_nodes().collect();
+
+    let summary: Vec<_> = nodes
+        .iter()
+        .map(|node| (node.depth, node.code.as_deref(), node.message.as_str()))
+        .collect();
+    assert_eq!(summary, [
+        (
+            0,
+            Some("config::invalid_format"),
+            "Failed to parse config at config.json"
+        ),
+        (1, Some("network::timeout"), "Network timeout after 30s"),
+        (2, None, "connection reset"),
+    ]);
+    assert_eq!(
+        depths_and_messages(&three_level_report(), true),
+        depths_and_messages(&three_level_report(), false)
+    );
+}
+
+#[test]
+fn test_depth_first_and_breadth_first_orders() {
+    let report = branching_report();
+    let config = "Failed to parse config at config.json".to_string();
+    let network = "Network timeout after 30s".to_string();
+    let multi = report.iter_nodes().next().unwrap().message;
+
+    assert_eq!(depths_and_messages(&report, false), [
+        (0, multi.clone()),
+        (1, config.clone()),
+        (2, network.clone()),
+        (1, config.clone()),
+    ]);
+    assert_eq!(depths_and_messages(&report, true), [
+        (0, multi),
+        (1, config.clone()),
+        (1, config),
+        (2, network),
+    ]);
+}
+
+#[test]
+fn test_iter_contexts_separates_typed_nodes() {
+    let report = three_level_report();
+    let contexts: Vec<_> = report.iter_contexts().collect();
+    let depths: Vec<_> = contexts.iter().map(|(depth, _)| *depth).collect();
+    assert_eq!(depths, [0, 1, 2]);
+
+    assert!(matches!(
+        contexts[1].1,
+        NodeContext::Typed(TestError::NetworkTimeout {
+            timeout: 30
+        })
+    ));
+    let NodeContext::Other(io_node) = &contexts[2].1 else {
+        panic!("the io root is not a TestError");
+    };
+    assert!(io_node.context::<std::io::Error>().is_some());
+    assert_eq!(contexts[2].1.to_string(), "connection reset");
+}
+
+#[test]
+fn test_attachment_strings_follow_the_walk() {
+    let report = two_level_report();
+    let strings: Vec<_> = report.iter_attachment_strings().collect();
+    assert!(strings.contains(&"while fetching remote config".to_string()));
+
+    // History is the same walk with each child's context message added.
+    let history: Vec<_> = report
+        .to_api_error()
+        .history
+        .into_iter()
+        .map(|frame| frame.message)
+        .filter(|message| message != "Network timeout after 30s")
+        .collect();
+    assert_eq!(history, strings);
+}
+
+#[test]
+fn test_attachment_count_includes_typed_payloads() {
+    let bare = config_report()
+        .iter_nodes()
+        .next()
+        .unwrap()
+        .attachment_count;
+    let report = config_report().attach_typed(7_u32);
+    assert_eq!(
+        report.iter_nodes().next().unwrap().attachment_count,
+        bare + 1
+    );
+    assert_eq!(
+        report.iter_attachment_strings().count(),
+        config_report().iter_attachment_strings().count()
+    );
+}
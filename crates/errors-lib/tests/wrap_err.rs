@@ -0,0 +1,53 @@
+/*
+ * Integration tests for LibResultExt::wrap_err / with_wrap_err.
+ */
+
+mod common;
+
+use std::cell::Cell;
+
+use common::{TestError, config_report};
+use errors_lib::{LibResult, LibResultExt, ReportExt};
+
+fn load_config(fail: bool) -> LibResult<u32, TestError> {
+    if fail { Err(config_report()) } else { Ok(42) }
+}
+
+fn history_of(result: LibResult<u32, TestError>) -> Vec<String> {
+    result
+        .unwrap_err()
+        .to_api_error()
+        .history
+        .into_iter()
+        .map(|frame| frame.message)
+        .collect()
+}
+
+#[test]
+fn test_wrap_err_attaches_message_on_failure() {
+    let history = history_of(load_config(true).wrap_err("loading config"));
+    assert!(history.contains(&"loading config".to_string()));
+}
+
+#[test]
+fn test_with_wrap_err_attaches_message_on_failure() {
+    let path = "config.json";
+    let history = history_of(load_config(true).with_wrap_err(|| format!("loading {path}")));
+    assert!(history.contains(&"loading config.json".to_string()));
+}
+
+#[test]
+fn test_wrap_err_passes_ok_through() {
+    assert_eq!(load_config(false).wrap_err("loading config").unwrap(), 42);
+}
+
+#[test]
+fn test_with_wrap_err_is_lazy_on_success() {
+    let called = Cell::new(false);
+    let result = load_config(false).with_wrap_err(|| {
+        called.set(true);
+        "loading config"
+    });
+    assert_eq!(result.unwrap(), 42);
+    assert!(!called.get());
+}
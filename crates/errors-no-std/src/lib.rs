@@ -0,0 +1,76 @@
+/*
+ * no_std build check for the errors-lib data model.
+ *
+ * Built on its own (`cargo build -p errors-no-std`), errors-lib gets only
+ * its `alloc` and `heapless` features, so a failure here means ApiError,
+ * ErrorFrame, ErrorTree or FixedApiError picked up a std dependency. In a
+ * workspace-wide build the CLI turns `std` back on, so run it by itself.
+ */
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{borrow::Cow, collections::BTreeMap, string::String, vec, vec::Vec};
+
+use errors_lib::{ApiError, ErrorCode, ErrorFrame, ErrorTree, FixedApiError};
+use serde::Serialize;
+
+const fn assert_serialize<T: Serialize>() {}
+
+const _: () = {
+    assert_serialize::<ApiError>();
+    assert_serialize::<ErrorFrame>();
+    assert_serialize::<ErrorTree>();
+    assert_serialize::<FixedApiError>();
+};
+
+/// An `ApiError` built by hand, as a `no_std` consumer would without
+/// `to_api_error`.
+#[must_use]
+pub fn sample_api_error() -> ApiError {
+    ApiError {
+        git_hash: Cow::Borrowed("unknown"),
+        git_branch: Cow::Borrowed("unknown"),
+        git_dirty: false,
+        build_timestamp: Cow::Borrowed("0"),
+        rustc_version: Cow::Borrowed("unknown"),
+        target: Cow::Borrowed("thumbv7em-none-eabihf"),
+        docs_url: Cow::Borrowed("https://docs.rs/errors-lib"),
+        code_url: None,
+        correlation_id: String::from("fw000001"),
+        title: String::from("Sensor read failed"),
+        summary: None,
+        code: ErrorCode::new("sensor::read").ok(),
+        domain: Some(String::from("sensor")),
+        help: None,
+        severity: None,
+        location: None,
+        thread_name: None,
+        thread_id: String::new(),
+        fields: BTreeMap::new(),
+        extra: BTreeMap::new(),
+        retryable: true,
+        retry_after_ms: Some(100),
+        max_retries: Some(3),
+        history: vec![ErrorFrame {
+            message: String::from("i2c bus timeout"),
+        }],
+        span_trace: None,
+        trace_id: None,
+        span_id: None,
+        tree: Some(ErrorTree::Leaf {
+            message: String::from("Sensor read failed"),
+            code: Some(String::from("sensor::read")),
+            attachments: Vec::new(),
+        }),
+    }
+}
+
+/// Encodes a `FixedApiError` into `buf` without allocating, returning the
+/// length written, or `None` when `buf` is too small.
+pub fn encode_fixed(buf: &mut [u8]) -> Option<usize> {
+    let mut err: FixedApiError = FixedApiError::new("Sensor read failed").with_code("sensor::read");
+    err.push_history("i2c bus timeout");
+    err.to_json(buf).ok()
+}